@@ -24,4 +24,64 @@ SELECT
     MAX(timestamp) as last_message_time,
     MIN(timestamp) as first_message_time
 FROM messages
-GROUP BY topic;
\ No newline at end of file
+GROUP BY topic;
+
+-- Named filter presets (topic/payload regex, time range, quick filter states)
+CREATE TABLE IF NOT EXISTS filter_presets (
+    name TEXT PRIMARY KEY,
+    topic_regex TEXT NOT NULL DEFAULT '',
+    payload_regex TEXT NOT NULL DEFAULT '',
+    start_time TEXT NOT NULL DEFAULT '',
+    end_time TEXT NOT NULL DEFAULT '',
+    quick_filters TEXT NOT NULL DEFAULT '{}',
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Message list JSON columns per topic pattern
+CREATE TABLE IF NOT EXISTS message_columns (
+    topic_pattern TEXT PRIMARY KEY,
+    columns TEXT NOT NULL DEFAULT '[]',
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Alerts fired by [[alerts]] rules
+CREATE TABLE IF NOT EXISTS alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    message_id INTEGER,
+    fired_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_alerts_fired_at ON alerts(fired_at);
+
+-- Bookmarked messages with an optional note
+CREATE TABLE IF NOT EXISTS bookmarks (
+    message_id INTEGER PRIMARY KEY,
+    note TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- Deleted messages kept for undo/restore until purged or expired by retention
+CREATE TABLE IF NOT EXISTS trash (
+    id INTEGER PRIMARY KEY,
+    batch TEXT NOT NULL,
+    deleted_at TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    timestamp DATETIME,
+    qos INTEGER DEFAULT 0,
+    retain BOOLEAN DEFAULT 0,
+    backlog BOOLEAN DEFAULT 0,
+    encoding TEXT,
+    marker TEXT,
+    level TEXT,
+    raw_payload TEXT,
+    created_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_trash_batch ON trash(batch);
+CREATE INDEX IF NOT EXISTS idx_trash_deleted_at ON trash(deleted_at);
@@ -0,0 +1,112 @@
+use std::process::Stdio;
+
+use rumqttc::{AsyncClient, QoS};
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use crate::db::{Alert, MessageRepository};
+use super::engine::FiredAlert;
+
+/// 執行告警規則設定的動作：寫入資料表、通知畫面、執行指令、發佈到 MQTT
+#[derive(Clone)]
+pub struct AlertDispatcher {
+    repository: MessageRepository,
+    publisher: Option<AsyncClient>,
+    notifier: Option<mpsc::UnboundedSender<Alert>>, // 畫面的提示音與狀態列橫幅
+}
+
+impl AlertDispatcher {
+    pub fn new(
+        repository: MessageRepository,
+        publisher: Option<AsyncClient>,
+        notifier: Option<mpsc::UnboundedSender<Alert>>,
+    ) -> Self {
+        Self {
+            repository,
+            publisher,
+            notifier,
+        }
+    }
+
+    pub async fn dispatch(&self, fired: FiredAlert) {
+        let FiredAlert { rule, mut alert, payload } = fired;
+        warn!("Alert '{}' fired on {}: {}", alert.rule, alert.topic, alert.description);
+
+        if rule.store {
+            match self.repository.insert_alert(&alert).await {
+                Ok(id) => alert.id = Some(id),
+                Err(e) => error!("Failed to store alert '{}': {}", alert.rule, e),
+            }
+        }
+
+        if rule.notify {
+            if let Some(notifier) = &self.notifier {
+                // 畫面可能已經關閉，送不出去時直接忽略
+                let _ = notifier.send(alert.clone());
+            }
+        }
+
+        if let Some(command) = &rule.command {
+            Self::run_command(command, &alert, payload.as_deref());
+        }
+
+        if let Some(topic) = &rule.publish {
+            let Some(publisher) = &self.publisher else {
+                return;
+            };
+            let body = serde_json::json!({
+                "rule": alert.rule,
+                "kind": alert.kind.as_str(),
+                "topic": alert.topic,
+                "description": alert.description,
+                "message_id": alert.message_id,
+                "payload": payload,
+                "fired_at": alert.fired_at.to_rfc3339(),
+            });
+            // 不等待送出佇列，避免 eventloop 塞住時連帶卡住訊息寫入
+            if let Err(e) = publisher.try_publish(topic.as_str(), QoS::AtLeastOnce, false, body.to_string()) {
+                error!("Failed to publish alert '{}' to {}: {}", alert.rule, topic, e);
+            }
+        }
+    }
+
+    // 不等待指令結束；輸出丟棄以免破壞畫面
+    fn run_command(command: &str, alert: &Alert, payload: Option<&str>) {
+        #[cfg(windows)]
+        let mut process = {
+            let mut process = tokio::process::Command::new("cmd");
+            process.arg("/C").arg(command);
+            process
+        };
+        #[cfg(not(windows))]
+        let mut process = {
+            let mut process = tokio::process::Command::new("sh");
+            process.arg("-c").arg(command);
+            process
+        };
+        process
+            .env("ALERT_RULE", &alert.rule)
+            .env("ALERT_KIND", alert.kind.as_str())
+            .env("ALERT_TOPIC", &alert.topic)
+            .env("ALERT_DESCRIPTION", &alert.description)
+            .env("ALERT_PAYLOAD", payload.unwrap_or(""))
+            .env("ALERT_TIME", alert.fired_at.to_rfc3339())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        match process.spawn() {
+            Ok(mut child) => {
+                let rule = alert.rule.clone();
+                tokio::spawn(async move {
+                    match child.wait().await {
+                        Ok(status) if status.success() => info!("Alert command for '{}' finished", rule),
+                        Ok(status) => warn!("Alert command for '{}' exited with {}", rule, status),
+                        Err(e) => error!("Alert command for '{}' failed: {}", rule, e),
+                    }
+                });
+            }
+            Err(e) => error!("Failed to run alert command for '{}': {}", alert.rule, e),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, Local, Utc};
+
+use crate::config::AlertRule;
+use crate::db::{Alert, AlertKind, Message};
+use crate::filter::{format_silence, parse_duration, Query};
+use crate::utils::topic_pattern::topic_matches;
+
+// 描述中 payload 的最大字元數
+const PAYLOAD_PREVIEW_CHARS: usize = 80;
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    config: AlertRule,
+    condition: Option<Query>,
+    max_rate: Option<(f64, Duration)>, // (每秒訊息數上限, 時間窗)
+    silence: Option<Duration>,
+    cooldown: Duration,
+}
+
+impl CompiledRule {
+    fn compile(rule: &AlertRule) -> Result<Self> {
+        let context = |e: anyhow::Error| anyhow!("Alert rule '{}': {}", rule.name, e);
+        let condition = rule.condition.as_deref().map(Query::parse).transpose().map_err(context)?;
+        let max_rate = match rule.max_rate {
+            Some(rate) => {
+                let window = parse_duration(&rule.rate_window).map_err(context)?;
+                if window < Duration::seconds(1) {
+                    bail!("Alert rule '{}': rate_window must be at least 1s", rule.name);
+                }
+                Some((rate, window))
+            }
+            None => None,
+        };
+        let silence = rule.silence.as_deref().map(parse_duration).transpose().map_err(context)?;
+        if max_rate.is_some() && silence.is_some() {
+            bail!("Alert rule '{}' must not set both max_rate and silence", rule.name);
+        }
+        if condition.is_none() && max_rate.is_none() && silence.is_none() {
+            bail!("Alert rule '{}' must set condition, max_rate or silence", rule.name);
+        }
+        Ok(Self {
+            config: rule.clone(),
+            condition,
+            max_rate,
+            silence,
+            cooldown: parse_duration(&rule.cooldown).map_err(context)?,
+        })
+    }
+
+    fn matches(&self, message: &Message) -> bool {
+        topic_matches(&self.config.topic, &message.topic)
+            && self.condition.as_ref().is_none_or(|query| query.matches(message, Local::now()))
+    }
+}
+
+// 每條規則在每個 topic 上的狀態
+#[derive(Debug, Clone, Default)]
+struct TopicState {
+    recent: VecDeque<DateTime<Utc>>, // 速率時間窗內的訊息時間
+    last_seen: Option<DateTime<Utc>>,
+    last_message_id: Option<i64>,
+    last_fired: Option<DateTime<Utc>>,
+    silence_fired: bool, // 這段沉寂已經告警過
+}
+
+/// 觸發的告警與其規則 (決定要執行的動作)
+#[derive(Debug, Clone)]
+pub struct FiredAlert {
+    pub rule: AlertRule,
+    pub alert: Alert,
+    pub payload: Option<String>, // 觸發的訊息內容，silence 時為 None
+}
+
+/// 在寫入訊息的路徑上評估告警規則
+#[derive(Debug, Clone, Default)]
+pub struct AlertEngine {
+    rules: Vec<CompiledRule>,
+    states: HashMap<(usize, String), TopicState>,
+}
+
+impl AlertEngine {
+    pub fn from_config(rules: &[AlertRule]) -> Result<Self> {
+        let rules = rules.iter().map(CompiledRule::compile).collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            rules,
+            states: HashMap::new(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn has_silence_rules(&self) -> bool {
+        self.rules.iter().any(|rule| rule.silence.is_some())
+    }
+
+    /// 以資料庫中各 topic 的最後訊息時間初始化 silence 規則，啟動前就已沉寂的 topic 也會告警
+    pub fn seed_last_seen(&mut self, last_seen: &HashMap<String, DateTime<Utc>>) {
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.silence.is_none() {
+                continue;
+            }
+            for (topic, time) in last_seen.iter().filter(|(topic, _)| topic_matches(&rule.config.topic, topic)) {
+                let state = self.states.entry((index, topic.clone())).or_default();
+                state.last_seen = Some(state.last_seen.map_or(*time, |seen| seen.max(*time)));
+            }
+        }
+    }
+
+    pub fn on_message(&mut self, message: &Message) -> Vec<FiredAlert> {
+        // 告警發佈到的 topic 不再評估，避免告警觸發告警
+        if self.rules.iter().any(|rule| rule.config.publish.as_deref() == Some(message.topic.as_str())) {
+            return Vec::new();
+        }
+
+        let mut fired = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            if !rule.matches(message) {
+                continue;
+            }
+            let now = message.timestamp;
+            let state = self.states.entry((index, message.topic.clone())).or_default();
+            state.last_seen = Some(now);
+            state.last_message_id = message.id;
+            state.silence_fired = false;
+            if rule.silence.is_some() {
+                continue;
+            }
+
+            let description = match rule.max_rate {
+                Some((max_rate, window)) => {
+                    state.recent.push_back(now);
+                    while state.recent.front().is_some_and(|time| *time <= now - window) {
+                        state.recent.pop_front();
+                    }
+                    let rate = state.recent.len() as f64 * 1000.0 / window.num_milliseconds() as f64;
+                    if rate <= max_rate {
+                        continue;
+                    }
+                    format!("{:.2} msg/s over {} (max {})", rate, rule.config.rate_window, max_rate)
+                }
+                None => format!("{}: {}", rule.config.condition.as_deref().unwrap_or(""), preview(&message.payload)),
+            };
+            if state.last_fired.is_some_and(|last| now - last < rule.cooldown) {
+                continue;
+            }
+            state.last_fired = Some(now);
+            let kind = if rule.max_rate.is_some() { AlertKind::Rate } else { AlertKind::Condition };
+            fired.push(FiredAlert {
+                rule: rule.config.clone(),
+                alert: Alert {
+                    id: None,
+                    rule: rule.config.name.clone(),
+                    kind,
+                    topic: message.topic.clone(),
+                    description,
+                    message_id: message.id,
+                    fired_at: now,
+                },
+                payload: Some(message.payload.clone()),
+            });
+        }
+        fired
+    }
+
+    /// 檢查 silence 規則，每段沉寂只告警一次，直到再收到訊息
+    pub fn check_silence(&mut self, now: DateTime<Utc>) -> Vec<FiredAlert> {
+        let mut fired = Vec::new();
+        for ((index, topic), state) in self.states.iter_mut() {
+            let rule = &self.rules[*index];
+            let (Some(silence), Some(last_seen)) = (rule.silence, state.last_seen) else {
+                continue;
+            };
+            let silent_for = now - last_seen;
+            if state.silence_fired || silent_for <= silence {
+                continue;
+            }
+            state.silence_fired = true;
+            fired.push(FiredAlert {
+                rule: rule.config.clone(),
+                alert: Alert {
+                    id: None,
+                    rule: rule.config.name.clone(),
+                    kind: AlertKind::Silence,
+                    topic: topic.clone(),
+                    description: format!("No message for {} (expected within {})", format_silence(silent_for), format_silence(silence)),
+                    message_id: state.last_message_id,
+                    fired_at: now,
+                },
+                payload: None,
+            });
+        }
+        fired.sort_by(|a, b| a.alert.topic.cmp(&b.alert.topic));
+        fired
+    }
+}
+
+fn preview(payload: &str) -> String {
+    let flat = payload.replace(['\n', '\r'], " ");
+    if flat.chars().count() > PAYLOAD_PREVIEW_CHARS {
+        format!("{}...", flat.chars().take(PAYLOAD_PREVIEW_CHARS).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, topic: &str) -> AlertRule {
+        AlertRule {
+            name: name.to_string(),
+            topic: topic.to_string(),
+            condition: None,
+            max_rate: None,
+            rate_window: "10s".to_string(),
+            silence: None,
+            cooldown: "1m".to_string(),
+            notify: true,
+            store: true,
+            command: None,
+            publish: Some("alerts/out".to_string()),
+        }
+    }
+
+    fn message(topic: &str, payload: &str, timestamp: DateTime<Utc>) -> Message {
+        let mut message = Message::new(topic.to_string(), payload.to_string(), 0, false);
+        message.timestamp = timestamp;
+        message
+    }
+
+    #[test]
+    fn test_alert_rules() {
+        let mut hot = rule("hot", "#");
+        hot.condition = Some("$.value > 80".to_string());
+        let mut flood = rule("flood", "app/#");
+        flood.max_rate = Some(0.5);
+        let mut dead = rule("dead", "devices/+/status");
+        dead.silence = Some("5m".to_string());
+        let mut engine = AlertEngine::from_config(&[hot, flood, dead]).unwrap();
+
+        // condition：冷卻時間內只觸發一次
+        let start = Utc::now();
+        assert!(engine.on_message(&message("sensors/a/temp", r#"{"value": 20}"#, start)).is_empty());
+        let fired = engine.on_message(&message("sensors/a/temp", r#"{"value": 95}"#, start));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].alert.kind, AlertKind::Condition);
+        assert!(engine.on_message(&message("sensors/a/temp", r#"{"value": 96}"#, start + Duration::seconds(30))).is_empty());
+        assert_eq!(engine.on_message(&message("sensors/a/temp", r#"{"value": 97}"#, start + Duration::seconds(61))).len(), 1);
+
+        // rate：10 秒內第 6 筆超過 0.5 msg/s
+        let fired: Vec<_> = (0..6)
+            .flat_map(|i| engine.on_message(&message("app/log", "x", start + Duration::seconds(i))))
+            .collect();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].alert.kind, AlertKind::Rate);
+
+        // silence：資料庫中的最後訊息也算，沉寂後只告警一次，收到訊息後重新計算
+        let last_seen = [("devices/a/status".to_string(), start - Duration::minutes(10))].into_iter().collect();
+        engine.seed_last_seen(&last_seen);
+        engine.on_message(&message("devices/b/status", "online", start));
+        let fired = engine.check_silence(start);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].alert.topic, "devices/a/status");
+        assert!(engine.check_silence(start + Duration::minutes(1)).is_empty());
+        assert_eq!(engine.check_silence(start + Duration::minutes(6)).len(), 1);
+
+        // 發佈告警的 topic 不評估
+        assert!(engine.on_message(&message("alerts/out", r#"{"value": 99}"#, start)).is_empty());
+
+        assert!(AlertEngine::from_config(&[rule("empty", "#")]).is_err());
+        let mut zero_window = rule("zero window", "#");
+        zero_window.max_rate = Some(1.0);
+        zero_window.rate_window = "0s".to_string();
+        assert!(AlertEngine::from_config(&[zero_window]).is_err());
+        assert!(AlertEngine::from_config(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod dispatcher;
+pub mod engine;
+
+pub use dispatcher::*;
+pub use engine::*;
@@ -0,0 +1,107 @@
+use anyhow::{bail, Result};
+
+pub const USAGE: &str = "\
+Usage: mqtt_log_view [OPTIONS] [DATABASE]
+
+Options:
+  -o, --open <DATABASE>   Browse a database file read-only without connecting to MQTT
+  -q, --query <EXPR>      Print messages matching a filter query and exit (no UI)
+  -n, --limit <N>         With --query, print at most the newest N messages
+  -h, --help              Print this help
+
+A positional DATABASE argument is the same as --open.
+--query reads the --open database, or database.path from config.toml.";
+
+/// 命令列參數
+#[derive(Debug, Default, Clone)]
+pub struct CliArgs {
+    pub open: Option<String>,
+    pub query: Option<String>,
+    pub limit: Option<usize>,
+    pub help: bool,
+}
+
+impl CliArgs {
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut cli = CliArgs::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => cli.help = true,
+                "-o" | "--open" => match args.next() {
+                    Some(path) => cli.set_open(path)?,
+                    None => bail!("{} requires a database path", arg),
+                },
+                _ if arg.starts_with("--open=") => cli.set_open(arg["--open=".len()..].to_string())?,
+                "-q" | "--query" => match args.next() {
+                    Some(query) => cli.query = Some(query),
+                    None => bail!("{} requires a query expression", arg),
+                },
+                _ if arg.starts_with("--query=") => cli.query = Some(arg["--query=".len()..].to_string()),
+                "-n" | "--limit" => match args.next() {
+                    Some(limit) => cli.set_limit(&limit)?,
+                    None => bail!("{} requires a number", arg),
+                },
+                _ if arg.starts_with("--limit=") => cli.set_limit(&arg["--limit=".len()..])?,
+                _ if arg.starts_with('-') => bail!("Unknown option: {}\n\n{}", arg, USAGE),
+                _ => cli.set_open(arg)?,
+            }
+        }
+
+        if cli.limit.is_some() && cli.query.is_none() {
+            bail!("--limit can only be used with --query");
+        }
+
+        Ok(cli)
+    }
+
+    fn set_limit(&mut self, value: &str) -> Result<()> {
+        match value.parse() {
+            Ok(limit) => self.limit = Some(limit),
+            Err(_) => bail!("Invalid --limit value: {}", value),
+        }
+        Ok(())
+    }
+
+    fn set_open(&mut self, path: String) -> Result<()> {
+        if self.open.is_some() {
+            bail!("Only one database file can be opened");
+        }
+        self.open = Some(path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs> {
+        CliArgs::parse_from(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_open() {
+        assert_eq!(parse(&["--open", "a.db"]).unwrap().open.as_deref(), Some("a.db"));
+        assert_eq!(parse(&["--open=a.db"]).unwrap().open.as_deref(), Some("a.db"));
+        assert_eq!(parse(&["a.db"]).unwrap().open.as_deref(), Some("a.db"));
+        assert!(parse(&[]).unwrap().open.is_none());
+        assert!(parse(&["--open"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let cli = parse(&["a.db", "--query", "topic:~^plant AND NOT retain", "-n", "5"]).unwrap();
+        assert_eq!(cli.query.as_deref(), Some("topic:~^plant AND NOT retain"));
+        assert_eq!(cli.limit, Some(5));
+        assert_eq!(parse(&["--query=retain"]).unwrap().query.as_deref(), Some("retain"));
+        assert!(parse(&["--limit", "5"]).is_err());
+        assert!(parse(&["--query", "retain", "--limit", "x"]).is_err());
+    }
+}
@@ -2,6 +2,10 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use anyhow::Result;
 
+use crate::filter::FilterPreset;
+use crate::ui::{ColumnSet, TopicColumnConfig};
+use crate::ui::views::TopicSortKey;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
     pub mqtt: MqttConfig,
@@ -9,6 +13,27 @@ pub struct Config {
     pub ui: UiConfig,
     pub performance: PerformanceConfig,
     pub quick_filters: QuickFiltersConfig,
+    #[serde(default)]
+    pub decoders: DecodersConfig,
+    #[serde(default)]
+    pub severity: SeverityConfig,
+    // 設定檔中共用的過濾 preset (唯讀)，另可在程式內存到資料庫
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<FilterPreset>,
+    // 訊息清單依 topic 顯示的 JSON 欄位；在程式內修改的版本存在資料庫並優先使用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub message_columns: Vec<ColumnSet>,
+    #[serde(default)]
+    pub topic_list: TopicListConfig,
+    // 預期固定發佈的 topic，超過間隔未收到訊息即視為沉寂
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stale_topics: Vec<StaleTopicRule>,
+    // 收到訊息時評估的告警規則
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<AlertRule>,
+    // 不顯示或不儲存的吵雜 topic，在 topic 清單調整時寫回設定檔
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mutes: Vec<MuteRule>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -18,6 +43,36 @@ pub struct MqttConfig {
     pub username: Option<String>,
     pub password: Option<String>,
     pub client_id: String,
+    // false = 持久 session，broker 會保留離線期間的 QoS 1/2 訊息 (client_id 必須固定)
+    #[serde(default = "default_clean_session")]
+    pub clean_session: bool,
+    // 訂閱 "#" 使用的 QoS (0-2)
+    #[serde(default)]
+    pub subscribe_qos: u8,
+    #[serde(default)]
+    pub reconnect: ReconnectConfig,
+}
+
+fn default_clean_session() -> bool {
+    true
+}
+
+/// 斷線重連的指數退避設定
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReconnectConfig {
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub multiplier: f64,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay_ms: 1000,
+            max_delay_ms: 60_000,
+            multiplier: 2.0,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,6 +81,19 @@ pub struct DatabaseConfig {
     pub max_messages: u64,
     pub auto_cleanup: bool,
     pub cleanup_days: u32,
+    // SQLite 遇到鎖定時的等待時間
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u64,
+}
+
+fn default_busy_timeout_ms() -> u64 {
+    5000
+}
+
+impl DatabaseConfig {
+    pub fn busy_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.busy_timeout_ms)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -36,11 +104,69 @@ pub struct UiConfig {
     pub enable_json_highlight: bool,
 }
 
+/// Topic 清單的排序與顯示欄位，在程式內調整時寫回設定檔
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TopicListConfig {
+    #[serde(default)]
+    pub sort: TopicSortKey,
+    #[serde(default = "default_sort_descending")]
+    pub descending: bool,
+    // 釘選的 topic，永遠排在清單最上方
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned: Vec<String>,
+    #[serde(default = "TopicColumnConfig::defaults")]
+    pub columns: Vec<TopicColumnConfig>,
+}
+
+fn default_sort_descending() -> bool {
+    true
+}
+
+impl Default for TopicListConfig {
+    fn default() -> Self {
+        Self {
+            sort: TopicSortKey::default(),
+            descending: default_sort_descending(),
+            pinned: Vec::new(),
+            columns: TopicColumnConfig::defaults(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PerformanceConfig {
     pub max_memory_mb: u64,
     pub cache_size: usize,
     pub batch_size: usize,
+    // MQTT -> SQLite 之間的待寫入佇列上限 (筆數)，位元組上限取 max_memory_mb
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    #[serde(default)]
+    pub overflow_policy: OverflowPolicy,
+    // MQTT eventloop 與事件處理之間的 channel 容量，滿了時 eventloop 暫停讀取
+    #[serde(default = "default_event_channel_capacity")]
+    pub event_channel_capacity: usize,
+}
+
+fn default_queue_capacity() -> usize {
+    10_000
+}
+
+fn default_event_channel_capacity() -> usize {
+    1_000
+}
+
+/// 佇列滿時的處理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// 暫停讀取 MQTT，讓 broker/TCP 承受壓力
+    Block,
+    /// 丟棄最舊的待寫入訊息
+    #[default]
+    DropOldest,
+    /// 丟棄新進來的訊息
+    DropNewest,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -52,11 +178,203 @@ pub struct QuickFiltersConfig {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct QuickFilter {
     pub name: String,
+    #[serde(default)]
     pub pattern: String,
+    // 依擷取出的日誌等級比對 (TRACE/DEBUG/INFO/WARN/ERROR/FATAL)，設定時忽略 pattern
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(default = "default_quick_filter_color")]
     pub color: String,
+    // F1-F12、Alt+<key>、Ctrl+<key>，空字串表示沒有快捷鍵
+    #[serde(default)]
     pub hotkey: String,
+    #[serde(default = "default_quick_filter_enabled")]
     pub enabled: bool,
+    #[serde(default)]
     pub case_sensitive: bool,
+    // true 時 pattern 為正規表示式，否則為子字串比對
+    #[serde(default)]
+    pub regex: bool,
+    // 在 Topic 清單顯示此過濾器的訊息數欄位
+    #[serde(default = "default_quick_filter_show_count")]
+    pub show_count: bool,
+}
+
+fn default_quick_filter_color() -> String {
+    "white".to_string()
+}
+
+fn default_quick_filter_enabled() -> bool {
+    true
+}
+
+fn default_quick_filter_show_count() -> bool {
+    true
+}
+
+/// 非 JSON payload 的解碼設定
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DecodersConfig {
+    // 自動以 Sparkplug B 解碼 spBv1.0/# (mappings 中符合的設定優先)
+    #[serde(default = "default_sparkplug")]
+    pub sparkplug: bool,
+    // .proto 檔或 descriptor set (protoc --descriptor_set_out) 檔
+    #[serde(default)]
+    pub proto_files: Vec<String>,
+    // .proto import 的搜尋路徑 (檔案所在目錄會自動加入)
+    #[serde(default)]
+    pub proto_include_paths: Vec<String>,
+    #[serde(default)]
+    pub mappings: Vec<DecoderMapping>,
+}
+
+fn default_sparkplug() -> bool {
+    true
+}
+
+impl Default for DecodersConfig {
+    fn default() -> Self {
+        Self {
+            sparkplug: default_sparkplug(),
+            proto_files: Vec::new(),
+            proto_include_paths: Vec::new(),
+            mappings: Vec::new(),
+        }
+    }
+}
+
+/// 日誌等級擷取規則，依序套用，第一個取得等級的規則生效
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeverityConfig {
+    #[serde(default)]
+    pub rules: Vec<SeverityRule>,
+}
+
+impl Default for SeverityConfig {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                SeverityRule {
+                    topic: default_severity_topic(),
+                    json_path: Some("$.level".to_string()),
+                    regex: None,
+                    syslog: false,
+                },
+                SeverityRule {
+                    topic: default_severity_topic(),
+                    json_path: None,
+                    regex: Some(r"^\s*\[?(TRACE|DEBUG|INFO|WARN|WARNING|ERROR|FATAL)\b".to_string()),
+                    syslog: false,
+                },
+                SeverityRule {
+                    topic: default_severity_topic(),
+                    json_path: None,
+                    regex: None,
+                    syslog: true,
+                },
+            ],
+        }
+    }
+}
+
+/// 每條規則只設定 json_path、regex、syslog 其中一種
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeverityRule {
+    #[serde(default = "default_severity_topic")]
+    pub topic: String, // MQTT topic pattern
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_path: Option<String>, // 例如 $.level 或 $.log.severity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>, // 取第一個 capture group (或名為 level 的 group)
+    #[serde(default)]
+    pub syslog: bool, // <PRI> 開頭的 syslog 格式
+}
+
+/// topic pattern 預期的發佈間隔，例如 topic = "sensors/+/status"、expected_interval = "5m"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StaleTopicRule {
+    pub topic: String,             // MQTT topic pattern
+    pub expected_interval: String, // 30s、5m、1h30m
+}
+
+/// 靜音的 topic pattern：hide 只在 topic 清單隱藏，drop 在收到時直接丟棄不寫入資料庫
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MuteRule {
+    pub topic: String, // MQTT topic pattern
+    #[serde(default)]
+    pub mode: MuteMode,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MuteMode {
+    #[default]
+    Hide,
+    Drop,
+}
+
+impl MuteMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MuteMode::Hide => "hide",
+            MuteMode::Drop => "drop",
+        }
+    }
+}
+
+/// 告警規則：topic pattern 搭配 condition、max_rate、silence (max_rate 與 silence 擇一)，觸發後執行設定的動作
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlertRule {
+    pub name: String,
+    #[serde(default = "default_alert_topic")]
+    pub topic: String, // MQTT topic pattern
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>, // 查詢語法，例如 $.temperature > 80 或 level:error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rate: Option<f64>, // 每秒訊息數超過此值時觸發
+    #[serde(default = "default_alert_rate_window")]
+    pub rate_window: String, // 計算速率的時間窗
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub silence: Option<String>, // 超過此時間沒有訊息時觸發，例如 5m
+    #[serde(default = "default_alert_cooldown")]
+    pub cooldown: String, // 同一規則與 topic 再次觸發的最短間隔 (silence 只在恢復後重新觸發)
+    #[serde(default = "default_alert_action")]
+    pub notify: bool, // 終端機提示音與狀態列橫幅
+    #[serde(default = "default_alert_action")]
+    pub store: bool, // 寫入資料庫的 alerts 資料表
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>, // 本機指令，告警內容放在 ALERT_* 環境變數
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish: Option<String>, // 將告警以 JSON 發佈到這個 MQTT topic
+}
+
+fn default_alert_topic() -> String {
+    "#".to_string()
+}
+
+fn default_alert_rate_window() -> String {
+    "1m".to_string()
+}
+
+fn default_alert_cooldown() -> String {
+    "1m".to_string()
+}
+
+fn default_alert_action() -> bool {
+    true
+}
+
+fn default_severity_topic() -> String {
+    "#".to_string()
+}
+
+/// topic pattern (支援 MQTT 的 + 與 # 萬用字元) 對應到解碼器
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DecoderMapping {
+    pub topic: String,
+    pub decoder: String, // msgpack | cbor | protobuf | sparkplug
+    #[serde(default)]
+    pub message: Option<String>, // protobuf 的完整 message 名稱，例如 acme.Telemetry
 }
 
 impl Default for Config {
@@ -68,12 +386,16 @@ impl Default for Config {
                 username: None,
                 password: None,
                 client_id: "mqtt_log_viewer".to_string(),
+                clean_session: true,
+                subscribe_qos: 0,
+                reconnect: ReconnectConfig::default(),
             },
             database: DatabaseConfig {
                 path: "./mqtt_logs.db".to_string(),
                 max_messages: 100_000,
-                auto_cleanup: true,
+                auto_cleanup: false,
                 cleanup_days: 30,
+                busy_timeout_ms: default_busy_timeout_ms(),
             },
             ui: UiConfig {
                 refresh_interval_ms: 250,
@@ -85,65 +407,133 @@ impl Default for Config {
                 max_memory_mb: 100,
                 cache_size: 1000,
                 batch_size: 100,
+                queue_capacity: default_queue_capacity(),
+                overflow_policy: OverflowPolicy::default(),
+                event_channel_capacity: default_event_channel_capacity(),
             },
             quick_filters: QuickFiltersConfig {
                 enabled: true,
                 filters: vec![
                     QuickFilter {
                         name: "INFO".to_string(),
-                        pattern: "INFO".to_string(),
+                        pattern: String::new(),
+                        level: Some("INFO".to_string()),
                         color: "light_green".to_string(),
                         hotkey: "F1".to_string(),
                         enabled: true,
                         case_sensitive: false,
+                        regex: false,
+                        show_count: true,
                     },
                     QuickFilter {
                         name: "WARN".to_string(),
-                        pattern: "WARN".to_string(),
+                        pattern: String::new(),
+                        level: Some("WARN".to_string()),
                         color: "yellow".to_string(),
                         hotkey: "F2".to_string(),
                         enabled: true,
                         case_sensitive: false,
+                        regex: false,
+                        show_count: true,
                     },
                     QuickFilter {
                         name: "ERROR".to_string(),
-                        pattern: "ERROR".to_string(),
+                        pattern: String::new(),
+                        level: Some("ERROR".to_string()),
                         color: "red".to_string(),
                         hotkey: "F3".to_string(),
                         enabled: true,
                         case_sensitive: false,
+                        regex: false,
+                        show_count: true,
                     },
                     QuickFilter {
                         name: "TRACE".to_string(),
-                        pattern: "TRACE".to_string(),
+                        pattern: String::new(),
+                        level: Some("TRACE".to_string()),
                         color: "dark_grey".to_string(),
                         hotkey: "F4".to_string(),
                         enabled: true,
                         case_sensitive: false,
+                        regex: false,
+                        show_count: true,
                     },
                     QuickFilter {
                         name: "DEBUG".to_string(),
-                        pattern: "DEBUG".to_string(),
+                        pattern: String::new(),
+                        level: Some("DEBUG".to_string()),
                         color: "cyan".to_string(),
                         hotkey: "F5".to_string(),
                         enabled: true,
                         case_sensitive: false,
+                        regex: false,
+                        show_count: true,
                     },
                 ],
             },
+            decoders: DecodersConfig::default(),
+            severity: SeverityConfig::default(),
+            presets: Vec::new(),
+            message_columns: Vec::new(),
+            topic_list: TopicListConfig::default(),
+            stale_topics: Vec::new(),
+            alerts: Vec::new(),
+            mutes: Vec::new(),
         }
     }
 }
 
 impl Config {
+    /// 設定檔路徑 (MQTT_LOG_VIEWER_CONFIG 或 ./config.toml)
+    pub fn path() -> String {
+        std::env::var("MQTT_LOG_VIEWER_CONFIG")
+            .unwrap_or_else(|_| "./config.toml".to_string())
+    }
+    
+    /// 讀取既有的設定檔，不存在時回傳錯誤 (不寫入預設值)
+    pub fn load_from(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let config: Config = toml::from_str(&content)?;
+        Ok(config)
+    }
+    
+    /// 只改寫設定檔中的 [topic_list]，保留其他設定與註解；檔案不存在時建立
+    pub fn save_topic_list(path: &str, topic_list: &TopicListConfig) -> Result<()> {
+        #[derive(Serialize)]
+        struct Section<'a> {
+            topic_list: &'a TopicListConfig,
+        }
+        let section = toml::to_string(&Section { topic_list })?;
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        fs::write(path, replace_section(&content, "topic_list", &section))?;
+        Ok(())
+    }
+    
+    /// 只改寫設定檔中的 [[mutes]]；沒有規則時移除整個區段
+    pub fn save_mutes(path: &str, mutes: &[MuteRule]) -> Result<()> {
+        #[derive(Serialize)]
+        struct Section<'a> {
+            mutes: &'a [MuteRule],
+        }
+        let section = if mutes.is_empty() { String::new() } else { toml::to_string(&Section { mutes })? };
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        fs::write(path, replace_section(&content, "mutes", &section))?;
+        Ok(())
+    }
+    
     pub fn load() -> Result<Self> {
-        let config_path = std::env::var("MQTT_LOG_VIEWER_CONFIG")
-            .unwrap_or_else(|_| "./config.toml".to_string());
+        let config_path = Self::path();
         
         if std::path::Path::new(&config_path).exists() {
-            let content = fs::read_to_string(&config_path)?;
-            let config: Config = toml::from_str(&content)?;
-            Ok(config)
+            Self::load_from(&config_path)
         } else {
             let config = Config::default();
             // Save default config to file
@@ -152,4 +542,96 @@ impl Config {
             Ok(config)
         }
     }
-}
\ No newline at end of file
+}
+// 以新的內容取代 [name]、[name.*] 與 [[name.*]] 表格，放在原本的位置 (沒有時加在檔尾)；
+// 表格後面接著下一個表格的註解保留下來
+fn replace_section(content: &str, name: &str, section: &str) -> String {
+    let header = regex::Regex::new(r#"^\s*\[\[?\s*([A-Za-z0-9_.\-" ]+?)\s*\]\]?\s*(#.*)?$"#).expect("valid regex");
+    let mut output = String::new();
+    let mut pending = String::new(); // 表格內的註解與空行，下一行決定是否保留
+    let mut inside = false;
+    let mut inserted = false;
+
+    for line in content.lines() {
+        if let Some(captures) = header.captures(line) {
+            let table = &captures[1];
+            let was_inside = inside;
+            inside = table == name || table.starts_with(&format!("{}.", name));
+            if was_inside && !inside {
+                // 與下一個表格之間保留一個空行
+                if !pending.starts_with('\n') {
+                    output.push('\n');
+                }
+                output.push_str(&pending);
+            }
+            pending.clear();
+            if inside && !inserted {
+                output.push_str(section);
+                inserted = true;
+            }
+        } else if inside {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                pending.push_str(line);
+                pending.push('\n');
+            } else {
+                pending.clear();
+            }
+            continue;
+        }
+        if !inside {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    if !inserted {
+        if !output.is_empty() && !output.ends_with("\n\n") {
+            output.push('\n');
+        }
+        output.push_str(section);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_section() {
+        let content = "[ui]\ntheme = \"dark\"\n\n[topic_list]\nsort = \"count\"\n\n[[topic_list.columns]]\ncolumn = \"topic\"\n\n# Columns comment\n[[message_columns]]\ntopic = \"#\"\n";
+        let replaced = replace_section(content, "topic_list", "[topic_list]\nsort = \"name\"\n");
+        assert_eq!(replaced, "[ui]\ntheme = \"dark\"\n\n[topic_list]\nsort = \"name\"\n\n# Columns comment\n[[message_columns]]\ntopic = \"#\"\n");
+
+        let appended = replace_section("[ui]\ntheme = \"dark\"\n", "topic_list", "[topic_list]\n");
+        assert_eq!(appended, "[ui]\ntheme = \"dark\"\n\n[topic_list]\n");
+
+        // 寫回的設定可以再讀回來，重複寫入不會產生重複的表格
+        let path = std::env::temp_dir().join(format!("mqtt_log_view_{}.toml", uuid::Uuid::new_v4()));
+        let path = path.to_string_lossy().to_string();
+        fs::write(&path, toml::to_string(&Config::default()).unwrap()).unwrap();
+        let topic_list = TopicListConfig {
+            sort: TopicSortKey::Bytes,
+            descending: false,
+            pinned: vec!["plant/a".to_string()],
+            ..Default::default()
+        };
+        Config::save_topic_list(&path, &topic_list).unwrap();
+        Config::save_topic_list(&path, &topic_list).unwrap();
+        assert_eq!(Config::load_from(&path).unwrap().topic_list, topic_list);
+
+        let mutes = vec![
+            MuteRule { topic: "a/heartbeat".to_string(), mode: MuteMode::Hide },
+            MuteRule { topic: "b/#".to_string(), mode: MuteMode::Drop },
+        ];
+        Config::save_mutes(&path, &mutes).unwrap();
+        Config::save_mutes(&path, &mutes).unwrap();
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.mutes, mutes);
+        assert_eq!(config.topic_list, topic_list);
+        Config::save_mutes(&path, &[]).unwrap();
+        assert!(Config::load_from(&path).unwrap().mutes.is_empty());
+        let _ = fs::remove_file(&path);
+    }
+}
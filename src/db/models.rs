@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+use crate::filter::Query;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub id: Option<i64>,
@@ -9,6 +11,11 @@ pub struct Message {
     pub timestamp: DateTime<Utc>,
     pub qos: i32,
     pub retain: bool,
+    pub backlog: bool, // broker 在重新連線後補送的離線訊息
+    pub encoding: Option<String>, // msgpack / cbor / protobuf 解碼成 JSON；None 為原始文字
+    pub raw_payload: Option<String>, // 原始 payload 的 base64，payload 欄位不是原始內容時才有
+    pub marker: Option<String>, // Sparkplug B 的 BIRTH/DEATH 與 seq 跳號標記
+    pub level: Option<String>, // 依 [severity] 規則擷取的日誌等級，例如 ERROR
     pub created_at: Option<DateTime<Utc>>,
 }
 
@@ -21,6 +28,11 @@ impl Message {
             timestamp: Utc::now(),
             qos,
             retain,
+            backlog: false,
+            encoding: None,
+            raw_payload: None,
+            marker: None,
+            level: None,
             created_at: Some(Utc::now()),
         }
     }
@@ -33,6 +45,50 @@ pub struct TopicStat {
     pub last_message_time: DateTime<Utc>,
     pub first_message_time: DateTime<Utc>,
     pub latest_payload: Option<String>,
+    pub quick_filter_counts: Vec<i64>, // 各快速過濾器符合的訊息數 (與 QuickFilterSet 同順序)
+    pub total_bytes: i64,              // payload 的總位元組數
+    pub rates: TopicRates,
+    pub silent_for: Option<chrono::Duration>, // 超過 [[stale_topics]] 預期間隔未收到訊息的時間
+    pub muted: bool, // 符合 [[mutes]] 規則，預設不列出
+}
+
+/// 計算訊息速率的滑動視窗 (標籤, 秒數)
+pub const RATE_WINDOWS: [(&str, i64); 3] = [("1m", 60), ("5m", 300), ("1h", 3600)];
+// sparkline 的格數與每格秒數：最近 1 小時，每格 5 分鐘
+pub const SPARKLINE_BUCKETS: usize = 12;
+pub const SPARKLINE_BUCKET_SECONDS: i64 = 300;
+
+/// topic 在各滑動視窗的每秒訊息數，以及最近一小時的訊息數分佈
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TopicRates {
+    pub per_second: [f64; 3], // 與 RATE_WINDOWS 同順序
+    pub buckets: Vec<u32>,    // SPARKLINE_BUCKETS 格，舊→新
+}
+
+impl TopicRates {
+    /// 以 now 為視窗終點計算；晚於 now 的訊息 (時鐘誤差) 算在最新的一格
+    pub fn from_timestamps(timestamps: impl Iterator<Item = DateTime<Utc>>, now: DateTime<Utc>) -> Self {
+        let mut counts = [0u64; 3];
+        let mut buckets = vec![0u32; SPARKLINE_BUCKETS];
+        for timestamp in timestamps {
+            let age = (now - timestamp).num_seconds().max(0);
+            for (count, (_, seconds)) in counts.iter_mut().zip(RATE_WINDOWS) {
+                if age < seconds {
+                    *count += 1;
+                }
+            }
+            let bucket = (age / SPARKLINE_BUCKET_SECONDS) as usize;
+            if bucket < SPARKLINE_BUCKETS {
+                buckets[SPARKLINE_BUCKETS - 1 - bucket] += 1;
+            }
+        }
+
+        let mut per_second = [0.0; 3];
+        for (rate, (count, (_, seconds))) in per_second.iter_mut().zip(counts.iter().zip(RATE_WINDOWS)) {
+            *rate = *count as f64 / seconds as f64;
+        }
+        Self { per_second, buckets }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -41,6 +97,7 @@ pub struct FilterCriteria {
     pub payload_regex: Option<String>,
     pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
+    pub query: Option<Query>, // 過濾欄位中的查詢語法，在 SQL 取出後逐筆比對
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
@@ -52,8 +109,87 @@ impl Default for FilterCriteria {
             payload_regex: None,
             start_time: None,
             end_time: None,
+            query: None,
             limit: Some(1000),
             offset: Some(0),
         }
     }
-}
\ No newline at end of file
+}
+
+/// 告警的觸發方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    Condition, // 訊息符合 condition
+    Rate,      // 速率超過 max_rate
+    Silence,   // 超過 silence 沒有訊息
+}
+
+impl AlertKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertKind::Condition => "condition",
+            AlertKind::Rate => "rate",
+            AlertKind::Silence => "silence",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "condition" => Some(AlertKind::Condition),
+            "rate" => Some(AlertKind::Rate),
+            "silence" => Some(AlertKind::Silence),
+            _ => None,
+        }
+    }
+}
+
+/// 已觸發的告警，存在 alerts 資料表
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub id: Option<i64>,
+    pub rule: String, // 規則名稱
+    pub kind: AlertKind,
+    pub topic: String,
+    pub description: String,
+    pub message_id: Option<i64>, // 觸發的訊息；silence 為沉寂前的最後一筆
+    pub fired_at: DateTime<Utc>,
+}
+
+/// 加上書籤的訊息與備註，存在 bookmarks 資料表
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    pub message: Message,
+    pub note: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 同一次刪除移到 trash 資料表的訊息，可以整批還原或永久刪除
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashEntry {
+    pub batch: String,
+    pub topic: String,
+    pub count: i64,
+    pub first_time: DateTime<Utc>, // 被刪除訊息的時間範圍
+    pub last_time: DateTime<Utc>,
+    pub deleted_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_topic_rates() {
+        let now = Utc::now();
+        // 最近 1 分鐘 30 筆、40 分鐘前 60 筆、2 小時前 1 筆
+        let timestamps = (0..30).map(|i| now - Duration::seconds(i))
+            .chain((0..60).map(|_| now - Duration::minutes(40)))
+            .chain(std::iter::once(now - Duration::hours(2)));
+        let rates = TopicRates::from_timestamps(timestamps, now);
+        assert_eq!(rates.per_second, [0.5, 0.1, 90.0 / 3600.0]);
+        assert_eq!(rates.buckets.iter().sum::<u32>(), 90);
+        assert_eq!(rates.buckets[SPARKLINE_BUCKETS - 1], 30);
+        assert_eq!(rates.buckets[SPARKLINE_BUCKETS - 9], 60);
+    }
+}
@@ -1,9 +1,15 @@
-use super::models::{Message, TopicStat, FilterCriteria};
-use anyhow::Result;
-use chrono::{DateTime, Utc};
-use rbatis::RBatis;
+use super::models::{Alert, AlertKind, Bookmark, Message, TopicStat, TopicRates, FilterCriteria, TrashEntry};
+use crate::filter::{FilterPreset, Query, QuickFilterSet};
+use crate::ui::ColumnSet;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local, Utc};
+use rbatis::{DefaultPool, RBatis};
+use rbdc::db::ExecResult;
 use rbdc_sqlite::driver::SqliteDriver;
+use rbdc_sqlite::{SqliteConnectOptions, SqliteJournalMode};
+use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 use tracing::{info, warn, error};
 use rayon::prelude::*;
 use regex::Regex;
@@ -11,27 +17,123 @@ use regex::Regex;
 #[derive(Clone)]
 pub struct MessageRepository {
     rb: RBatis,
+    read_only: bool,
+    // 查詢 messages 用的欄位清單；唯讀開啟的舊資料庫缺少的欄位以預設值代替
+    message_columns: String,
 }
 
+// 後來才加入 messages 的欄位及其預設值
+const OPTIONAL_MESSAGE_COLUMNS: &[(&str, &str)] = &[("backlog", "0"), ("encoding", "NULL"), ("marker", "NULL"), ("level", "NULL"), ("raw_payload", "NULL")];
+
+// 刪除與還原時在 messages 和 trash 之間複製的欄位 (trash 另有 batch 與 deleted_at)
+const TRASH_COLUMNS: &str = "id, topic, payload, timestamp, qos, retain, backlog, encoding, marker, level, raw_payload, created_at";
+
 impl MessageRepository {
-    pub async fn new(db_path: &str) -> Result<Self> {
-        let rb = RBatis::new();
-        
+    pub async fn new(db_path: &str, busy_timeout: Duration) -> Result<Self> {
         // Create parent directory if it doesn't exist
         if let Some(parent) = Path::new(db_path).parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
         
-        let url = format!("sqlite:{}", db_path);
-        rb.link(SqliteDriver {}, &url).await?;
+        // WAL 讓 UI 讀取與寫入互不阻塞，busy_timeout 處理短暫的鎖衝突
+        let options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(busy_timeout);
         
-        let repo = Self { rb };
+        let mut repo = Self::connect(options, false).await?;
         repo.initialize_schema().await?;
+        repo.message_columns = repo.build_message_columns().await?;
         
         info!("Database initialized at: {}", db_path);
         Ok(repo)
     }
     
+    /// 以唯讀方式開啟既有的資料庫檔案（不建立、不遷移 schema）
+    pub async fn open_read_only(db_path: &str, busy_timeout: Duration) -> Result<Self> {
+        if !Path::new(db_path).exists() {
+            bail!("Database file not found: {}", db_path);
+        }
+        
+        // 唯讀連線無法切換 journal mode，必須沿用檔案目前的模式
+        let options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(false)
+            .read_only(true)
+            .journal_mode(Self::detect_journal_mode(db_path)?)
+            .busy_timeout(busy_timeout);
+        
+        let mut repo = Self::connect(options, true).await?;
+        repo.message_columns = repo.build_message_columns().await?;
+        
+        info!("Database opened read-only at: {}", db_path);
+        Ok(repo)
+    }
+    
+    // SQLite 檔頭第 18 個位元組為 2 表示 WAL，1 表示傳統 rollback journal
+    fn detect_journal_mode(db_path: &str) -> Result<SqliteJournalMode> {
+        let mut header = [0u8; 100];
+        let mut file = std::fs::File::open(db_path)?;
+        std::io::Read::read_exact(&mut file, &mut header)?;
+        
+        if !header.starts_with(b"SQLite format 3\0") {
+            bail!("Not a SQLite database: {}", db_path);
+        }
+        
+        Ok(if header[18] == 2 {
+            SqliteJournalMode::Wal
+        } else {
+            SqliteJournalMode::Delete
+        })
+    }
+    
+    async fn connect(options: SqliteConnectOptions, read_only: bool) -> Result<Self> {
+        let rb = RBatis::new();
+        rb.init_option::<SqliteDriver, SqliteConnectOptions, DefaultPool>(SqliteDriver {}, options)?;
+        rb.try_acquire().await?;
+        
+        Ok(Self {
+            rb,
+            read_only,
+            message_columns: String::new(),
+        })
+    }
+    
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+    
+    /// 所有寫入操作都經過這裡，唯讀模式下直接拒絕
+    async fn exec(&self, sql: &str, args: Vec<rbs::Value>) -> Result<ExecResult> {
+        if self.read_only {
+            bail!("Database is opened read-only");
+        }
+        Ok(self.rb.exec(sql, args).await?)
+    }
+    
+    /// 在同一個交易中依序執行，任一句失敗時整個復原；回傳各句影響的筆數
+    async fn exec_in_transaction(&self, statements: Vec<(&str, Vec<rbs::Value>)>) -> Result<Vec<u64>> {
+        if self.read_only {
+            bail!("Database is opened read-only");
+        }
+        let tx = self.rb.acquire_begin().await?;
+        let mut affected = Vec::with_capacity(statements.len());
+        for (sql, args) in statements {
+            match tx.exec(sql, args).await {
+                Ok(result) => affected.push(result.rows_affected),
+                Err(e) => {
+                    if let Err(rollback_error) = tx.rollback().await {
+                        error!("Failed to roll back transaction: {}", rollback_error);
+                    }
+                    return Err(e.into());
+                }
+            }
+        }
+        tx.commit().await?;
+        Ok(affected)
+    }
+    
     async fn initialize_schema(&self) -> Result<()> {
         let schema_sql = include_str!("../../migrations/init.sql");
         
@@ -39,29 +141,84 @@ impl MessageRepository {
         for statement in schema_sql.split(';') {
             let statement = statement.trim();
             if !statement.is_empty() {
-                self.rb.exec(statement, vec![]).await?;
+                self.exec(statement, vec![]).await?;
             }
         }
         
+        // 舊版資料庫沒有的欄位，在這裡補上
+        self.ensure_column("messages", "backlog", "BOOLEAN DEFAULT 0").await?;
+        self.ensure_column("messages", "encoding", "TEXT").await?;
+        self.ensure_column("messages", "marker", "TEXT").await?;
+        self.ensure_column("messages", "level", "TEXT").await?;
+        self.ensure_column("messages", "raw_payload", "TEXT").await?;
+        self.ensure_column("trash", "raw_payload", "TEXT").await?;
+        
         info!("Database schema initialized");
         Ok(())
     }
     
+    async fn table_columns(&self, table: &str) -> Result<Vec<String>> {
+        let result = self.rb.query(&format!("PRAGMA table_info({})", table), vec![]).await?;
+        
+        let columns = match result {
+            rbs::Value::Array(rows) => rows
+                .iter()
+                .filter_map(|row| row["name"].as_str().map(|name| name.to_string()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        
+        Ok(columns)
+    }
+    
+    /// 欄位不存在時以 ALTER TABLE 新增
+    async fn ensure_column(&self, table: &str, column: &str, definition: &str) -> Result<()> {
+        if !self.table_columns(table).await?.iter().any(|c| c == column) {
+            info!("Adding column {}.{}", table, column);
+            self.exec(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), vec![]).await?;
+        }
+        
+        Ok(())
+    }
+    
+    async fn build_message_columns(&self) -> Result<String> {
+        let existing = self.table_columns("messages").await?;
+        let mut columns = vec!["id", "topic", "payload", "timestamp", "qos", "retain", "created_at"]
+            .into_iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>();
+        
+        for (column, default) in OPTIONAL_MESSAGE_COLUMNS {
+            if existing.iter().any(|c| c == column) {
+                columns.push(column.to_string());
+            } else {
+                columns.push(format!("{} AS {}", default, column));
+            }
+        }
+        
+        Ok(columns.join(", "))
+    }
+    
     pub async fn insert_message(&self, message: &Message) -> Result<i64> {
         let sql = r#"
-            INSERT INTO messages (topic, payload, timestamp, qos, retain) 
-            VALUES (?, ?, ?, ?, ?)
+            INSERT INTO messages (topic, payload, timestamp, qos, retain, backlog, encoding, marker, level, raw_payload) 
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#;
         
         tracing::debug!("Inserting message: topic={}, payload_len={}, timestamp={}", 
                        message.topic, message.payload.len(), message.timestamp);
         
-        let result = self.rb.exec(sql, vec![
+        let result = self.exec(sql, vec![
             rbs::to_value(&message.topic)?,
             rbs::to_value(&message.payload)?,
             rbs::to_value(&message.timestamp.to_rfc3339())?, // Convert to string
             rbs::to_value(&message.qos)?,
             rbs::to_value(&message.retain)?,
+            rbs::to_value(message.backlog)?,
+            rbs::to_value(&message.encoding)?,
+            rbs::to_value(&message.marker)?,
+            rbs::to_value(&message.level)?,
+            rbs::to_value(&message.raw_payload)?,
         ]).await?;
         
         let insert_id = result.last_insert_id.as_i64().unwrap_or(0);
@@ -75,87 +232,32 @@ impl MessageRepository {
         criteria: &FilterCriteria,
     ) -> Result<Vec<Message>> {
         // First, get all messages for the topic (with time filters only)
-        let mut sql = "SELECT id, topic, payload, timestamp, qos, retain, created_at FROM messages WHERE topic = ?".to_string();
+        let mut sql = format!("SELECT {} FROM messages WHERE topic = ?", self.message_columns);
         let mut args = vec![rbs::to_value(topic)?];
         
         // Add time range filters (these stay in SQL for efficiency)
-        if let Some(start_time) = &criteria.start_time {
+        let (start_time, end_time) = Self::time_range(criteria);
+        if let Some(start_time) = &start_time {
             sql.push_str(" AND timestamp >= ?");
             args.push(rbs::to_value(start_time)?);
         }
         
-        if let Some(end_time) = &criteria.end_time {
+        if let Some(end_time) = &end_time {
             sql.push_str(" AND timestamp <= ?");
             args.push(rbs::to_value(end_time)?);
         }
         
+        if let Some(prefilter) = Self::query_prefilter(criteria.query.as_ref(), &mut args)? {
+            sql.push_str(" AND ");
+            sql.push_str(&prefilter);
+        }
+        
         sql.push_str(" ORDER BY timestamp DESC");
         
         tracing::debug!("Executing SQL: {} with args: {:?}", sql, args);
         
         let result = self.rb.query(&sql, args).await?;
-        let mut messages = Vec::new();
-        
-        if let rbs::Value::Array(rows) = result {
-            for (idx, row_value) in rows.into_iter().enumerate() {
-                if let rbs::Value::Map(row) = row_value {
-                    // Debug: print all keys in the map
-                    if idx == 0 {
-                        tracing::debug!("First row keys: {:?}", row.0.keys().collect::<Vec<_>>());
-                    }
-                    
-                    let id_key = rbs::Value::String("id".to_string());
-                    let topic_key = rbs::Value::String("topic".to_string());
-                    let payload_key = rbs::Value::String("payload".to_string());
-                    let timestamp_key = rbs::Value::String("timestamp".to_string());
-                    let qos_key = rbs::Value::String("qos".to_string());
-                    let retain_key = rbs::Value::String("retain".to_string());
-                    
-                    // Debug: check what value we get for payload
-                    let payload_value = row.get(&payload_key);
-                    if idx == 0 {
-                        tracing::debug!("First row payload value type: {:?}", payload_value);
-                    }
-                    
-                    let id = row.get(&id_key).as_i64();
-                    let topic = row.get(&topic_key).as_str().unwrap_or("").to_string();
-                    
-                    // Handle payload - it might be a Map (JSON object) or a String
-                    let payload = match payload_value {
-                        rbs::Value::String(s) => s.clone(),
-                        rbs::Value::Map(_) | rbs::Value::Array(_) => {
-                            // Convert Map/Array back to JSON string
-                            serde_json::to_string(payload_value).unwrap_or_else(|_| "{}".to_string())
-                        }
-                        _ => payload_value.as_str().unwrap_or("").to_string()
-                    };
-                    let timestamp_str = row.get(&timestamp_key).as_str().unwrap_or("");
-                    let qos = row.get(&qos_key).as_i64().unwrap_or(0) as i32;
-                    let retain = row.get(&retain_key).as_bool().unwrap_or(false);
-                    
-                    // Debug logging
-                    if idx < 3 {
-                        tracing::debug!("Row {} data - topic: {}, payload: {}, timestamp: {}", idx, topic, payload, timestamp_str);
-                    }
-                    
-                    let timestamp = DateTime::parse_from_rfc3339(timestamp_str)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now());
-                    
-                    messages.push(Message {
-                        id,
-                        topic,
-                        payload,
-                        timestamp,
-                        qos,
-                        retain,
-                        created_at: None,
-                    });
-                }
-            }
-        } else {
-            tracing::warn!("Query result is not an array: {:?}", result);
-        }
+        let mut messages = Self::rows_to_messages(result);
         
         tracing::debug!("Retrieved {} messages from database", messages.len());
         
@@ -179,6 +281,8 @@ impl MessageRepository {
             }
         }
         
+        messages = Self::apply_query(messages, criteria);
+        
         // Apply limit and offset after filtering
         if let Some(offset) = criteria.offset {
             messages = messages.into_iter().skip(offset as usize).collect();
@@ -192,24 +296,35 @@ impl MessageRepository {
         Ok(messages)
     }
     
-    pub async fn get_topic_stats(&self, criteria: &FilterCriteria) -> Result<Vec<TopicStat>> {
+    /// quick_filters 與 active 決定哪些訊息計入統計；各過濾器的計數則包含目前隱藏的訊息
+    pub async fn get_topic_stats(
+        &self,
+        criteria: &FilterCriteria,
+        quick_filters: &QuickFilterSet,
+        active: &[bool],
+    ) -> Result<Vec<TopicStat>> {
         // For topic stats, we need to get all messages first, then filter and aggregate
-        let mut sql = "SELECT topic, payload, timestamp FROM messages".to_string();
+        let mut sql = format!("SELECT {} FROM messages", self.message_columns);
         
         let mut args = vec![];
         let mut where_clauses = vec![];
         
         // Add time range filters (these stay in SQL for efficiency)
-        if let Some(start_time) = &criteria.start_time {
+        let (start_time, end_time) = Self::time_range(criteria);
+        if let Some(start_time) = &start_time {
             where_clauses.push("timestamp >= ?".to_string());
             args.push(rbs::to_value(start_time)?);
         }
         
-        if let Some(end_time) = &criteria.end_time {
+        if let Some(end_time) = &end_time {
             where_clauses.push("timestamp <= ?".to_string());
             args.push(rbs::to_value(end_time)?);
         }
         
+        if let Some(prefilter) = Self::query_prefilter(criteria.query.as_ref(), &mut args)? {
+            where_clauses.push(prefilter);
+        }
+        
         if !where_clauses.is_empty() {
             sql.push_str(" WHERE ");
             sql.push_str(&where_clauses.join(" AND "));
@@ -221,32 +336,7 @@ impl MessageRepository {
         
         // Get all messages
         let result = self.rb.query(&sql, args).await?;
-        let mut all_messages = Vec::new();
-        
-        if let rbs::Value::Array(rows) = result {
-            for row_value in rows {
-                if let rbs::Value::Map(row) = row_value {
-                    let topic_key = rbs::Value::String("topic".to_string());
-                    let payload_key = rbs::Value::String("payload".to_string());
-                    let timestamp_key = rbs::Value::String("timestamp".to_string());
-                    
-                    let topic = row.get(&topic_key).as_str().unwrap_or("").to_string();
-                    let payload = match row.get(&payload_key) {
-                        rbs::Value::String(s) => s.clone(),
-                        rbs::Value::Map(_) | rbs::Value::Array(_) => {
-                            serde_json::to_string(row.get(&payload_key)).unwrap_or_else(|_| "{}".to_string())
-                        }
-                        _ => row.get(&payload_key).as_str().unwrap_or("").to_string()
-                    };
-                    let timestamp_str = row.get(&timestamp_key).as_str().unwrap_or("");
-                    let timestamp = DateTime::parse_from_rfc3339(timestamp_str)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now());
-                    
-                    all_messages.push((topic, payload, timestamp));
-                }
-            }
-        }
+        let mut all_messages = Self::rows_to_messages(result);
         
         tracing::debug!("Retrieved {} messages for stats", all_messages.len());
         
@@ -256,7 +346,7 @@ impl MessageRepository {
                 Ok(regex) => {
                     all_messages = all_messages
                         .into_par_iter()
-                        .filter(|(topic, _, _)| regex.is_match(topic))
+                        .filter(|message| regex.is_match(&message.topic))
                         .collect();
                     tracing::debug!("After topic regex filter: {} messages", all_messages.len());
                 }
@@ -271,7 +361,7 @@ impl MessageRepository {
                 Ok(regex) => {
                     all_messages = all_messages
                         .into_par_iter()
-                        .filter(|(_, payload, _)| regex.is_match(payload))
+                        .filter(|message| regex.is_match(&message.payload))
                         .collect();
                     tracing::debug!("After payload regex filter: {} messages", all_messages.len());
                 }
@@ -281,33 +371,67 @@ impl MessageRepository {
             }
         }
         
+        all_messages = Self::apply_query(all_messages, criteria);
+        
+        // 速率以現在為視窗終點；唯讀開啟的錄製檔以其中最新的訊息為準
+        let now = if self.is_read_only() {
+            all_messages.iter().map(|message| message.timestamp).max().unwrap_or_else(Utc::now)
+        } else {
+            Utc::now()
+        };
+        
         // Group by topic and calculate stats
-        use std::collections::HashMap;
-        let mut topic_map: HashMap<String, Vec<(String, DateTime<Utc>)>> = HashMap::new();
+        let mut topic_map: HashMap<String, Vec<Message>> = HashMap::new();
         
-        for (topic, payload, timestamp) in all_messages {
-            topic_map.entry(topic.clone())
+        for message in all_messages {
+            topic_map.entry(message.topic.clone())
                 .or_insert_with(Vec::new)
-                .push((payload, timestamp));
+                .push(message);
         }
         
+        let filter_count = quick_filters.filters.len();
         let mut topic_stats: Vec<TopicStat> = topic_map
             .into_par_iter()
-            .map(|(topic, messages)| {
-                let message_count = messages.len() as i64;
-                let last_message_time = messages.iter().map(|(_, t)| *t).max().unwrap_or_else(Utc::now);
-                let first_message_time = messages.iter().map(|(_, t)| *t).min().unwrap_or_else(Utc::now);
-                let latest_payload = messages.iter()
+            .filter_map(|(topic, messages)| {
+                // 快速過濾器：計算各過濾器的訊息數，並排除被關閉的過濾器隱藏的訊息
+                let mut quick_filter_counts = vec![0i64; filter_count];
+                let mut visible = Vec::with_capacity(messages.len());
+                for message in messages {
+                    let matching = quick_filters.matching_indices(&topic, &message.payload, message.level.as_deref());
+                    for &index in &matching {
+                        quick_filter_counts[index] += 1;
+                    }
+                    if QuickFilterSet::is_visible(&matching, active) {
+                        visible.push((message.payload, message.timestamp));
+                    }
+                }
+                
+                // 所有訊息都被隱藏的 topic 不列出
+                if visible.is_empty() {
+                    return None;
+                }
+                
+                let message_count = visible.len() as i64;
+                let last_message_time = visible.iter().map(|(_, t)| *t).max().unwrap_or_else(Utc::now);
+                let first_message_time = visible.iter().map(|(_, t)| *t).min().unwrap_or_else(Utc::now);
+                let latest_payload = visible.iter()
                     .max_by_key(|(_, t)| *t)
                     .map(|(p, _)| p.clone());
+                let rates = TopicRates::from_timestamps(visible.iter().map(|(_, t)| *t), now);
+                let total_bytes = visible.iter().map(|(p, _)| p.len() as i64).sum();
                 
-                TopicStat {
+                Some(TopicStat {
                     topic,
                     message_count,
                     last_message_time,
                     first_message_time,
                     latest_payload,
-                }
+                    quick_filter_counts,
+                    total_bytes,
+                    rates,
+                    silent_for: None,
+                    muted: false,
+                })
             })
             .collect();
         
@@ -323,20 +447,202 @@ impl MessageRepository {
         Ok(topic_stats)
     }
     
-    pub async fn cleanup_old_messages(&self, days: u32) -> Result<u64> {
-        let sql = "DELETE FROM messages WHERE created_at < datetime('now', '-{} days')";
-        let sql = sql.replace("{}", &days.to_string());
+    /// 所有 topic 中符合查詢的訊息，取最新的 limit 筆 (依時間新到舊)
+    pub async fn query_messages(&self, query: &Query, limit: Option<usize>) -> Result<Vec<Message>> {
+        let (start_time, end_time) = query.time_bounds(Local::now());
+        let mut sql = format!("SELECT {} FROM messages WHERE 1 = 1", self.message_columns);
+        let mut args = vec![];
+        if let Some(start_time) = &start_time {
+            sql.push_str(" AND timestamp >= ?");
+            args.push(rbs::to_value(start_time)?);
+        }
+        if let Some(end_time) = &end_time {
+            sql.push_str(" AND timestamp <= ?");
+            args.push(rbs::to_value(end_time)?);
+        }
+        if let Some(prefilter) = Self::query_prefilter(Some(query), &mut args)? {
+            sql.push_str(" AND ");
+            sql.push_str(&prefilter);
+        }
+        sql.push_str(" ORDER BY timestamp DESC");
+        
+        let result = self.rb.query(&sql, args).await?;
+        let now = Local::now();
+        let mut messages: Vec<Message> = Self::rows_to_messages(result)
+            .into_par_iter()
+            .filter(|message| query.matches(message, now))
+            .collect();
+        if let Some(limit) = limit {
+            messages.truncate(limit);
+        }
+        Ok(messages)
+    }
+    
+    /// 同一 topic 中緊接在 message 之前的訊息
+    pub async fn get_previous_message(&self, message: &Message) -> Result<Option<Message>> {
+        let mut sql = format!("SELECT {} FROM messages WHERE topic = ? AND (timestamp < ?", self.message_columns);
+        let mut args = vec![rbs::to_value(&message.topic)?, rbs::to_value(message.timestamp.to_rfc3339())?];
+        if let Some(id) = message.id {
+            // 同一時間的多筆訊息依 id 區分先後
+            sql.push_str(" OR (timestamp = ? AND id < ?)");
+            args.push(rbs::to_value(message.timestamp.to_rfc3339())?);
+            args.push(rbs::to_value(id)?);
+        }
+        sql.push_str(") ORDER BY timestamp DESC, id DESC LIMIT 1");
+        
+        let result = self.rb.query(&sql, args).await?;
+        Ok(Self::rows_to_messages(result).into_iter().next())
+    }
+    
+    // 過濾欄位的時間與查詢中最上層 AND 的時間條件取交集，交給 SQL 處理
+    fn time_range(criteria: &FilterCriteria) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let (query_start, query_end) = criteria.query
+            .as_ref()
+            .map(|query| query.time_bounds(Local::now()))
+            .unwrap_or_default();
+        let start_time = criteria.start_time.max(query_start);
+        let end_time = match (criteria.end_time, query_end) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        (start_time, end_time)
+    }
+    
+    // 查詢中可轉成 SQL 的條件 (json_extract、qos...)，其餘在取出後由 apply_query 比對
+    fn query_prefilter(query: Option<&Query>, args: &mut Vec<rbs::Value>) -> Result<Option<String>> {
+        let Some((sql, values)) = query.and_then(Query::sql_prefilter) else {
+            return Ok(None);
+        };
+        for value in values {
+            args.push(rbs::to_value(value)?);
+        }
+        Ok(Some(sql))
+    }
+    
+    fn apply_query(messages: Vec<Message>, criteria: &FilterCriteria) -> Vec<Message> {
+        match &criteria.query {
+            Some(query) => {
+                let now = Local::now();
+                messages.into_par_iter().filter(|message| query.matches(message, now)).collect()
+            }
+            None => messages,
+        }
+    }
+    
+    fn rows_to_messages(result: rbs::Value) -> Vec<Message> {
+        let rows = match result {
+            rbs::Value::Array(rows) => rows,
+            other => {
+                tracing::warn!("Query result is not an array: {:?}", other);
+                return Vec::new();
+            }
+        };
         
-        let result = self.rb.exec(&sql, vec![]).await?;
-        let deleted_count = result.rows_affected;
+        let key = |name: &str| rbs::Value::String(name.to_string());
+        rows.into_iter()
+            .filter_map(|row_value| match row_value {
+                rbs::Value::Map(row) => Some(row),
+                _ => None,
+            })
+            .map(|row| {
+                // payload 若為 JSON，rbdc 會轉成 Map/Array，需轉回字串
+                let payload_value = row.get(&key("payload"));
+                let payload = match payload_value {
+                    rbs::Value::String(s) => s.clone(),
+                    rbs::Value::Map(_) | rbs::Value::Array(_) => {
+                        serde_json::to_string(payload_value).unwrap_or_else(|_| "{}".to_string())
+                    }
+                    _ => payload_value.as_str().unwrap_or("").to_string()
+                };
+                let timestamp = DateTime::parse_from_rfc3339(row.get(&key("timestamp")).as_str().unwrap_or(""))
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now());
+                
+                Message {
+                    id: row.get(&key("id")).as_i64(),
+                    topic: row.get(&key("topic")).as_str().unwrap_or("").to_string(),
+                    payload,
+                    timestamp,
+                    qos: row.get(&key("qos")).as_i64().unwrap_or(0) as i32,
+                    retain: row.get(&key("retain")).as_bool().unwrap_or(false),
+                    backlog: row.get(&key("backlog")).as_bool().unwrap_or(false),
+                    encoding: row.get(&key("encoding")).as_str().map(|s| s.to_string()),
+                    raw_payload: row.get(&key("raw_payload")).as_str().map(|s| s.to_string()),
+                    marker: row.get(&key("marker")).as_str().map(|s| s.to_string()),
+                    level: row.get(&key("level")).as_str().map(|s| s.to_string()),
+                    created_at: None,
+                }
+            })
+            .collect()
+    }
+    
+    /// 把超過保留天數的訊息移到垃圾桶，有書籤的訊息保留
+    pub async fn cleanup_old_messages(&self, days: u32) -> Result<u64> {
+        let condition = format!(
+            "created_at < datetime('now', '-{} days') AND id NOT IN (SELECT message_id FROM bookmarks)",
+            days
+        );
+        let deleted_count = self.move_to_trash(&condition, vec![]).await?.map_or(0, |entry| entry.count as u64);
         
         if deleted_count > 0 {
-            info!("Cleaned up {} old messages", deleted_count);
+            info!("Moved {} old messages to trash", deleted_count);
         }
         
         Ok(deleted_count)
     }
     
+    /// 永久刪除移到垃圾桶超過保留天數的訊息
+    pub async fn purge_expired_trash(&self, days: u32) -> Result<u64> {
+        let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+        let purged = self.purge_trash_where("deleted_at < ?", rbs::to_value(cutoff.to_rfc3339())?).await?;
+        
+        if purged > 0 {
+            info!("Purged {} expired messages from trash", purged);
+        }
+        Ok(purged)
+    }
+    
+    /// 最早與最新訊息的時間，資料表為空時回傳 None
+    pub async fn get_time_range(&self) -> Result<Option<(DateTime<Utc>, DateTime<Utc>)>> {
+        let sql = "SELECT MIN(timestamp) AS first_time, MAX(timestamp) AS last_time FROM messages";
+        let result = self.rb.query(sql, vec![]).await?;
+        
+        let parse = |value: &rbs::Value| {
+            value.as_str()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.with_timezone(&Utc))
+        };
+        
+        if let rbs::Value::Array(rows) = result {
+            if let Some(row) = rows.first() {
+                if let (Some(first), Some(last)) = (parse(&row["first_time"]), parse(&row["last_time"])) {
+                    return Ok(Some((first, last)));
+                }
+            }
+        }
+        
+        Ok(None)
+    }
+    
+    /// 各 topic 最後一筆訊息的時間 (不受過濾條件影響)
+    pub async fn get_last_seen_times(&self) -> Result<HashMap<String, DateTime<Utc>>> {
+        let sql = "SELECT topic, MAX(timestamp) AS last_seen FROM messages GROUP BY topic";
+        let result = self.rb.query(sql, vec![]).await?;
+        
+        let mut last_seen = HashMap::new();
+        if let rbs::Value::Array(rows) = result {
+            for row in rows {
+                let time = row["last_seen"].as_str()
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                    .map(|dt| dt.with_timezone(&Utc));
+                if let (Some(topic), Some(time)) = (row["topic"].as_str(), time) {
+                    last_seen.insert(topic.to_string(), time);
+                }
+            }
+        }
+        Ok(last_seen)
+    }
+    
     pub async fn get_total_message_count(&self) -> Result<i64> {
         let sql = "SELECT COUNT(*) as count FROM messages";
         // For now, return 0 - will implement proper counting later
@@ -350,52 +656,580 @@ impl MessageRepository {
         Ok(0)
     }
     
-    /// 刪除指定topic的所有訊息
-    pub async fn delete_messages_by_topic(&self, topic: &str) -> Result<i64> {
-        let sql = "DELETE FROM messages WHERE topic = ?";
-        let args = vec![rbs::to_value(topic)?];
+    /// 刪除指定topic的所有訊息（移到垃圾桶）
+    pub async fn delete_messages_by_topic(&self, topic: &str) -> Result<Option<TrashEntry>> {
+        let deleted = self.move_to_trash("topic = ?", vec![rbs::to_value(topic)?]).await?;
         
-        let result = self.rb.exec(sql, args).await?;
-        let affected_rows = result.rows_affected;
-        
-        info!("Deleted {} messages for topic: {}", affected_rows, topic);
-        Ok(affected_rows as i64)
+        info!("Deleted {} messages for topic: {}", deleted.as_ref().map_or(0, |entry| entry.count), topic);
+        Ok(deleted)
     }
     
-    /// 刪除指定ID的單筆訊息
-    pub async fn delete_message_by_id(&self, id: i64) -> Result<bool> {
-        let sql = "DELETE FROM messages WHERE id = ?";
-        let args = vec![rbs::to_value(id)?];
-        
-        let result = self.rb.exec(sql, args).await?;
-        let affected_rows = result.rows_affected;
+    /// 刪除指定ID的單筆訊息（移到垃圾桶）
+    pub async fn delete_message_by_id(&self, id: i64) -> Result<Option<TrashEntry>> {
+        let deleted = self.move_to_trash("id = ?", vec![rbs::to_value(id)?]).await?;
         
-        if affected_rows > 0 {
+        if deleted.is_some() {
             info!("Deleted message with id: {}", id);
-            Ok(true)
         } else {
             warn!("No message found with id: {}", id);
-            Ok(false)
         }
+        Ok(deleted)
     }
     
     /// 刪除指定topic和timestamp的訊息（用於沒有ID的情況）
-    pub async fn delete_message_by_topic_and_timestamp(&self, topic: &str, timestamp: &DateTime<Utc>) -> Result<bool> {
-        let sql = "DELETE FROM messages WHERE topic = ? AND timestamp = ?";
+    pub async fn delete_message_by_topic_and_timestamp(&self, topic: &str, timestamp: &DateTime<Utc>) -> Result<Option<TrashEntry>> {
         let args = vec![
             rbs::to_value(topic)?,
             rbs::to_value(timestamp.to_rfc3339())?
         ];
+        let deleted = self.move_to_trash("topic = ? AND timestamp = ?", args).await?;
         
-        let result = self.rb.exec(sql, args).await?;
-        let affected_rows = result.rows_affected;
-        
-        if affected_rows > 0 {
+        if deleted.is_some() {
             info!("Deleted message for topic: {} at timestamp: {}", topic, timestamp);
-            Ok(true)
         } else {
             warn!("No message found for topic: {} at timestamp: {}", topic, timestamp);
-            Ok(false)
         }
+        Ok(deleted)
+    }
+    
+    /// 符合條件的訊息以同一個 batch 移到 trash 資料表，保留原本的 id 以便還原 (書籤也會跟著回來)
+    async fn move_to_trash(&self, condition: &str, mut args: Vec<rbs::Value>) -> Result<Option<TrashEntry>> {
+        let batch = uuid::Uuid::new_v4().to_string();
+        let sql = format!(
+            "INSERT OR REPLACE INTO trash (batch, deleted_at, {columns}) SELECT ?, ?, {columns} FROM messages WHERE {condition}",
+            columns = TRASH_COLUMNS,
+            condition = condition,
+        );
+        args.insert(0, rbs::to_value(Utc::now().to_rfc3339())?);
+        args.insert(0, rbs::to_value(&batch)?);
+        let affected = self.exec_in_transaction(vec![
+            (sql.as_str(), args),
+            ("DELETE FROM messages WHERE id IN (SELECT id FROM trash WHERE batch = ?)", vec![rbs::to_value(&batch)?]),
+        ]).await?;
+        if affected[0] == 0 {
+            return Ok(None);
+        }
+        Ok(self.list_trash_entries(Some(&batch)).await?.into_iter().next())
+    }
+    
+    /// 垃圾桶中的刪除紀錄，新到舊
+    pub async fn list_trash(&self) -> Result<Vec<TrashEntry>> {
+        self.list_trash_entries(None).await
+    }
+    
+    async fn list_trash_entries(&self, batch: Option<&str>) -> Result<Vec<TrashEntry>> {
+        let mut sql = "SELECT batch, MIN(topic) AS topic, COUNT(*) AS count, MIN(timestamp) AS first_time, \
+                       MAX(timestamp) AS last_time, MAX(deleted_at) AS deleted_at FROM trash".to_string();
+        let mut args = Vec::new();
+        if let Some(batch) = batch {
+            sql.push_str(" WHERE batch = ?");
+            args.push(rbs::to_value(batch)?);
+        }
+        sql.push_str(" GROUP BY batch ORDER BY deleted_at DESC");
+        let result = self.rb.query(&sql, args).await?;
+        
+        let parse = |value: &rbs::Value| {
+            value.as_str()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now)
+        };
+        let mut entries = Vec::new();
+        if let rbs::Value::Array(rows) = result {
+            for row in rows {
+                entries.push(TrashEntry {
+                    batch: row["batch"].as_str().unwrap_or("").to_string(),
+                    topic: row["topic"].as_str().unwrap_or("").to_string(),
+                    count: row["count"].as_i64().unwrap_or(0),
+                    first_time: parse(&row["first_time"]),
+                    last_time: parse(&row["last_time"]),
+                    deleted_at: parse(&row["deleted_at"]),
+                });
+            }
+        }
+        Ok(entries)
+    }
+    
+    /// 將整批刪除的訊息放回 messages，回傳還原的筆數。
+    /// id 已被其他內容不同的訊息占用時該筆不還原，留在垃圾桶中
+    pub async fn restore_trash(&self, batch: &str) -> Result<u64> {
+        let insert = format!(
+            "INSERT OR IGNORE INTO messages ({columns}) SELECT {columns} FROM trash WHERE batch = ?",
+            columns = TRASH_COLUMNS,
+        );
+        let affected = self.exec_in_transaction(vec![
+            (insert.as_str(), vec![rbs::to_value(batch)?]),
+            ("DELETE FROM trash WHERE batch = ? AND EXISTS (SELECT 1 FROM messages AS m WHERE m.id = trash.id \
+              AND m.topic = trash.topic AND m.payload = trash.payload AND m.timestamp IS trash.timestamp)",
+             vec![rbs::to_value(batch)?]),
+        ]).await?;
+        let restored = affected[0];
+        
+        info!("Restored {} messages from trash batch {}", restored, batch);
+        if let Some(left) = self.list_trash_entries(Some(batch)).await?.first() {
+            warn!("{} messages of trash batch {} conflict with existing messages and stay in trash", left.count, batch);
+        }
+        Ok(restored)
+    }
+    
+    /// 永久刪除垃圾桶中的一批訊息，連同這些訊息的書籤
+    pub async fn purge_trash(&self, batch: &str) -> Result<u64> {
+        let affected = self.purge_trash_where("batch = ?", rbs::to_value(batch)?).await?;
+        
+        info!("Purged {} messages from trash batch {}", affected, batch);
+        Ok(affected)
+    }
+    
+    async fn purge_trash_where(&self, condition: &str, arg: rbs::Value) -> Result<u64> {
+        let bookmarks = format!("DELETE FROM bookmarks WHERE message_id IN (SELECT id FROM trash WHERE {})", condition);
+        let trash = format!("DELETE FROM trash WHERE {}", condition);
+        let affected = self.exec_in_transaction(vec![
+            (bookmarks.as_str(), vec![arg.clone()]),
+            (trash.as_str(), vec![arg]),
+        ]).await?;
+        Ok(affected[1])
+    }
+    
+    /// 資料庫中儲存的過濾 preset，依名稱排序
+    pub async fn list_presets(&self) -> Result<Vec<FilterPreset>> {
+        let sql = "SELECT name, topic_regex, payload_regex, start_time, end_time, quick_filters FROM filter_presets ORDER BY name";
+        let result = self.rb.query(sql, vec![]).await?;
+        
+        let text = |value: &rbs::Value| value.as_str().unwrap_or("").to_string();
+        let mut presets = Vec::new();
+        if let rbs::Value::Array(rows) = result {
+            for row in rows {
+                presets.push(FilterPreset {
+                    name: text(&row["name"]),
+                    topic_regex: text(&row["topic_regex"]),
+                    payload_regex: text(&row["payload_regex"]),
+                    start_time: text(&row["start_time"]),
+                    end_time: text(&row["end_time"]),
+                    // JSON 文字可能被驅動程式解析成 Map
+                    quick_filters: match &row["quick_filters"] {
+                        rbs::Value::String(json) => serde_json::from_str(json).unwrap_or_default(),
+                        value => serde_json::to_value(value).and_then(serde_json::from_value).unwrap_or_default(),
+                    },
+                    from_config: false,
+                });
+            }
+        }
+        
+        Ok(presets)
+    }
+    
+    /// 新增 preset，同名時覆寫
+    pub async fn save_preset(&self, preset: &FilterPreset) -> Result<()> {
+        let sql = r#"
+            INSERT OR REPLACE INTO filter_presets (name, topic_regex, payload_regex, start_time, end_time, quick_filters, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+        "#;
+        
+        self.exec(sql, vec![
+            rbs::to_value(&preset.name)?,
+            rbs::to_value(&preset.topic_regex)?,
+            rbs::to_value(&preset.payload_regex)?,
+            rbs::to_value(&preset.start_time)?,
+            rbs::to_value(&preset.end_time)?,
+            rbs::to_value(serde_json::to_string(&preset.quick_filters)?)?,
+        ]).await?;
+        
+        info!("Saved filter preset: {}", preset.name);
+        Ok(())
+    }
+    
+    pub async fn delete_preset(&self, name: &str) -> Result<bool> {
+        let result = self.exec("DELETE FROM filter_presets WHERE name = ?", vec![rbs::to_value(name)?]).await?;
+        Ok(result.rows_affected > 0)
+    }
+    
+    pub async fn list_column_sets(&self) -> Result<Vec<ColumnSet>> {
+        let sql = "SELECT topic_pattern, columns FROM message_columns ORDER BY updated_at DESC";
+        let result = self.rb.query(sql, vec![]).await?;
+        
+        let mut sets = Vec::new();
+        if let rbs::Value::Array(rows) = result {
+            for row in rows {
+                sets.push(ColumnSet {
+                    topic: row["topic_pattern"].as_str().unwrap_or("").to_string(),
+                    // JSON 文字可能被驅動程式解析成 Array
+                    columns: match &row["columns"] {
+                        rbs::Value::String(json) => serde_json::from_str(json).unwrap_or_default(),
+                        value => serde_json::to_value(value).and_then(serde_json::from_value).unwrap_or_default(),
+                    },
+                    from_config: false,
+                });
+            }
+        }
+        
+        Ok(sets)
+    }
+    
+    /// 儲存 topic pattern 的欄位設定，同一 pattern 時覆寫
+    pub async fn save_column_set(&self, set: &ColumnSet) -> Result<()> {
+        let sql = r#"
+            INSERT OR REPLACE INTO message_columns (topic_pattern, columns, updated_at)
+            VALUES (?, ?, CURRENT_TIMESTAMP)
+        "#;
+        
+        self.exec(sql, vec![
+            rbs::to_value(&set.topic)?,
+            rbs::to_value(serde_json::to_string(&set.columns)?)?,
+        ]).await?;
+        
+        info!("Saved message columns for {}", set.topic);
+        Ok(())
+    }
+    
+    pub async fn delete_column_set(&self, topic_pattern: &str) -> Result<bool> {
+        let result = self.exec("DELETE FROM message_columns WHERE topic_pattern = ?", vec![rbs::to_value(topic_pattern)?]).await?;
+        Ok(result.rows_affected > 0)
+    }
+    
+    pub async fn insert_alert(&self, alert: &Alert) -> Result<i64> {
+        let sql = r#"
+            INSERT INTO alerts (rule, kind, topic, description, message_id, fired_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+        "#;
+        
+        let result = self.exec(sql, vec![
+            rbs::to_value(&alert.rule)?,
+            rbs::to_value(alert.kind.as_str())?,
+            rbs::to_value(&alert.topic)?,
+            rbs::to_value(&alert.description)?,
+            rbs::to_value(alert.message_id)?,
+            rbs::to_value(alert.fired_at.to_rfc3339())?,
+        ]).await?;
+        
+        Ok(result.last_insert_id.as_i64().unwrap_or(0))
+    }
+    
+    /// 最近觸發的告警，新→舊
+    pub async fn list_alerts(&self, limit: i64) -> Result<Vec<Alert>> {
+        let sql = "SELECT id, rule, kind, topic, description, message_id, fired_at FROM alerts ORDER BY id DESC LIMIT ?";
+        let result = self.rb.query(sql, vec![rbs::to_value(limit)?]).await?;
+        
+        let text = |value: &rbs::Value| value.as_str().unwrap_or("").to_string();
+        let mut alerts = Vec::new();
+        if let rbs::Value::Array(rows) = result {
+            for row in rows {
+                let fired_at = DateTime::parse_from_rfc3339(row["fired_at"].as_str().unwrap_or(""))
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now());
+                alerts.push(Alert {
+                    id: row["id"].as_i64(),
+                    rule: text(&row["rule"]),
+                    kind: AlertKind::parse(row["kind"].as_str().unwrap_or("")).unwrap_or(AlertKind::Condition),
+                    topic: text(&row["topic"]),
+                    description: text(&row["description"]),
+                    message_id: row["message_id"].as_i64(),
+                    fired_at,
+                });
+            }
+        }
+        
+        Ok(alerts)
+    }
+    
+    pub async fn get_message_by_id(&self, id: i64) -> Result<Option<Message>> {
+        let sql = format!("SELECT {} FROM messages WHERE id = ?", self.message_columns);
+        let result = self.rb.query(&sql, vec![rbs::to_value(id)?]).await?;
+        Ok(Self::rows_to_messages(result).into_iter().next())
+    }
+
+    /// 加上書籤或更新備註，保留原本的建立時間
+    pub async fn set_bookmark(&self, message_id: i64, note: &str) -> Result<()> {
+        let sql = r#"
+            INSERT INTO bookmarks (message_id, note, created_at, updated_at) VALUES (?, ?, ?, ?)
+            ON CONFLICT(message_id) DO UPDATE SET note = excluded.note, updated_at = excluded.updated_at
+        "#;
+        let now = Utc::now().to_rfc3339();
+
+        self.exec(sql, vec![
+            rbs::to_value(message_id)?,
+            rbs::to_value(note)?,
+            rbs::to_value(&now)?,
+            rbs::to_value(&now)?,
+        ]).await?;
+
+        info!("Bookmarked message {}", message_id);
+        Ok(())
+    }
+
+    pub async fn delete_bookmark(&self, message_id: i64) -> Result<bool> {
+        let result = self.exec("DELETE FROM bookmarks WHERE message_id = ?", vec![rbs::to_value(message_id)?]).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 所有 topic 的書籤，新→舊；訊息已刪除的書籤不列出
+    pub async fn list_bookmarks(&self) -> Result<Vec<Bookmark>> {
+        let result = self.rb.query("SELECT message_id, note, created_at FROM bookmarks ORDER BY created_at DESC", vec![]).await?;
+        let mut notes = Vec::new();
+        if let rbs::Value::Array(rows) = result {
+            for row in rows {
+                let created_at = DateTime::parse_from_rfc3339(row["created_at"].as_str().unwrap_or(""))
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now());
+                if let Some(id) = row["message_id"].as_i64() {
+                    notes.push((id, row["note"].as_str().unwrap_or("").to_string(), created_at));
+                }
+            }
+        }
+
+        let sql = format!("SELECT {} FROM messages WHERE id IN (SELECT message_id FROM bookmarks)", self.message_columns);
+        let mut messages: HashMap<i64, Message> = Self::rows_to_messages(self.rb.query(&sql, vec![]).await?)
+            .into_iter()
+            .filter_map(|message| Some((message.id?, message)))
+            .collect();
+
+        Ok(notes
+            .into_iter()
+            .filter_map(|(id, note, created_at)| {
+                Some(Bookmark {
+                    message: messages.remove(&id)?,
+                    note,
+                    created_at,
+                })
+            })
+            .collect())
+    }
+
+    /// 指定訊息中有書籤的 id 與備註
+    pub async fn get_bookmark_notes(&self, message_ids: &[i64]) -> Result<HashMap<i64, String>> {
+        if message_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let placeholders = vec!["?"; message_ids.len()].join(", ");
+        let sql = format!("SELECT message_id, note FROM bookmarks WHERE message_id IN ({})", placeholders);
+        let args = message_ids.iter().map(rbs::to_value).collect::<Result<Vec<_>, _>>()?;
+        let result = self.rb.query(&sql, args).await?;
+
+        let mut notes = HashMap::new();
+        if let rbs::Value::Array(rows) = result {
+            for row in rows {
+                if let Some(id) = row["message_id"].as_i64() {
+                    notes.insert(id, row["note"].as_str().unwrap_or("").to_string());
+                }
+            }
+        }
+        Ok(notes)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 測試用的暫存資料庫，離開範圍時 (包含 assert 失敗) 關閉並刪除檔案
+    struct TempRepository {
+        repo: Option<MessageRepository>,
+        path: String,
+    }
+
+    impl std::ops::Deref for TempRepository {
+        type Target = MessageRepository;
+
+        fn deref(&self) -> &MessageRepository {
+            self.repo.as_ref().unwrap()
+        }
+    }
+
+    impl Drop for TempRepository {
+        fn drop(&mut self) {
+            self.repo.take();
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{}", self.path, suffix));
+            }
+        }
+    }
+
+    async fn temp_repository() -> TempRepository {
+        let path = std::env::temp_dir().join(format!("mqtt_log_view_{}.db", uuid::Uuid::new_v4()));
+        let path = path.to_string_lossy().to_string();
+        let repo = MessageRepository::new(&path, Duration::from_secs(1)).await.unwrap();
+        TempRepository { repo: Some(repo), path }
+    }
+
+    #[tokio::test]
+    async fn test_read_only_repository_rejects_writes() {
+        let repo = temp_repository().await;
+        repo.insert_message(&Message::new("a/b".to_string(), "hello".to_string(), 0, false)).await.unwrap();
+
+        let read_only = MessageRepository::open_read_only(&repo.path, Duration::from_secs(1)).await.unwrap();
+        let messages = read_only.get_messages_by_topic("a/b", &FilterCriteria::default()).await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].payload, "hello");
+        assert!(read_only.get_previous_message(&messages[0]).await.unwrap().is_none());
+
+        assert!(read_only.insert_message(&messages[0]).await.is_err());
+        assert!(read_only.delete_messages_by_topic("a/b").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_json_pushdown() {
+        let repo = temp_repository().await;
+        for payload in [r#"{"temp": 35, "status": "fault"}"#, r#"{"temp": "20"}"#, r#"{"status": 1}"#, "not json"] {
+            repo.insert_message(&Message::new("plant/a".to_string(), payload.to_string(), 0, false)).await.unwrap();
+        }
+
+        let count = |text: &str| {
+            let query = Query::parse(text).unwrap();
+            let repo = &repo;
+            async move { repo.query_messages(&query, None).await.unwrap().len() }
+        };
+        assert_eq!(count("$.temp > 30").await, 1);
+        assert_eq!(count("json.$.temp <= 30").await, 1);
+        assert_eq!(count("$.status == 'fault'").await, 1);
+        assert_eq!(count("$.status = 1").await, 1);
+        assert_eq!(count("$.status exists OR payload:json").await, 3);
+        assert_eq!(count("NOT $.temp exists").await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_alert_round_trip() {
+        let repo = temp_repository().await;
+        let id = repo.insert_message(&Message::new("plant/a".to_string(), "{}".to_string(), 0, false)).await.unwrap();
+
+        let mut alert = Alert {
+            id: None,
+            rule: "plant silent".to_string(),
+            kind: AlertKind::Silence,
+            topic: "plant/a".to_string(),
+            description: "No message for 6m 0s".to_string(),
+            message_id: Some(id),
+            fired_at: Utc::now(),
+        };
+        alert.id = Some(repo.insert_alert(&alert).await.unwrap());
+        let alerts = repo.list_alerts(10).await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].fired_at.timestamp(), alert.fired_at.timestamp());
+        assert_eq!(alerts[0].kind, alert.kind);
+        assert_eq!(alerts[0].message_id, alert.message_id);
+        assert_eq!(repo.get_message_by_id(id).await.unwrap().unwrap().topic, "plant/a");
+    }
+
+    #[tokio::test]
+    async fn test_bookmark_notes() {
+        let repo = temp_repository().await;
+        let first = repo.insert_message(&Message::new("plant/a".to_string(), "1".to_string(), 0, false)).await.unwrap();
+        let second = repo.insert_message(&Message::new("plant/b".to_string(), "2".to_string(), 0, false)).await.unwrap();
+
+        repo.set_bookmark(first, "").await.unwrap();
+        repo.set_bookmark(first, "pump tripped").await.unwrap();
+        repo.set_bookmark(second, "").await.unwrap();
+        let notes = repo.get_bookmark_notes(&[first, second, second + 1]).await.unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[&first], "pump tripped");
+
+        // 訊息刪除後書籤不再列出
+        repo.delete_message_by_id(second).await.unwrap();
+        let bookmarks = repo.list_bookmarks().await.unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].message.topic, "plant/a");
+        assert!(repo.delete_bookmark(first).await.unwrap());
+        assert!(repo.list_bookmarks().await.unwrap().is_empty());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_trash_restore_and_purge() {
+        let repo = temp_repository().await;
+        let first = repo.insert_message(&Message::new("plant/a".to_string(), r#"{"temp": 1}"#.to_string(), 0, false)).await.unwrap();
+        repo.insert_message(&Message::new("plant/a".to_string(), "2".to_string(), 0, false)).await.unwrap();
+        let single = repo.insert_message(&Message::new("plant/b".to_string(), "3".to_string(), 0, false)).await.unwrap();
+        repo.set_bookmark(first, "keep").await.unwrap();
+
+        let topic = repo.delete_messages_by_topic("plant/a").await.unwrap().unwrap();
+        assert_eq!((topic.topic.as_str(), topic.count), ("plant/a", 2));
+        assert!(repo.delete_message_by_id(single).await.unwrap().is_some());
+        assert!(repo.delete_message_by_id(single).await.unwrap().is_none());
+        assert!(repo.get_messages_by_topic("plant/a", &FilterCriteria::default()).await.unwrap().is_empty());
+        assert_eq!(repo.list_trash().await.unwrap().len(), 2);
+
+        // 還原後 id、payload 與書籤都回來
+        assert_eq!(repo.restore_trash(&topic.batch).await.unwrap(), 2);
+        let restored = repo.get_message_by_id(first).await.unwrap().unwrap();
+        assert_eq!(restored.payload, r#"{"temp":1}"#);
+        assert_eq!(repo.list_bookmarks().await.unwrap().len(), 1);
+
+        let trash = repo.list_trash().await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(repo.purge_trash(&trash[0].batch).await.unwrap(), 1);
+        assert!(repo.list_trash().await.unwrap().is_empty());
+        assert!(repo.get_message_by_id(single).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_trash() {
+        let repo = temp_repository().await;
+        let old = repo.insert_message(&Message::new("plant/a".to_string(), "1".to_string(), 0, false)).await.unwrap();
+        let recent = repo.insert_message(&Message::new("plant/b".to_string(), "2".to_string(), 0, false)).await.unwrap();
+        repo.set_bookmark(old, "expired").await.unwrap();
+        repo.set_bookmark(recent, "kept").await.unwrap();
+        let expired = repo.delete_message_by_id(old).await.unwrap().unwrap();
+        repo.delete_message_by_id(recent).await.unwrap().unwrap();
+
+        // 把其中一批的刪除時間往前推到保留天數之外
+        let deleted_at = (Utc::now() - chrono::Duration::days(31)).to_rfc3339();
+        repo.exec("UPDATE trash SET deleted_at = ? WHERE batch = ?", vec![rbs::to_value(deleted_at).unwrap(), rbs::to_value(&expired.batch).unwrap()]).await.unwrap();
+
+        assert_eq!(repo.purge_expired_trash(30).await.unwrap(), 1);
+        let trash = repo.list_trash().await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].topic, "plant/b");
+        assert!(repo.get_bookmark_notes(&[old]).await.unwrap().is_empty());
+
+        // 還原未過期的一批，書籤仍在
+        assert_eq!(repo.restore_trash(&trash[0].batch).await.unwrap(), 1);
+        assert!(repo.list_trash().await.unwrap().is_empty());
+        assert_eq!(repo.get_bookmark_notes(&[recent]).await.unwrap()[&recent], "kept");
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_moves_old_messages_to_trash() {
+        let repo = temp_repository().await;
+        let old = repo.insert_message(&Message::new("plant/a".to_string(), "1".to_string(), 0, false)).await.unwrap();
+        let bookmarked = repo.insert_message(&Message::new("plant/a".to_string(), "2".to_string(), 0, false)).await.unwrap();
+        let recent = repo.insert_message(&Message::new("plant/b".to_string(), "3".to_string(), 0, false)).await.unwrap();
+        repo.set_bookmark(bookmarked, "evidence").await.unwrap();
+        repo.exec("UPDATE messages SET created_at = datetime('now', '-31 days') WHERE id != ?", vec![rbs::to_value(recent).unwrap()]).await.unwrap();
+
+        // 過期訊息進垃圾桶而非直接刪除，有書籤的留在 messages
+        assert_eq!(repo.cleanup_old_messages(30).await.unwrap(), 1);
+        assert!(repo.get_message_by_id(old).await.unwrap().is_none());
+        assert!(repo.get_message_by_id(bookmarked).await.unwrap().is_some());
+        assert!(repo.get_message_by_id(recent).await.unwrap().is_some());
+        let trash = repo.list_trash().await.unwrap();
+        assert_eq!((trash.len(), trash[0].count), (1, 1));
+        assert_eq!(repo.cleanup_old_messages(30).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_preset_round_trip() {
+        let repo = temp_repository().await;
+
+        let mut preset = FilterPreset {
+            name: "plant errors".to_string(),
+            topic_regex: "^plant/".to_string(),
+            payload_regex: String::new(),
+            start_time: "-1h".to_string(),
+            end_time: String::new(),
+            quick_filters: [("DEBUG".to_string(), false)].into_iter().collect(),
+            from_config: false,
+        };
+        repo.save_preset(&preset).await.unwrap();
+        preset.payload_regex = "ALARM".to_string();
+        repo.save_preset(&preset).await.unwrap();
+
+        assert_eq!(repo.list_presets().await.unwrap(), vec![preset]);
+        assert!(repo.delete_preset("plant errors").await.unwrap());
+        assert!(repo.list_presets().await.unwrap().is_empty());
+
+        let set = ColumnSet {
+            topic: "plant/+/telemetry".to_string(),
+            columns: vec![crate::ui::PayloadColumn::parse("$.temperature Temp").unwrap()],
+            from_config: false,
+        };
+        repo.save_column_set(&set).await.unwrap();
+        assert_eq!(repo.list_column_sets().await.unwrap(), vec![set]);
+        assert!(repo.delete_column_set("plant/+/telemetry").await.unwrap());
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+use super::{bytes_to_hex, PayloadDecoder};
+
+pub struct CborDecoder;
+
+impl PayloadDecoder for CborDecoder {
+    fn name(&self) -> &str {
+        "cbor"
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<Value> {
+        let value: ciborium::Value = ciborium::de::from_reader(payload)?;
+        Ok(to_json(value))
+    }
+}
+
+fn to_json(value: ciborium::Value) -> Value {
+    match value {
+        ciborium::Value::Null => Value::Null,
+        ciborium::Value::Bool(b) => Value::Bool(b),
+        ciborium::Value::Integer(i) => {
+            let n = i128::from(i);
+            if let Ok(n) = i64::try_from(n) {
+                Value::from(n)
+            } else if let Ok(n) = u64::try_from(n) {
+                Value::from(n)
+            } else {
+                Value::String(n.to_string())
+            }
+        }
+        // NaN/Infinity 無法表示為 JSON，轉成 null
+        ciborium::Value::Float(f) => serde_json::Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null),
+        ciborium::Value::Text(s) => Value::String(s),
+        ciborium::Value::Bytes(bytes) => Value::String(bytes_to_hex(&bytes)),
+        ciborium::Value::Array(items) => Value::Array(items.into_iter().map(to_json).collect()),
+        ciborium::Value::Map(entries) => {
+            let mut map = Map::new();
+            for (key, value) in entries {
+                let key = match key {
+                    ciborium::Value::Text(s) => s,
+                    other => to_json(other).to_string(),
+                };
+                map.insert(key, to_json(value));
+            }
+            Value::Object(map)
+        }
+        ciborium::Value::Tag(tag, value) => {
+            let mut map = Map::new();
+            map.insert("tag".to_string(), Value::from(tag));
+            map.insert("value".to_string(), to_json(*value));
+            Value::Object(map)
+        }
+        _ => Value::Null,
+    }
+}
@@ -0,0 +1,170 @@
+pub mod cbor;
+pub mod msgpack;
+pub mod protobuf;
+pub mod sparkplug;
+
+use std::sync::Arc;
+use anyhow::{bail, Result};
+use serde_json::Value;
+use tracing::{debug, info};
+
+use crate::config::DecodersConfig;
+use crate::utils::topic_pattern::topic_matches;
+
+pub use cbor::CborDecoder;
+pub use msgpack::MsgPackDecoder;
+pub use protobuf::{ProtobufDecoder, ProtoSchemas};
+pub use sparkplug::{SparkplugDecoder, SparkplugTopic};
+
+/// 將二進位 payload 轉成 JSON 的解碼器
+pub trait PayloadDecoder: Send + Sync {
+    /// 存進資料庫 encoding 欄位的名稱
+    fn name(&self) -> &str;
+    fn decode(&self, payload: &[u8]) -> Result<Value>;
+
+    /// 需要 topic 或跨訊息狀態的解碼器 (例如 Sparkplug B) 覆寫此方法
+    fn decode_message(&self, _topic: &str, payload: &[u8]) -> Result<DecodedPayload> {
+        Ok(DecodedPayload {
+            text: self.decode(payload)?.to_string(),
+            encoding: Some(self.name().to_string()),
+            marker: None,
+        })
+    }
+}
+
+/// 解碼後要存入資料庫的 payload
+#[derive(Debug, Clone)]
+pub struct DecodedPayload {
+    pub text: String,
+    pub encoding: Option<String>, // None 表示原樣 (UTF-8 文字) 儲存
+    pub marker: Option<String>,   // 訊息列表上的標記，例如 NBIRTH、SEQ GAP
+}
+
+/// 依 topic pattern 挑選解碼器，第一個符合的 mapping 生效
+#[derive(Clone, Default)]
+pub struct DecoderRegistry {
+    mappings: Vec<(String, Arc<dyn PayloadDecoder>)>,
+}
+
+impl DecoderRegistry {
+    pub fn from_config(config: &DecodersConfig) -> Result<Self> {
+        let schemas = if config.proto_files.is_empty() {
+            None
+        } else {
+            Some(ProtoSchemas::load(&config.proto_files, &config.proto_include_paths)?)
+        };
+
+        let mut registry = Self::default();
+        for mapping in &config.mappings {
+            let decoder: Arc<dyn PayloadDecoder> = match mapping.decoder.to_ascii_lowercase().as_str() {
+                "msgpack" | "messagepack" => Arc::new(MsgPackDecoder),
+                "cbor" => Arc::new(CborDecoder),
+                "sparkplug" | "sparkplug_b" | "spb" => Arc::new(SparkplugDecoder::default()),
+                "protobuf" | "proto" => {
+                    let schemas = match &schemas {
+                        Some(schemas) => schemas,
+                        None => bail!("Decoder mapping '{}' uses protobuf but no proto_files are configured", mapping.topic),
+                    };
+                    let message = match &mapping.message {
+                        Some(message) => message,
+                        None => bail!("Decoder mapping '{}' uses protobuf but has no message name", mapping.topic),
+                    };
+                    Arc::new(ProtobufDecoder::new(schemas, message)?)
+                }
+                other => bail!("Unknown decoder '{}' for topic '{}'", other, mapping.topic),
+            };
+
+            info!("Decoder {} mapped to topic pattern {}", decoder.name(), mapping.topic);
+            registry.register(&mapping.topic, decoder);
+        }
+
+        if config.sparkplug {
+            let pattern = format!("{}/#", sparkplug::SPARKPLUG_NAMESPACE);
+            info!("Decoder sparkplug mapped to topic pattern {}", pattern);
+            registry.register(&pattern, Arc::new(SparkplugDecoder::default()));
+        }
+
+        Ok(registry)
+    }
+
+    pub fn register(&mut self, topic_pattern: &str, decoder: Arc<dyn PayloadDecoder>) {
+        self.mappings.push((topic_pattern.to_string(), decoder));
+    }
+
+    pub fn decoder_for(&self, topic: &str) -> Option<&Arc<dyn PayloadDecoder>> {
+        self.mappings
+            .iter()
+            .find(|(pattern, _)| topic_matches(pattern, topic))
+            .map(|(_, decoder)| decoder)
+    }
+
+    /// 解碼失敗或沒有對應的解碼器時，退回原本的 UTF-8 (lossy) 文字
+    pub fn decode(&self, topic: &str, payload: &[u8]) -> DecodedPayload {
+        if let Some(decoder) = self.decoder_for(topic) {
+            match decoder.decode_message(topic, payload) {
+                Ok(decoded) => return decoded,
+                Err(e) => {
+                    debug!("{} decode failed for topic {}: {}", decoder.name(), topic, e);
+                }
+            }
+        }
+
+        DecodedPayload {
+            text: String::from_utf8_lossy(payload).to_string(),
+            encoding: None,
+            marker: None,
+        }
+    }
+}
+
+impl DecodedPayload {
+    /// 存入的文字不是原始 payload 時 (解碼成 JSON 或非 UTF-8)，以 base64 保留原始位元組以便之後重新解碼
+    pub fn raw_payload(&self, payload: &[u8]) -> Option<String> {
+        use base64::Engine;
+        if self.encoding.is_some() || std::str::from_utf8(payload).is_err() {
+            Some(base64::engine::general_purpose::STANDARD.encode(payload))
+        } else {
+            None
+        }
+    }
+}
+
+/// 二進位資料在 JSON 中以十六進位字串表示
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_decodes_matching_topics() {
+        let mut registry = DecoderRegistry::default();
+        registry.register("devices/+/msgpack", Arc::new(MsgPackDecoder));
+        registry.register("sensors/#", Arc::new(CborDecoder));
+
+        let mut msgpack = Vec::new();
+        let value = rmpv::Value::Map(vec![("temp".into(), rmpv::Value::F64(21.5))]);
+        rmpv::encode::write_value(&mut msgpack, &value).unwrap();
+        let decoded = registry.decode("devices/a/msgpack", &msgpack);
+        assert_eq!(decoded.text, r#"{"temp":21.5}"#);
+        assert_eq!(decoded.encoding.as_deref(), Some("msgpack"));
+
+        let mut cbor = Vec::new();
+        ciborium::ser::into_writer(&vec![1, 2, 3], &mut cbor).unwrap();
+        let decoded = registry.decode("sensors/x", &cbor);
+        assert_eq!(decoded.text, "[1,2,3]");
+        assert_eq!(decoded.encoding.as_deref(), Some("cbor"));
+        assert_eq!(decoded.raw_payload(&cbor).as_deref(), Some("gwECAw=="));
+
+        // 沒有對應的解碼器或解碼失敗時保留原始文字
+        let decoded = registry.decode("other", b"hello");
+        assert_eq!(decoded.text, "hello");
+        assert!(decoded.encoding.is_none());
+        assert!(decoded.raw_payload(b"hello").is_none());
+        let decoded = registry.decode("sensors/x", b"\xff\xff");
+        assert!(decoded.encoding.is_none());
+        assert_eq!(decoded.raw_payload(b"\xff\xff").as_deref(), Some("//8="));
+    }
+}
@@ -0,0 +1,59 @@
+use anyhow::{bail, Result};
+use serde_json::{Map, Value};
+
+use super::{bytes_to_hex, PayloadDecoder};
+
+pub struct MsgPackDecoder;
+
+impl PayloadDecoder for MsgPackDecoder {
+    fn name(&self) -> &str {
+        "msgpack"
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<Value> {
+        let mut reader = payload;
+        let value = rmpv::decode::read_value(&mut reader)?;
+        if !reader.is_empty() {
+            bail!("{} trailing bytes after MessagePack value", reader.len());
+        }
+        Ok(to_json(value))
+    }
+}
+
+fn to_json(value: rmpv::Value) -> Value {
+    match value {
+        rmpv::Value::Nil => Value::Null,
+        rmpv::Value::Boolean(b) => Value::Bool(b),
+        rmpv::Value::Integer(i) => match (i.as_i64(), i.as_u64()) {
+            (Some(n), _) => Value::from(n),
+            (_, Some(n)) => Value::from(n),
+            _ => Value::Null,
+        },
+        rmpv::Value::F32(f) => Value::from(f as f64),
+        rmpv::Value::F64(f) => Value::from(f),
+        rmpv::Value::String(s) => match s.as_str() {
+            Some(text) => Value::String(text.to_string()),
+            None => Value::String(String::from_utf8_lossy(s.as_bytes()).to_string()),
+        },
+        rmpv::Value::Binary(bytes) => Value::String(bytes_to_hex(&bytes)),
+        rmpv::Value::Array(items) => Value::Array(items.into_iter().map(to_json).collect()),
+        rmpv::Value::Map(entries) => {
+            let mut map = Map::new();
+            for (key, value) in entries {
+                // JSON 物件的 key 只能是字串
+                let key = match key {
+                    rmpv::Value::String(s) => s.as_str().map(|s| s.to_string()).unwrap_or_default(),
+                    other => to_json(other).to_string(),
+                };
+                map.insert(key, to_json(value));
+            }
+            Value::Object(map)
+        }
+        rmpv::Value::Ext(kind, data) => {
+            let mut map = Map::new();
+            map.insert("ext_type".to_string(), Value::from(kind));
+            map.insert("data".to_string(), Value::String(bytes_to_hex(&data)));
+            Value::Object(map)
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+use anyhow::{anyhow, Context, Result};
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
+use serde_json::Value;
+
+use super::PayloadDecoder;
+
+/// 從 .proto 或已編譯的 descriptor set (.pb / .desc) 載入的 schema
+#[derive(Clone)]
+pub struct ProtoSchemas {
+    pool: DescriptorPool,
+}
+
+impl ProtoSchemas {
+    pub fn load(files: &[String], include_paths: &[String]) -> Result<Self> {
+        let mut pool = DescriptorPool::new();
+
+        for file in files {
+            let path = Path::new(file);
+            let is_proto = path.extension().and_then(|e| e.to_str()) == Some("proto");
+
+            if is_proto {
+                // protox 要求檔名相對於某個 include 路徑，檔案所在目錄一律加入
+                let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+                let file_name = path
+                    .file_name()
+                    .ok_or_else(|| anyhow!("Invalid proto file path: {}", file))?;
+                let mut includes = vec![parent.to_path_buf()];
+                includes.extend(include_paths.iter().map(|p| Path::new(p).to_path_buf()));
+
+                let descriptor_set = protox::compile([file_name], includes)
+                    .map_err(|e| anyhow!("Failed to compile {}: {}", file, e))?;
+                pool.add_file_descriptor_set(descriptor_set)
+                    .with_context(|| format!("Failed to load {}", file))?;
+            } else {
+                let bytes = std::fs::read(path).with_context(|| format!("Failed to read descriptor set {}", file))?;
+                pool.decode_file_descriptor_set(bytes.as_slice())
+                    .with_context(|| format!("Failed to load descriptor set {}", file))?;
+            }
+        }
+
+        Ok(Self { pool })
+    }
+
+    pub fn message(&self, name: &str) -> Result<MessageDescriptor> {
+        self.pool
+            .get_message_by_name(name)
+            .ok_or_else(|| anyhow!("Protobuf message '{}' not found in configured schemas", name))
+    }
+}
+
+pub struct ProtobufDecoder {
+    descriptor: MessageDescriptor,
+}
+
+impl ProtobufDecoder {
+    pub fn new(schemas: &ProtoSchemas, message_name: &str) -> Result<Self> {
+        Ok(Self {
+            descriptor: schemas.message(message_name)?,
+        })
+    }
+}
+
+impl PayloadDecoder for ProtobufDecoder {
+    fn name(&self) -> &str {
+        "protobuf"
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<Value> {
+        let message = DynamicMessage::decode(self.descriptor.clone(), payload)?;
+        Ok(serde_json::to_value(&message)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+    use prost_reflect::Value as ProtoValue;
+
+    #[test]
+    fn test_proto_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("mqtt_log_view_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let proto = dir.join("telemetry.proto");
+        std::fs::write(&proto, r#"
+            syntax = "proto3";
+            package plant;
+            message Reading {
+                string sensor = 1;
+                double value = 2;
+                repeated int32 flags = 3;
+            }
+        "#).unwrap();
+
+        let schemas = ProtoSchemas::load(&[proto.to_string_lossy().to_string()], &[]);
+        let _ = std::fs::remove_dir_all(&dir);
+        let schemas = schemas.unwrap();
+        assert!(ProtobufDecoder::new(&schemas, "plant.Missing").is_err());
+
+        let descriptor = schemas.message("plant.Reading").unwrap();
+        let mut reading = DynamicMessage::new(descriptor);
+        reading.set_field_by_name("sensor", ProtoValue::String("boiler".to_string()));
+        reading.set_field_by_name("value", ProtoValue::F64(81.5));
+        reading.set_field_by_name("flags", ProtoValue::List(vec![ProtoValue::I32(1), ProtoValue::I32(3)]));
+        let payload = reading.encode_to_vec();
+
+        let decoder = ProtobufDecoder::new(&schemas, "plant.Reading").unwrap();
+        let decoded = decoder.decode_message("plant/boiler", &payload).unwrap();
+        assert_eq!(decoded.encoding.as_deref(), Some("protobuf"));
+        assert_eq!(
+            serde_json::from_str::<Value>(&decoded.text).unwrap(),
+            serde_json::json!({"sensor": "boiler", "value": 81.5, "flags": [1, 3]})
+        );
+        // 保留原始位元組，schema 有誤時可以重新解碼
+        assert!(decoded.raw_payload(&payload).is_some());
+        assert!(decoder.decode(b"\xff\xff\xff").is_err());
+    }
+}
@@ -0,0 +1,375 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use anyhow::{anyhow, Result};
+use prost::Message;
+use serde_json::{json, Map, Value};
+
+use super::{bytes_to_hex, DecodedPayload, PayloadDecoder};
+
+pub const SPARKPLUG_NAMESPACE: &str = "spBv1.0";
+
+// Sparkplug B payload (sparkplug_b.proto)；DataSet / Template 等複合型別不解析，以 null 顯示
+#[derive(Clone, PartialEq, Message)]
+pub struct Payload {
+    #[prost(uint64, optional, tag = "1")]
+    pub timestamp: Option<u64>,
+    #[prost(message, repeated, tag = "2")]
+    pub metrics: Vec<Metric>,
+    #[prost(uint64, optional, tag = "3")]
+    pub seq: Option<u64>,
+    #[prost(string, optional, tag = "4")]
+    pub uuid: Option<String>,
+    #[prost(bytes = "vec", optional, tag = "5")]
+    pub body: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Metric {
+    #[prost(string, optional, tag = "1")]
+    pub name: Option<String>,
+    #[prost(uint64, optional, tag = "2")]
+    pub alias: Option<u64>,
+    #[prost(uint64, optional, tag = "3")]
+    pub timestamp: Option<u64>,
+    #[prost(uint32, optional, tag = "4")]
+    pub datatype: Option<u32>,
+    #[prost(bool, optional, tag = "5")]
+    pub is_historical: Option<bool>,
+    #[prost(bool, optional, tag = "6")]
+    pub is_transient: Option<bool>,
+    #[prost(bool, optional, tag = "7")]
+    pub is_null: Option<bool>,
+    #[prost(oneof = "MetricValue", tags = "10, 11, 12, 13, 14, 15, 16")]
+    pub value: Option<MetricValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum MetricValue {
+    #[prost(uint32, tag = "10")]
+    Int(u32),
+    #[prost(uint64, tag = "11")]
+    Long(u64),
+    #[prost(float, tag = "12")]
+    Float(f32),
+    #[prost(double, tag = "13")]
+    Double(f64),
+    #[prost(bool, tag = "14")]
+    Boolean(bool),
+    #[prost(string, tag = "15")]
+    String(String),
+    #[prost(bytes = "vec", tag = "16")]
+    Bytes(Vec<u8>),
+}
+
+/// spBv1.0/{group}/{message_type}/{edge_node}[/{device}]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparkplugTopic {
+    pub group: String,
+    pub message_type: String,
+    pub edge_node: String,
+    pub device: Option<String>,
+}
+
+impl SparkplugTopic {
+    /// STATE 等非 edge node 的 topic 回傳 None
+    pub fn parse(topic: &str) -> Option<Self> {
+        let parts: Vec<&str> = topic.split('/').collect();
+        if parts.len() < 4 || parts.len() > 5 || parts[0] != SPARKPLUG_NAMESPACE {
+            return None;
+        }
+        if !matches!(parts[2], "NBIRTH" | "NDEATH" | "NDATA" | "NCMD" | "DBIRTH" | "DDEATH" | "DDATA" | "DCMD") {
+            return None;
+        }
+
+        Some(Self {
+            group: parts[1].to_string(),
+            message_type: parts[2].to_string(),
+            edge_node: parts[3].to_string(),
+            device: parts.get(4).map(|d| d.to_string()),
+        })
+    }
+
+    pub fn node_key(&self) -> String {
+        format!("{}/{}", self.group, self.edge_node)
+    }
+
+    pub fn is_birth(&self) -> bool {
+        self.message_type.ends_with("BIRTH")
+    }
+
+    pub fn is_death(&self) -> bool {
+        self.message_type.ends_with("DEATH")
+    }
+
+    /// Topic 清單用的樹狀標籤：node 層顯示 group/node，device 層縮排在其下
+    pub fn display_label(&self) -> String {
+        match &self.device {
+            Some(device) => format!(" └ {} {}", device, self.message_type),
+            None => format!("{}/{} {}", self.group, self.edge_node, self.message_type),
+        }
+    }
+
+    // 同一個 node/device 內依生命週期排列
+    fn type_order(&self) -> u8 {
+        match &self.message_type[1..] {
+            "BIRTH" => 0,
+            "DATA" => 1,
+            "CMD" => 2,
+            _ => 3,
+        }
+    }
+
+    fn sort_cmp(&self, other: &Self) -> Ordering {
+        (&self.group, &self.edge_node, self.device.is_some(), &self.device, self.type_order())
+            .cmp(&(&other.group, &other.edge_node, other.device.is_some(), &other.device, other.type_order()))
+    }
+}
+
+/// 將 Sparkplug topic 依 group / edge node / device 排在一起，其餘 topic 維持原本順序接在後面
+pub fn group_topics<T>(items: &mut Vec<T>, topic_of: impl Fn(&T) -> &str) {
+    let mut sparkplug = Vec::new();
+    let mut others = Vec::new();
+    for item in items.drain(..) {
+        match SparkplugTopic::parse(topic_of(&item)) {
+            Some(topic) => sparkplug.push((topic, item)),
+            None => others.push(item),
+        }
+    }
+
+    sparkplug.sort_by(|(a, _), (b, _)| a.sort_cmp(b));
+    items.extend(sparkplug.into_iter().map(|(_, item)| item));
+    items.extend(others);
+}
+
+#[derive(Default)]
+struct NodeState {
+    aliases: HashMap<u64, (String, Option<u32>)>, // alias -> (metric 名稱, datatype)
+    last_seq: Option<u64>,
+}
+
+/// Sparkplug B 解碼器，依 edge node 追蹤 NBIRTH/DBIRTH 宣告的 alias 與 seq
+#[derive(Default)]
+pub struct SparkplugDecoder {
+    nodes: Mutex<HashMap<String, NodeState>>,
+}
+
+impl PayloadDecoder for SparkplugDecoder {
+    fn name(&self) -> &str {
+        "sparkplug"
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<Value> {
+        let payload = Payload::decode(payload)?;
+        Ok(payload_to_json(&payload, &HashMap::new()))
+    }
+
+    fn decode_message(&self, topic: &str, payload: &[u8]) -> Result<DecodedPayload> {
+        let sparkplug_topic = SparkplugTopic::parse(topic)
+            .ok_or_else(|| anyhow!("Not a Sparkplug B edge node topic: {}", topic))?;
+        let payload = Payload::decode(payload)?;
+
+        let mut nodes = self.nodes.lock().unwrap();
+        let node_key = sparkplug_topic.node_key();
+        if sparkplug_topic.message_type == "NBIRTH" {
+            // 新的 NBIRTH 代表 node 重新上線，alias 與 seq 重新計算
+            nodes.remove(&node_key);
+        }
+        let node = nodes.entry(node_key.clone()).or_default();
+
+        if sparkplug_topic.is_birth() {
+            for metric in &payload.metrics {
+                if let (Some(name), Some(alias)) = (&metric.name, metric.alias) {
+                    node.aliases.insert(alias, (name.clone(), metric.datatype));
+                }
+            }
+        }
+
+        let mut markers = Vec::new();
+        if sparkplug_topic.is_birth() || sparkplug_topic.is_death() {
+            markers.push(sparkplug_topic.message_type.clone());
+        }
+
+        // NDEATH 由 broker 代發 (will message)，沒有 seq
+        if sparkplug_topic.message_type != "NDEATH" {
+            if let Some(seq) = payload.seq {
+                if sparkplug_topic.message_type != "NBIRTH" {
+                    if let Some(last) = node.last_seq {
+                        let expected = (last + 1) % 256;
+                        if seq != expected {
+                            markers.push(format!("SEQ GAP {}→{}", expected, seq));
+                        }
+                    }
+                }
+                node.last_seq = Some(seq);
+            }
+        }
+
+        let value = payload_to_json(&payload, &node.aliases);
+        if sparkplug_topic.message_type == "NDEATH" {
+            nodes.remove(&node_key);
+        }
+
+        Ok(DecodedPayload {
+            text: value.to_string(),
+            encoding: Some(self.name().to_string()),
+            marker: if markers.is_empty() { None } else { Some(markers.join(" ")) },
+        })
+    }
+}
+
+fn payload_to_json(payload: &Payload, aliases: &HashMap<u64, (String, Option<u32>)>) -> Value {
+    let mut map = Map::new();
+    if let Some(timestamp) = payload.timestamp {
+        map.insert("timestamp".to_string(), json!(timestamp));
+    }
+    if let Some(seq) = payload.seq {
+        map.insert("seq".to_string(), json!(seq));
+    }
+    let metrics: Vec<Value> = payload.metrics.iter().map(|m| metric_to_json(m, aliases)).collect();
+    map.insert("metrics".to_string(), Value::Array(metrics));
+    if let Some(uuid) = &payload.uuid {
+        map.insert("uuid".to_string(), json!(uuid));
+    }
+    if let Some(body) = &payload.body {
+        map.insert("body".to_string(), json!(bytes_to_hex(body)));
+    }
+    Value::Object(map)
+}
+
+fn metric_to_json(metric: &Metric, aliases: &HashMap<u64, (String, Option<u32>)>) -> Value {
+    let known = metric.alias.and_then(|alias| aliases.get(&alias));
+    let name = match (&metric.name, known, metric.alias) {
+        (Some(name), _, _) => name.clone(),
+        (None, Some((name, _)), _) => name.clone(),
+        (None, None, Some(alias)) => format!("alias:{}", alias),
+        (None, None, None) => String::new(),
+    };
+    // DATA 訊息通常只帶 alias，型別從 BIRTH 取得
+    let datatype = metric.datatype.or_else(|| known.and_then(|(_, datatype)| *datatype));
+
+    let mut map = Map::new();
+    map.insert("name".to_string(), json!(name));
+    if let Some(alias) = metric.alias {
+        map.insert("alias".to_string(), json!(alias));
+    }
+    if let Some(datatype) = datatype {
+        map.insert("type".to_string(), json!(datatype_name(datatype)));
+    }
+    let value = if metric.is_null == Some(true) {
+        Value::Null
+    } else {
+        metric.value.as_ref().map(|v| metric_value_to_json(v, datatype)).unwrap_or(Value::Null)
+    };
+    map.insert("value".to_string(), value);
+    if let Some(timestamp) = metric.timestamp {
+        map.insert("timestamp".to_string(), json!(timestamp));
+    }
+    if metric.is_historical == Some(true) {
+        map.insert("historical".to_string(), json!(true));
+    }
+    Value::Object(map)
+}
+
+fn metric_value_to_json(value: &MetricValue, datatype: Option<u32>) -> Value {
+    match value {
+        // 有號整數以補數存放在 uint32 / uint64
+        MetricValue::Int(v) => match datatype {
+            Some(1) => json!(*v as i8),
+            Some(2) => json!(*v as i16),
+            Some(3) => json!(*v as i32),
+            _ => json!(v),
+        },
+        MetricValue::Long(v) => match datatype {
+            Some(4) => json!(*v as i64),
+            _ => json!(v),
+        },
+        MetricValue::Float(v) => serde_json::Number::from_f64(*v as f64).map(Value::Number).unwrap_or(Value::Null),
+        MetricValue::Double(v) => serde_json::Number::from_f64(*v).map(Value::Number).unwrap_or(Value::Null),
+        MetricValue::Boolean(v) => json!(v),
+        MetricValue::String(v) => json!(v),
+        MetricValue::Bytes(v) => json!(bytes_to_hex(v)),
+    }
+}
+
+fn datatype_name(datatype: u32) -> &'static str {
+    match datatype {
+        1 => "Int8",
+        2 => "Int16",
+        3 => "Int32",
+        4 => "Int64",
+        5 => "UInt8",
+        6 => "UInt16",
+        7 => "UInt32",
+        8 => "UInt64",
+        9 => "Float",
+        10 => "Double",
+        11 => "Boolean",
+        12 => "String",
+        13 => "DateTime",
+        14 => "Text",
+        15 => "UUID",
+        16 => "DataSet",
+        17 => "Bytes",
+        18 => "File",
+        19 => "Template",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(name: Option<&str>, alias: u64, value: MetricValue) -> Metric {
+        Metric {
+            name: name.map(|n| n.to_string()),
+            alias: Some(alias),
+            datatype: name.map(|_| 9),
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    fn encode(seq: u64, metrics: Vec<Metric>) -> Vec<u8> {
+        Payload { timestamp: Some(1), metrics, seq: Some(seq), ..Default::default() }.encode_to_vec()
+    }
+
+    #[test]
+    fn test_aliases_and_sequence_gaps() {
+        let decoder = SparkplugDecoder::default();
+
+        let birth = encode(0, vec![metric(Some("Temperature"), 7, MetricValue::Float(20.0))]);
+        let decoded = decoder.decode_message("spBv1.0/Plant/NBIRTH/Edge1", &birth).unwrap();
+        assert_eq!(decoded.marker.as_deref(), Some("NBIRTH"));
+
+        let data = encode(1, vec![metric(None, 7, MetricValue::Float(21.5))]);
+        let decoded = decoder.decode_message("spBv1.0/Plant/NDATA/Edge1", &data).unwrap();
+        assert!(decoded.marker.is_none());
+        let value: Value = serde_json::from_str(&decoded.text).unwrap();
+        assert_eq!(value["metrics"][0]["name"], "Temperature");
+        assert_eq!(value["metrics"][0]["type"], "Float");
+        assert_eq!(value["metrics"][0]["value"], 21.5);
+
+        let data = encode(4, vec![]);
+        let decoded = decoder.decode_message("spBv1.0/Plant/DDATA/Edge1/Pump", &data).unwrap();
+        assert_eq!(decoded.marker.as_deref(), Some("SEQ GAP 2→4"));
+    }
+
+    #[test]
+    fn test_group_topics() {
+        let mut topics = vec![
+            "other/topic",
+            "spBv1.0/Plant/DDATA/Edge1/Pump",
+            "spBv1.0/Plant/NDATA/Edge1",
+            "spBv1.0/Plant/NBIRTH/Edge1",
+        ];
+        group_topics(&mut topics, |t| t);
+        assert_eq!(topics, vec![
+            "spBv1.0/Plant/NBIRTH/Edge1",
+            "spBv1.0/Plant/NDATA/Edge1",
+            "spBv1.0/Plant/DDATA/Edge1/Pump",
+            "other/topic",
+        ]);
+    }
+}
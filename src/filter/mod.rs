@@ -0,0 +1,15 @@
+pub mod mute;
+pub mod preset;
+pub mod query;
+pub mod quick;
+pub mod severity;
+pub mod stale;
+pub mod time_expr;
+
+pub use mute::*;
+pub use preset::*;
+pub use query::*;
+pub use quick::*;
+pub use severity::*;
+pub use stale::*;
+pub use time_expr::*;
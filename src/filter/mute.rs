@@ -0,0 +1,89 @@
+use std::sync::{Arc, RwLock};
+
+use crate::config::{MuteMode, MuteRule};
+use crate::utils::topic_pattern::topic_matches;
+
+/// 靜音規則，MQTT 接收端 (drop) 與畫面 (hide) 共用同一份，畫面修改後立即生效
+#[derive(Debug, Clone, Default)]
+pub struct MuteRules {
+    rules: Arc<RwLock<Vec<MuteRule>>>,
+}
+
+impl MuteRules {
+    pub fn new(rules: &[MuteRule]) -> Self {
+        Self {
+            rules: Arc::new(RwLock::new(rules.to_vec())),
+        }
+    }
+
+    pub fn set(&self, rules: &[MuteRule]) {
+        *self.rules.write().unwrap() = rules.to_vec();
+    }
+
+    pub fn rules(&self) -> Vec<MuteRule> {
+        self.rules.read().unwrap().clone()
+    }
+
+    /// 符合的規則中有 drop 時為 drop
+    pub fn mode_for(&self, topic: &str) -> Option<MuteMode> {
+        self.rules
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|rule| topic_matches(&rule.topic, topic))
+            .map(|rule| rule.mode)
+            .max_by_key(|mode| *mode == MuteMode::Drop)
+    }
+
+    /// 收到訊息時是否直接丟棄
+    pub fn should_drop(&self, topic: &str) -> bool {
+        self.mode_for(topic) == Some(MuteMode::Drop)
+    }
+
+    /// 切換 topic 本身 (非萬用字元) 的規則：無 → hide → drop → 無
+    pub fn cycle(&self, topic: &str) -> Option<MuteMode> {
+        let mut rules = self.rules.write().unwrap();
+        match rules.iter().position(|rule| rule.topic == topic) {
+            Some(index) if rules[index].mode == MuteMode::Hide => {
+                rules[index].mode = MuteMode::Drop;
+                Some(MuteMode::Drop)
+            }
+            Some(index) => {
+                rules.remove(index);
+                None
+            }
+            None => {
+                rules.push(MuteRule {
+                    topic: topic.to_string(),
+                    mode: MuteMode::Hide,
+                });
+                Some(MuteMode::Hide)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mute_rules() {
+        let mutes = MuteRules::new(&[MuteRule {
+            topic: "devices/+/heartbeat".to_string(),
+            mode: MuteMode::Hide,
+        }]);
+        assert_eq!(mutes.mode_for("devices/a/heartbeat"), Some(MuteMode::Hide));
+        assert_eq!(mutes.mode_for("devices/a/status"), None);
+
+        // 共用同一份規則
+        let ingest = mutes.clone();
+        assert_eq!(mutes.cycle("devices/a/heartbeat"), Some(MuteMode::Hide));
+        assert_eq!(mutes.cycle("devices/a/heartbeat"), Some(MuteMode::Drop));
+        assert!(ingest.should_drop("devices/a/heartbeat"));
+        assert!(!ingest.should_drop("devices/b/heartbeat"));
+        assert_eq!(mutes.cycle("devices/a/heartbeat"), None);
+        assert_eq!(mutes.rules().len(), 1);
+        assert_eq!(ingest.mode_for("devices/a/heartbeat"), Some(MuteMode::Hide));
+    }
+}
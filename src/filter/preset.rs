@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// 具名的過濾條件組合 (設定檔的 [[presets]] 或資料庫的 filter_presets)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterPreset {
+    pub name: String,
+    #[serde(default)]
+    pub topic_regex: String,
+    #[serde(default)]
+    pub payload_regex: String,
+    // 與時間過濾欄位相同的格式 (絕對時間或 -15m、today 等相對時間)，空字串表示不限
+    #[serde(default)]
+    pub start_time: String,
+    #[serde(default)]
+    pub end_time: String,
+    // 快速過濾器名稱 -> 開關，未列出的過濾器維持目前狀態
+    #[serde(default)]
+    pub quick_filters: BTreeMap<String, bool>,
+    // 設定檔中的 preset 無法在程式內刪除或覆寫
+    #[serde(skip)]
+    pub from_config: bool,
+}
+
+impl FilterPreset {
+    /// 清單中顯示的摘要
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.topic_regex.is_empty() {
+            parts.push(format!("topic={}", self.topic_regex));
+        }
+        if !self.payload_regex.is_empty() {
+            parts.push(format!("payload={}", self.payload_regex));
+        }
+        if !self.start_time.is_empty() || !self.end_time.is_empty() {
+            parts.push(format!("time={}..{}", self.start_time, self.end_time));
+        }
+        let disabled: Vec<&str> = self.quick_filters
+            .iter()
+            .filter(|(_, enabled)| !**enabled)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if !disabled.is_empty() {
+            parts.push(format!("off={}", disabled.join(",")));
+        }
+        parts.join("  ")
+    }
+}
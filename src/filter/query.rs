@@ -0,0 +1,688 @@
+use std::cell::OnceCell;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Local, Utc};
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
+
+use crate::db::Message;
+use crate::utils::json_path::{json_number, JsonPath};
+use super::time_expr::TimeExpr;
+
+/// 比較運算子
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Contains, // :
+    Regex,    // :~
+    Eq,       // =
+    Ne,       // !=
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl CompareOp {
+    fn parse(text: &str) -> Option<Self> {
+        Some(match text {
+            ":" => CompareOp::Contains,
+            ":~" | "~" => CompareOp::Regex,
+            "=" | "==" => CompareOp::Eq,
+            "!=" => CompareOp::Ne,
+            ">" => CompareOp::Gt,
+            ">=" => CompareOp::Ge,
+            "<" => CompareOp::Lt,
+            "<=" => CompareOp::Le,
+            _ => return None,
+        })
+    }
+
+    fn sql(&self) -> Option<&'static str> {
+        Some(match self {
+            CompareOp::Contains | CompareOp::Eq => "=",
+            CompareOp::Ne => "<>",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Regex => return None,
+        })
+    }
+
+    fn compare<T: PartialOrd>(&self, left: T, right: T) -> bool {
+        match self {
+            CompareOp::Contains | CompareOp::Eq => left == right,
+            CompareOp::Ne => left != right,
+            CompareOp::Gt => left > right,
+            CompareOp::Ge => left >= right,
+            CompareOp::Lt => left < right,
+            CompareOp::Le => left <= right,
+            CompareOp::Regex => false,
+        }
+    }
+}
+
+/// 字串欄位的比對方式；: 為不分大小寫的子字串
+#[derive(Debug, Clone)]
+pub enum TextMatch {
+    Contains(String),
+    Equals(String),
+    NotEquals(String),
+    Regex(Regex),
+}
+
+impl TextMatch {
+    fn new(op: CompareOp, value: &str, field: &str) -> Result<Self> {
+        Ok(match op {
+            CompareOp::Contains => TextMatch::Contains(value.to_lowercase()),
+            CompareOp::Eq => TextMatch::Equals(value.to_string()),
+            CompareOp::Ne => TextMatch::NotEquals(value.to_string()),
+            CompareOp::Regex => TextMatch::Regex(
+                RegexBuilder::new(value)
+                    .build()
+                    .map_err(|e| anyhow!("Invalid regex for {}: {}", field, e))?,
+            ),
+            _ => bail!("'{}' only supports :, :~, = and !=", field),
+        })
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            TextMatch::Contains(pattern) => text.to_lowercase().contains(pattern.as_str()),
+            TextMatch::Equals(value) => text == value,
+            TextMatch::NotEquals(value) => text != value,
+            TextMatch::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// JSON 欄位比較的右側值
+#[derive(Debug, Clone)]
+pub enum JsonMatch {
+    Exists,
+    Number(CompareOp, f64),
+    Text(TextMatch),
+    Bool(bool, bool), // (期望值, 是否相等)
+}
+
+#[derive(Debug, Clone)]
+pub enum Condition {
+    Topic(TextMatch),
+    Payload(TextMatch),
+    Level(TextMatch),
+    Json(JsonPath, JsonMatch),
+    Qos(CompareOp, i64),
+    Size(CompareOp, i64),
+    Retain(bool),
+    Backlog(bool),
+    Time(CompareOp, TimeExpr),
+}
+
+/// 過濾查詢的語法樹
+#[derive(Debug, Clone)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Condition(Condition),
+}
+
+// 評估單筆訊息時共用的狀態，payload 只在需要時解析一次 JSON
+struct EvalContext<'a> {
+    message: &'a Message,
+    now: DateTime<Local>,
+    json: OnceCell<Option<Value>>,
+}
+
+impl EvalContext<'_> {
+    fn json(&self) -> Option<&Value> {
+        self.json
+            .get_or_init(|| serde_json::from_str(&self.message.payload).ok())
+            .as_ref()
+    }
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let text = text.strip_prefix('?').unwrap_or(text);
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            bail!("Empty query");
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let query = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            bail!("Unexpected {} at position {}", token.describe(), parser.pos + 1);
+        }
+        Ok(query)
+    }
+
+    /// 過濾欄位的內容是否為查詢語法：以 ? 開頭，或含有 field:value / field > value 的條件
+    pub fn looks_like_query(text: &str) -> bool {
+        use std::sync::OnceLock;
+        static FIELD_CONDITION: OnceLock<Regex> = OnceLock::new();
+        let text = text.trim_start();
+        text.starts_with('?')
+            || FIELD_CONDITION
+                .get_or_init(|| {
+                    Regex::new(r"(?i)(^|[\s(])((topic|payload|level|qos|size|retain|backlog|time|json\.\S+?|\$\S*?)\s*(:~?|!=|==?|>=?|<=?|~)|(json\.|\$)\S*\s+(contains|exists)\b)")
+                        .unwrap()
+                })
+                .is_match(text)
+    }
+
+    pub fn matches(&self, message: &Message, now: DateTime<Local>) -> bool {
+        let context = EvalContext { message, now, json: OnceCell::new() };
+        self.eval(&context)
+    }
+
+    fn eval(&self, context: &EvalContext) -> bool {
+        match self {
+            Query::And(left, right) => left.eval(context) && right.eval(context),
+            Query::Or(left, right) => left.eval(context) || right.eval(context),
+            Query::Not(inner) => !inner.eval(context),
+            Query::Condition(condition) => condition.eval(context),
+        }
+    }
+
+    /// 可交給 SQLite 預先篩選的 WHERE 條件 (json_extract 等)；結果一定涵蓋所有符合的訊息，
+    /// 無法轉換的部分仍由 matches 逐筆比對
+    pub fn sql_prefilter(&self) -> Option<(String, Vec<Value>)> {
+        let mut args = Vec::new();
+        self.to_sql(&mut args).map(|sql| (sql, args))
+    }
+
+    // 只在回傳 Some 時留下參數
+    fn to_sql(&self, args: &mut Vec<Value>) -> Option<String> {
+        let mark = args.len();
+        match self {
+            Query::And(left, right) => match (left.to_sql(args), right.to_sql(args)) {
+                (Some(left), Some(right)) => Some(format!("({} AND {})", left, right)),
+                (left, right) => left.or(right),
+            },
+            Query::Or(left, right) => {
+                let sql = left.to_sql(args).zip(right.to_sql(args));
+                if sql.is_none() {
+                    args.truncate(mark);
+                }
+                sql.map(|(left, right)| format!("({} OR {})", left, right))
+            }
+            Query::Not(_) => None,
+            Query::Condition(condition) => condition.to_sql(args),
+        }
+    }
+
+    /// 最上層 AND 中的時間條件，可以先交給 SQL 縮小範圍
+    pub fn time_bounds(&self, now: DateTime<Local>) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        match self {
+            Query::And(left, right) => {
+                let (left_start, left_end) = left.time_bounds(now);
+                let (right_start, right_end) = right.time_bounds(now);
+                (left_start.max(right_start), min_option(left_end, right_end))
+            }
+            Query::Condition(Condition::Time(op, expr)) => {
+                let time = expr.resolve(now).with_timezone(&Utc);
+                match op {
+                    CompareOp::Gt | CompareOp::Ge => (Some(time), None),
+                    CompareOp::Lt | CompareOp::Le => (None, Some(time)),
+                    _ => (None, None),
+                }
+            }
+            _ => (None, None),
+        }
+    }
+}
+
+fn min_option<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+impl Condition {
+    fn eval(&self, context: &EvalContext) -> bool {
+        let message = context.message;
+        match self {
+            Condition::Topic(matcher) => matcher.is_match(&message.topic),
+            Condition::Payload(matcher) => matcher.is_match(&message.payload),
+            Condition::Level(matcher) => message.level.as_deref().is_some_and(|level| matcher.is_match(level)),
+            Condition::Qos(op, value) => op.compare(message.qos as i64, *value),
+            Condition::Size(op, value) => op.compare(message.payload.len() as i64, *value),
+            Condition::Retain(expected) => message.retain == *expected,
+            Condition::Backlog(expected) => message.backlog == *expected,
+            Condition::Time(op, expr) => {
+                op.compare(message.timestamp, expr.resolve(context.now).with_timezone(&Utc))
+            }
+            Condition::Json(path, json_match) => {
+                let value = match context.json().and_then(|json| path.get(json)) {
+                    Some(value) => value,
+                    None => return false,
+                };
+                match json_match {
+                    JsonMatch::Exists => !value.is_null(),
+                    JsonMatch::Number(op, expected) => json_number(value).is_some_and(|n| op.compare(n, *expected)),
+                    JsonMatch::Bool(expected, equal) => value.as_bool().is_some_and(|b| (b == *expected) == *equal),
+                    JsonMatch::Text(matcher) => match (value, matcher) {
+                        (Value::String(text), _) => matcher.is_match(text),
+                        // 陣列的 contains 比對元素 (不分大小寫)
+                        (Value::Array(items), TextMatch::Contains(expected)) => items.iter().any(|item| {
+                            let text = match item {
+                                Value::String(text) => text.to_lowercase(),
+                                other => other.to_string(),
+                            };
+                            text == *expected
+                        }),
+                        (other, _) => matcher.is_match(&other.to_string()),
+                    },
+                }
+            }
+        }
+    }
+
+    fn to_sql(&self, args: &mut Vec<Value>) -> Option<String> {
+        match self {
+            Condition::Topic(TextMatch::Equals(topic)) => {
+                args.push(Value::from(topic.as_str()));
+                Some("topic = ?".to_string())
+            }
+            Condition::Topic(TextMatch::NotEquals(topic)) => {
+                args.push(Value::from(topic.as_str()));
+                Some("topic <> ?".to_string())
+            }
+            Condition::Qos(op, value) => {
+                let op = op.sql()?;
+                args.push(Value::from(*value));
+                Some(format!("qos {} ?", op))
+            }
+            Condition::Size(op, value) => {
+                let op = op.sql()?;
+                args.push(Value::from(*value));
+                Some(format!("length(CAST(payload AS BLOB)) {} ?", op))
+            }
+            Condition::Retain(expected) => Some(format!("retain = {}", *expected as i32)),
+            Condition::Json(path, json_match) => {
+                let path = path.to_sqlite()?;
+                // payload 不是 JSON 時 json_extract 會出錯，先以 json_valid 檢查
+                let json_type = "(CASE WHEN json_valid(payload) THEN json_type(payload, ?) END)";
+                let json_extract = "(CASE WHEN json_valid(payload) THEN json_extract(payload, ?) END)";
+                Some(match json_match {
+                    JsonMatch::Exists => {
+                        args.push(Value::from(path));
+                        format!("{} <> 'null'", json_type)
+                    }
+                    JsonMatch::Number(op, value) => {
+                        let op = op.sql()?;
+                        args.push(Value::from(path));
+                        args.push(Value::from(*value));
+                        format!("CAST({} AS REAL) {} ?", json_extract, op)
+                    }
+                    JsonMatch::Bool(expected, equal) => {
+                        args.push(Value::from(path));
+                        format!("{} = '{}'", json_type, expected == equal)
+                    }
+                    JsonMatch::Text(TextMatch::Equals(text)) => {
+                        // 非字串的值以文字形式比對，交給 matches 處理
+                        args.push(Value::from(path.as_str()));
+                        args.push(Value::from(path));
+                        args.push(Value::from(text.as_str()));
+                        format!("({} <> 'text' OR {} = ?)", json_type, json_extract)
+                    }
+                    JsonMatch::Text(_) => {
+                        args.push(Value::from(path));
+                        format!("{} IS NOT NULL", json_type)
+                    }
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+// 數字或可轉成數字的字串
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Op(String),
+    Word(String),
+    Quoted(String),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::LParen => "'('".to_string(),
+            Token::RParen => "')'".to_string(),
+            Token::Op(op) => format!("'{}'", op),
+            Token::Word(word) => format!("'{}'", word),
+            Token::Quoted(text) => format!("\"{}\"", text),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+const OP_CHARS: &[char] = &[':', '=', '!', '<', '>', '~'];
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::LParen);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::RParen);
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => bail!("Unterminated string starting at position {}", tokens.len() + 1),
+                    Some('\\') if chars.get(i + 1).is_some() => {
+                        // 只處理跳脫引號與反斜線，其餘保留給正規表示式
+                        let next = chars[i + 1];
+                        if next != c && next != '\\' {
+                            value.push('\\');
+                        }
+                        value.push(next);
+                        i += 2;
+                    }
+                    Some(&ch) if ch == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&ch) => {
+                        value.push(ch);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(Token::Quoted(value));
+        } else if OP_CHARS.contains(&c) {
+            let start = i;
+            while i < chars.len() && OP_CHARS.contains(&chars[i]) {
+                i += 1;
+            }
+            let op: String = chars[start..i].iter().collect();
+            if CompareOp::parse(&op).is_none() {
+                bail!("Unknown operator '{}'", op);
+            }
+            tokens.push(Token::Op(op));
+        } else {
+            let start = i;
+            while i < chars.len()
+                && !chars[i].is_whitespace()
+                && !matches!(chars[i], '(' | ')' | '"' | '\'')
+                && !OP_CHARS.contains(&chars[i])
+            {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect_value(&mut self, field: &str) -> Result<String> {
+        match self.next() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => Ok(value),
+            Some(token) => bail!("Expected a value after '{}', found {}", field, token.describe()),
+            None => bail!("Expected a value after '{}'", field),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Query> {
+        let mut left = self.parse_and()?;
+        while self.peek().is_some_and(|t| t.is_keyword("OR")) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Query::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // 相鄰的條件視為 AND
+    fn parse_and(&mut self) -> Result<Query> {
+        let mut left = self.parse_not()?;
+        loop {
+            match self.peek() {
+                Some(token) if token.is_keyword("AND") => self.pos += 1,
+                Some(Token::RParen) | None => break,
+                Some(token) if token.is_keyword("OR") => break,
+                Some(_) => {}
+            }
+            let right = self.parse_not()?;
+            left = Query::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Query> {
+        if self.peek().is_some_and(|t| t.is_keyword("NOT")) {
+            self.pos += 1;
+            return Ok(Query::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Query> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let query = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(query),
+                    _ => bail!("Missing ')'"),
+                }
+            }
+            // 單獨的字串視為 payload 子字串
+            Some(Token::Quoted(text)) => Ok(Query::Condition(Condition::Payload(TextMatch::Contains(text.to_lowercase())))),
+            Some(Token::Word(word)) => {
+                // 文字形式的運算子：json.$.tags contains "x"、$.temp exists
+                if is_field(&word) {
+                    if self.peek().is_some_and(|t| t.is_keyword("EXISTS")) {
+                        self.pos += 1;
+                        let path = json_path_text(&word).ok_or_else(|| anyhow!("'exists' only applies to JSON paths, not '{}'", word))?;
+                        return Ok(Query::Condition(Condition::Json(JsonPath::parse(path)?, JsonMatch::Exists)));
+                    }
+                    if self.peek().is_some_and(|t| t.is_keyword("CONTAINS")) {
+                        self.pos += 1;
+                        let value = self.expect_value(&word)?;
+                        return Ok(Query::Condition(parse_condition(&word, CompareOp::Contains, &value)?));
+                    }
+                }
+
+                if let Some(Token::Op(op)) = self.peek().cloned() {
+                    self.pos += 1;
+                    let op = CompareOp::parse(&op).ok_or_else(|| anyhow!("Unknown operator '{}'", op))?;
+                    let value = self.expect_value(&word)?;
+                    return Ok(Query::Condition(parse_condition(&word, op, &value)?));
+                }
+
+                let lower = word.to_ascii_lowercase();
+                Ok(Query::Condition(match lower.as_str() {
+                    "retain" => Condition::Retain(true),
+                    "backlog" => Condition::Backlog(true),
+                    _ => match json_path_text(&word) {
+                        Some(path) => Condition::Json(JsonPath::parse(path)?, JsonMatch::Exists),
+                        None => Condition::Payload(TextMatch::Contains(lower)),
+                    },
+                }))
+            }
+            Some(token) => bail!("Unexpected {}", token.describe()),
+            None => bail!("Unexpected end of query"),
+        }
+    }
+}
+
+fn parse_condition(field: &str, op: CompareOp, value: &str) -> Result<Condition> {
+    let lower = field.to_ascii_lowercase();
+    Ok(match lower.as_str() {
+        "topic" => Condition::Topic(TextMatch::new(op, value, field)?),
+        "payload" => Condition::Payload(TextMatch::new(op, value, field)?),
+        "level" => Condition::Level(TextMatch::new(op, value, field)?),
+        "qos" => Condition::Qos(numeric_op(op, field)?, parse_integer(value, field)?),
+        "size" => Condition::Size(numeric_op(op, field)?, parse_size(value)?),
+        "retain" => Condition::Retain(parse_bool_condition(op, value, field)?),
+        "backlog" => Condition::Backlog(parse_bool_condition(op, value, field)?),
+        "time" => {
+            if !matches!(op, CompareOp::Gt | CompareOp::Ge | CompareOp::Lt | CompareOp::Le) {
+                bail!("'time' only supports >, >=, < and <=");
+            }
+            Condition::Time(op, TimeExpr::parse(value)?)
+        }
+        _ if json_path_text(field).is_some() => {
+            let path = JsonPath::parse(json_path_text(field).unwrap_or_default())?;
+            let json_match = match (op, value.parse::<f64>(), value.to_ascii_lowercase().as_str()) {
+                (CompareOp::Eq | CompareOp::Ne, _, "true" | "false") => {
+                    JsonMatch::Bool(value.eq_ignore_ascii_case("true"), op == CompareOp::Eq)
+                }
+                (CompareOp::Contains | CompareOp::Regex, _, _) => JsonMatch::Text(TextMatch::new(op, value, field)?),
+                (_, Ok(number), _) => JsonMatch::Number(op, number),
+                (CompareOp::Eq | CompareOp::Ne, Err(_), _) => JsonMatch::Text(TextMatch::new(op, value, field)?),
+                _ => bail!("'{}' {:?} needs a numeric value, got '{}'", field, op, value),
+            };
+            Condition::Json(path, json_match)
+        }
+        _ => bail!("Unknown field '{}' (topic, payload, level, json.<path> or $.<path>, qos, size, retain, backlog, time)", field),
+    })
+}
+
+const FIELDS: &[&str] = &["topic", "payload", "level", "qos", "size", "retain", "backlog", "time"];
+
+fn is_field(word: &str) -> bool {
+    FIELDS.iter().any(|field| word.eq_ignore_ascii_case(field)) || json_path_text(word).is_some()
+}
+
+// json.$.a 或 $.a 形式的欄位，回傳 JSON path 部分
+fn json_path_text(word: &str) -> Option<&str> {
+    if word.starts_with('$') {
+        Some(word)
+    } else if word.len() > 5 && word.get(..5).is_some_and(|prefix| prefix.eq_ignore_ascii_case("json.")) {
+        Some(&word[5..])
+    } else {
+        None
+    }
+}
+
+fn numeric_op(op: CompareOp, field: &str) -> Result<CompareOp> {
+    match op {
+        CompareOp::Regex => bail!("'{}' does not support regex", field),
+        CompareOp::Contains => Ok(CompareOp::Eq),
+        op => Ok(op),
+    }
+}
+
+fn parse_integer(value: &str, field: &str) -> Result<i64> {
+    value.parse().map_err(|_| anyhow!("'{}' needs an integer, got '{}'", field, value))
+}
+
+// 位元組數，可加 k / m 單位 (1024 進位)
+fn parse_size(value: &str) -> Result<i64> {
+    let lower = value.to_ascii_lowercase();
+    let lower = lower.trim_end_matches('b');
+    let (number, multiplier) = if let Some(n) = lower.strip_suffix('k') {
+        (n, 1024)
+    } else if let Some(n) = lower.strip_suffix('m') {
+        (n, 1024 * 1024)
+    } else {
+        (lower, 1)
+    };
+    number
+        .parse::<i64>()
+        .map(|n| n * multiplier)
+        .map_err(|_| anyhow!("Invalid size '{}' (e.g. 512, 4k, 1mb)", value))
+}
+
+fn parse_bool_condition(op: CompareOp, value: &str, field: &str) -> Result<bool> {
+    let expected = match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => true,
+        "false" | "0" | "no" => false,
+        _ => bail!("'{}' needs true or false, got '{}'", field, value),
+    };
+    match op {
+        CompareOp::Contains | CompareOp::Eq => Ok(expected),
+        CompareOp::Ne => Ok(!expected),
+        _ => bail!("'{}' only supports : = and !=", field),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn message(topic: &str, payload: &str, retain: bool) -> Message {
+        let mut message = Message::new(topic.to_string(), payload.to_string(), 1, retain);
+        message.timestamp = Utc.with_ymd_and_hms(2024, 5, 1, 11, 50, 0).unwrap();
+        message
+    }
+
+    #[test]
+    fn test_query_matches() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap().with_timezone(&Local);
+        let query = Query::parse(r#"topic:~"^plant/" AND (json.$.temp > 80 OR payload:"ALARM") AND NOT retain"#).unwrap();
+
+        assert!(query.matches(&message("plant/a", r#"{"temp": 85}"#, false), now));
+        assert!(query.matches(&message("plant/a", r#"{"temp": 20, "msg": "alarm"}"#, false), now));
+        assert!(!query.matches(&message("plant/a", r#"{"temp": 85}"#, true), now));
+        assert!(!query.matches(&message("office/a", r#"{"temp": 85}"#, false), now));
+
+        let query = Query::parse(r#"qos>=1 size<1k time > "-15m" json.$.ok = true"#).unwrap();
+        assert!(query.matches(&message("a", r#"{"ok": true}"#, false), now));
+        assert!(!query.matches(&message("a", r#"{"ok": false}"#, false), now));
+        assert_eq!(query.time_bounds(now).0, Some(Utc.with_ymd_and_hms(2024, 5, 1, 11, 45, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_query_errors_and_detection() {
+        assert!(Query::parse("topic:").is_err());
+        assert!(Query::parse("(retain").is_err());
+        assert!(Query::parse("color:red").is_err());
+        assert!(Query::parse("qos:~1").is_err());
+        assert!(Query::parse(r#"payload:"open"#).is_err());
+
+        let query = Query::parse(r#"$.tags contains "Hot" AND json.$.id exists"#).unwrap();
+        assert!(query.matches(&message("a", r#"{"id": 1, "tags": ["hot", "wet"]}"#, false), Local::now()));
+        assert!(!query.matches(&message("a", r#"{"tags": ["hot"]}"#, false), Local::now()));
+        assert!(Query::parse("topic exists").is_err());
+        let (sql, args) = Query::parse("$.temp > 30 AND NOT retain").unwrap().sql_prefilter().unwrap();
+        assert!(sql.contains("json_extract") && args.len() == 2);
+        assert!(Query::parse("$.temp > 30 OR NOT retain").unwrap().sql_prefilter().is_none());
+
+        assert!(Query::looks_like_query("topic:~^a AND retain"));
+        assert!(Query::looks_like_query("$.temperature > 30"));
+        assert!(Query::looks_like_query("$.tags contains x"));
+        assert!(Query::looks_like_query("? NOT retain"));
+        assert!(Query::looks_like_query("json.$.temp > 80"));
+        assert!(!Query::looks_like_query("ERROR|WARN"));
+        assert!(!Query::looks_like_query("(timeout|refused)"));
+    }
+}
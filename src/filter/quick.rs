@@ -0,0 +1,296 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Color;
+use regex::{Regex, RegexBuilder};
+
+use crate::config::{QuickFilter, QuickFiltersConfig};
+use crate::db::Message;
+use crate::utils::color::parse_color;
+use super::severity::Level;
+
+/// 快速過濾器的快捷鍵：F1–F12、Alt+字元、Ctrl+字元
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hotkey {
+    F(u8),
+    Alt(char),
+    Ctrl(char),
+}
+
+// 已有內建功能的按鍵，不能再指定給快速過濾器
+const RESERVED_HOTKEYS: &[Hotkey] = &[
+    Hotkey::F(6),
+    Hotkey::F(7),
+    Hotkey::F(8),
+    Hotkey::Alt('c'),
+    Hotkey::Ctrl('c'),
+    Hotkey::Ctrl('v'),
+    Hotkey::Ctrl('o'),
+    Hotkey::Ctrl('p'),
+    Hotkey::Ctrl('s'),
+];
+
+impl Hotkey {
+    pub fn parse(text: &str) -> Result<Self> {
+        let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let lower = text.to_ascii_lowercase();
+
+        let hotkey = if let Some(number) = lower.strip_prefix('f').filter(|n| !n.is_empty()) {
+            match number.parse::<u8>() {
+                Ok(n) if (1..=12).contains(&n) => Hotkey::F(n),
+                _ => bail!("Unsupported function key '{}'", text),
+            }
+        } else if let Some(key) = lower.strip_prefix("alt+").or_else(|| lower.strip_prefix("alt-")) {
+            Hotkey::Alt(Self::single_char(key, &text)?)
+        } else if let Some(key) = lower.strip_prefix("ctrl+").or_else(|| lower.strip_prefix("ctrl-")) {
+            Hotkey::Ctrl(Self::single_char(key, &text)?)
+        } else {
+            bail!("Unsupported hotkey '{}' (expected F1-F12, Alt+<key> or Ctrl+<key>)", text);
+        };
+
+        if RESERVED_HOTKEYS.contains(&hotkey) {
+            bail!("Hotkey '{}' is already used by a built-in command", text);
+        }
+        Ok(hotkey)
+    }
+
+    fn single_char(key: &str, text: &str) -> Result<char> {
+        let mut chars = key.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(anyhow!("Hotkey '{}' must name a single key", text)),
+        }
+    }
+
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        match (*self, key_event.code) {
+            (Hotkey::F(n), KeyCode::F(code)) => n == code,
+            (Hotkey::Alt(c), KeyCode::Char(code)) => {
+                key_event.modifiers.contains(KeyModifiers::ALT) && c == code.to_ascii_lowercase()
+            }
+            (Hotkey::Ctrl(c), KeyCode::Char(code)) => {
+                key_event.modifiers.contains(KeyModifiers::CONTROL) && c == code.to_ascii_lowercase()
+            }
+            _ => false,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Hotkey::F(n) => format!("F{}", n),
+            Hotkey::Alt(c) => format!("Alt+{}", c.to_ascii_uppercase()),
+            Hotkey::Ctrl(c) => format!("Ctrl+{}", c.to_ascii_uppercase()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Substring { pattern: String, case_sensitive: bool },
+    Regex(Regex),
+    Level(Level), // 比對擷取出的日誌等級，不看內容
+}
+
+/// 編譯過的快速過濾器
+#[derive(Debug, Clone)]
+pub struct CompiledQuickFilter {
+    pub name: String,
+    pub color: Option<Color>,
+    pub hotkey: Option<Hotkey>,
+    pub enabled_by_default: bool,
+    pub show_count: bool, // 在 Topic 清單顯示此過濾器的計數欄
+    matcher: Matcher,
+}
+
+impl CompiledQuickFilter {
+    pub fn compile(filter: &QuickFilter) -> Result<Self> {
+        let matcher = if let Some(level) = &filter.level {
+            let level = Level::parse(level)
+                .ok_or_else(|| anyhow!("Quick filter '{}': unknown level '{}'", filter.name, level))?;
+            Matcher::Level(level)
+        } else if filter.regex {
+            let regex = RegexBuilder::new(&filter.pattern)
+                .case_insensitive(!filter.case_sensitive)
+                .build()
+                .map_err(|e| anyhow!("Quick filter '{}': invalid regex: {}", filter.name, e))?;
+            Matcher::Regex(regex)
+        } else if filter.case_sensitive {
+            Matcher::Substring { pattern: filter.pattern.clone(), case_sensitive: true }
+        } else {
+            Matcher::Substring { pattern: filter.pattern.to_lowercase(), case_sensitive: false }
+        };
+
+        let hotkey = if filter.hotkey.trim().is_empty() {
+            None
+        } else {
+            Some(Hotkey::parse(&filter.hotkey).map_err(|e| anyhow!("Quick filter '{}': {}", filter.name, e))?)
+        };
+
+        Ok(Self {
+            name: filter.name.clone(),
+            color: parse_color(&filter.color),
+            hotkey,
+            enabled_by_default: filter.enabled,
+            show_count: filter.show_count,
+            matcher,
+        })
+    }
+
+    /// content 為 "topic payload"；lowercase 為預先轉小寫的版本，避免每個過濾器重複轉換
+    fn is_match(&self, content: &str, lowercase: &str, level: Option<Level>) -> bool {
+        match &self.matcher {
+            Matcher::Level(expected) => level == Some(*expected),
+            Matcher::Substring { pattern, case_sensitive: true } => content.contains(pattern.as_str()),
+            Matcher::Substring { pattern, case_sensitive: false } => lowercase.contains(pattern.as_str()),
+            Matcher::Regex(regex) => regex.is_match(content),
+        }
+    }
+}
+
+/// 設定檔中的所有快速過濾器
+#[derive(Debug, Clone, Default)]
+pub struct QuickFilterSet {
+    pub filters: Vec<CompiledQuickFilter>,
+}
+
+impl QuickFilterSet {
+    /// 無法編譯的過濾器會略過，錯誤訊息一併回傳給呼叫端顯示
+    pub fn from_config(config: &QuickFiltersConfig) -> (Self, Vec<String>) {
+        let mut set = Self::default();
+        let mut errors = Vec::new();
+        if !config.enabled {
+            return (set, errors);
+        }
+
+        for filter in &config.filters {
+            match CompiledQuickFilter::compile(filter) {
+                Ok(compiled) => {
+                    if let Some(hotkey) = compiled.hotkey {
+                        if set.filters.iter().any(|f| f.hotkey == Some(hotkey)) {
+                            errors.push(format!("Quick filter '{}': hotkey {} is used twice", filter.name, hotkey.label()));
+                        }
+                    }
+                    set.filters.push(compiled);
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        (set, errors)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// 第一個快捷鍵符合的過濾器
+    pub fn hotkey_index(&self, key_event: &KeyEvent) -> Option<usize> {
+        self.filters
+            .iter()
+            .position(|f| f.hotkey.is_some_and(|hotkey| hotkey.matches(key_event)))
+    }
+
+    /// 訊息符合的所有過濾器索引；level 為資料庫中存的日誌等級
+    pub fn matching_indices(&self, topic: &str, payload: &str, level: Option<&str>) -> Vec<usize> {
+        if self.filters.is_empty() {
+            return Vec::new();
+        }
+        let content = format!("{} {}", topic, payload);
+        let lowercase = content.to_lowercase();
+        let level = level.and_then(Level::parse);
+        self.filters
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.is_match(&content, &lowercase, level))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// 不符合任何過濾器的訊息一律顯示；符合的訊息只在其中至少一個過濾器開啟時顯示
+    pub fn is_visible(matching: &[usize], active: &[bool]) -> bool {
+        matching.is_empty() || matching.iter().any(|&index| active.get(index).copied().unwrap_or(false))
+    }
+
+    /// 第一個符合的過濾器顏色，沒有時依日誌等級的預設顏色
+    pub fn color_for(&self, message: &Message) -> Option<Color> {
+        self.matching_indices(&message.topic, &message.payload, message.level.as_deref())
+            .first()
+            .and_then(|&index| self.filters[index].color)
+            .or_else(|| message.level.as_deref().and_then(Level::parse).map(|level| level.color()))
+    }
+
+    /// 狀態列上的標籤，例如 F1 或 Alt+E；沒有快捷鍵時以序號表示
+    pub fn hotkey_label(&self, index: usize) -> String {
+        match self.filters.get(index).and_then(|f| f.hotkey) {
+            Some(hotkey) => hotkey.label(),
+            None => format!("#{}", index + 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(name: &str, pattern: &str, regex: bool, hotkey: &str) -> QuickFilter {
+        QuickFilter {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            level: None,
+            color: "red".to_string(),
+            hotkey: hotkey.to_string(),
+            enabled: true,
+            case_sensitive: false,
+            regex,
+            show_count: true,
+        }
+    }
+
+    #[test]
+    fn test_hotkey_parse() {
+        assert_eq!(Hotkey::parse("F9").unwrap(), Hotkey::F(9));
+        assert_eq!(Hotkey::parse("alt+E").unwrap(), Hotkey::Alt('e'));
+        assert_eq!(Hotkey::parse("Ctrl-1").unwrap(), Hotkey::Ctrl('1'));
+        assert!(Hotkey::parse("F13").is_err());
+        assert!(Hotkey::parse("F7").is_err()); // JSON toggle
+        assert!(Hotkey::parse("Alt+C").is_err()); // Copy
+        assert!(Hotkey::parse("x").is_err());
+    }
+
+    #[test]
+    fn test_filters_from_config() {
+        let config = QuickFiltersConfig {
+            enabled: true,
+            filters: vec![
+                filter("ERR", "error", false, "F1"),
+                filter("Timeout", r"timeout after \d+ms", true, "Alt+t"),
+                filter("Broken", "(", true, ""),
+                filter("Dup", "dup", false, "F1"),
+            ],
+        };
+        let (set, errors) = QuickFilterSet::from_config(&config);
+        assert_eq!(set.filters.len(), 3);
+        assert_eq!(errors.len(), 2);
+
+        assert_eq!(set.matching_indices("app/log", "ERROR: Timeout after 30ms", None), vec![0, 1]);
+        assert!(set.matching_indices("app/log", "timeout after ms", None).is_empty());
+        assert!(QuickFilterSet::is_visible(&[], &[false, false, false]));
+        assert!(QuickFilterSet::is_visible(&[0, 1], &[false, true, false]));
+        assert!(!QuickFilterSet::is_visible(&[0], &[false, true, false]));
+
+        let key = KeyEvent::new(KeyCode::Char('T'), KeyModifiers::ALT);
+        assert_eq!(set.hotkey_index(&key), Some(1));
+        assert_eq!(set.hotkey_label(1), "Alt+T");
+    }
+
+    #[test]
+    fn test_level_filter_ignores_content() {
+        let mut warn = filter("WARN", "", false, "F2");
+        warn.level = Some("warn".to_string());
+        let config = QuickFiltersConfig { enabled: true, filters: vec![warn] };
+        let (set, errors) = QuickFilterSet::from_config(&config);
+        assert!(errors.is_empty());
+
+        assert_eq!(set.matching_indices("app/log", "all good", Some("WARN")), vec![0]);
+        assert!(set.matching_indices("warn_counter", "WARN", Some("INFO")).is_empty());
+        assert!(set.matching_indices("app/log", "WARN", None).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::style::Color;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::config::{SeverityConfig, SeverityRule};
+use crate::utils::json_path::JsonPath;
+use crate::utils::topic_pattern::topic_matches;
+
+/// 日誌等級，依嚴重程度排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl Level {
+    /// 接受常見的別名 (warning、err、critical...)，不分大小寫
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "trace" | "trc" | "verbose" => Some(Level::Trace),
+            "debug" | "dbg" => Some(Level::Debug),
+            "info" | "inf" | "information" | "informational" | "notice" => Some(Level::Info),
+            "warn" | "wrn" | "warning" => Some(Level::Warn),
+            "error" | "err" | "eror" => Some(Level::Error),
+            "fatal" | "critical" | "crit" | "alert" | "emerg" | "emergency" | "panic" => Some(Level::Fatal),
+            _ => None,
+        }
+    }
+
+    /// pino / bunyan 的數值等級 (10 = trace ... 60 = fatal)
+    pub fn from_number(value: i64) -> Option<Self> {
+        match value {
+            10 => Some(Level::Trace),
+            20 => Some(Level::Debug),
+            30 => Some(Level::Info),
+            40 => Some(Level::Warn),
+            50 => Some(Level::Error),
+            60 => Some(Level::Fatal),
+            _ => None,
+        }
+    }
+
+    /// syslog severity (PRI % 8)
+    pub fn from_syslog(severity: u8) -> Self {
+        match severity {
+            0..=2 => Level::Fatal,
+            3 => Level::Error,
+            4 => Level::Warn,
+            5 | 6 => Level::Info,
+            _ => Level::Debug,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+            Level::Fatal => "FATAL",
+        }
+    }
+
+    /// 沒有快速過濾器指定顏色時使用的預設顏色
+    pub fn color(&self) -> Color {
+        match self {
+            Level::Trace => Color::DarkGrey,
+            Level::Debug => Color::Cyan,
+            Level::Info => Color::Green,
+            Level::Warn => Color::Yellow,
+            Level::Error => Color::Red,
+            Level::Fatal => Color::Magenta,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Method {
+    JsonPath(JsonPath),
+    Regex(Regex),
+    Syslog,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    topic: String,
+    method: Method,
+}
+
+impl CompiledRule {
+    fn compile(rule: &SeverityRule) -> Result<Self> {
+        let method = match (&rule.json_path, &rule.regex, rule.syslog) {
+            (Some(path), None, false) => Method::JsonPath(JsonPath::parse(path)?),
+            (None, Some(pattern), false) => Method::Regex(
+                Regex::new(pattern).map_err(|e| anyhow!("Invalid severity regex '{}': {}", pattern, e))?,
+            ),
+            (None, None, true) => Method::Syslog,
+            _ => bail!("Severity rule for '{}' must set exactly one of json_path, regex or syslog", rule.topic),
+        };
+        Ok(Self { topic: rule.topic.clone(), method })
+    }
+
+    fn extract(&self, payload: &str) -> Option<Level> {
+        match &self.method {
+            Method::JsonPath(path) => match path.extract(payload)? {
+                Value::String(text) => Level::parse(&text),
+                Value::Number(number) => number.as_i64().and_then(Level::from_number),
+                _ => None,
+            },
+            Method::Regex(regex) => {
+                let captures = regex.captures(payload)?;
+                let text = captures.name("level").or_else(|| captures.get(1)).or_else(|| captures.get(0))?;
+                Level::parse(text.as_str())
+            }
+            Method::Syslog => {
+                let rest = payload.trim_start().strip_prefix('<')?;
+                let end = rest.find('>')?;
+                let pri: u16 = rest[..end].parse().ok()?;
+                (pri <= 191).then(|| Level::from_syslog((pri % 8) as u8))
+            }
+        }
+    }
+}
+
+/// 依設定的規則從訊息擷取日誌等級
+#[derive(Debug, Clone, Default)]
+pub struct SeverityExtractor {
+    rules: Vec<CompiledRule>,
+}
+
+impl SeverityExtractor {
+    pub fn from_config(config: &SeverityConfig) -> Result<Self> {
+        let rules = config.rules.iter().map(CompiledRule::compile).collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn extract(&self, topic: &str, payload: &str) -> Option<Level> {
+        self.rules
+            .iter()
+            .filter(|rule| topic_matches(&rule.topic, topic))
+            .find_map(|rule| rule.extract(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules() {
+        let extractor = SeverityExtractor::from_config(&SeverityConfig::default()).unwrap();
+        assert_eq!(extractor.extract("app/log", r#"{"level":"warning","msg":"x"}"#), Some(Level::Warn));
+        assert_eq!(extractor.extract("app/log", r#"{"level":50}"#), Some(Level::Error));
+        assert_eq!(extractor.extract("app/log", "[ERROR] disk full"), Some(Level::Error));
+        assert_eq!(extractor.extract("app/log", "<11>Oct 18 host app: failed"), Some(Level::Error));
+        // 內文提到 information 或 topic 名稱含 error 不算
+        assert_eq!(extractor.extract("error_counter", "see information page"), None);
+    }
+
+    #[test]
+    fn test_rule_requires_one_method() {
+        let rule = SeverityRule {
+            topic: "#".to_string(),
+            json_path: Some("$.level".to_string()),
+            regex: None,
+            syslog: true,
+        };
+        assert!(SeverityExtractor::from_config(&SeverityConfig { rules: vec![rule] }).is_err());
+    }
+}
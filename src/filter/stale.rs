@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::StaleTopicRule;
+use crate::db::{TopicRates, TopicStat};
+use crate::filter::parse_duration;
+use crate::utils::topic_pattern::topic_matches;
+
+/// 依設定的預期發佈間隔判斷 topic 是否已沉寂，第一個符合的規則生效
+#[derive(Debug, Clone, Default)]
+pub struct StaleRules {
+    rules: Vec<(String, Duration)>, // (topic pattern, 預期間隔)
+}
+
+impl StaleRules {
+    /// 無效的規則略過並回傳錯誤訊息
+    pub fn from_config(config: &[StaleTopicRule]) -> (Self, Vec<String>) {
+        let mut rules = Vec::new();
+        let mut errors = Vec::new();
+        for rule in config {
+            match parse_duration(&rule.expected_interval) {
+                Ok(interval) if interval > Duration::zero() => rules.push((rule.topic.clone(), interval)),
+                Ok(_) => errors.push(format!("Stale topic rule '{}': interval must be positive", rule.topic)),
+                Err(e) => errors.push(format!("Stale topic rule '{}': {}", rule.topic, e)),
+            }
+        }
+        (Self { rules }, errors)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn expected_interval(&self, topic: &str) -> Option<Duration> {
+        self.rules
+            .iter()
+            .find(|(pattern, _)| topic_matches(pattern, topic))
+            .map(|(_, interval)| *interval)
+    }
+
+    /// 超過預期間隔未收到訊息時回傳已沉寂的時間
+    pub fn silence(&self, topic: &str, last_seen: DateTime<Utc>, now: DateTime<Utc>) -> Option<Duration> {
+        let silent = now - last_seen;
+        (silent > self.expected_interval(topic)?).then_some(silent)
+    }
+
+    /// 標記清單中已沉寂的 topic。last_seen 為整個資料庫各 topic 的最後訊息時間，
+    /// 因時間範圍等過濾而不在清單中的沉寂 topic 也補上 (沒有訊息的列)，include 決定是否列出
+    pub fn mark_topics(
+        &self,
+        topics: &mut Vec<TopicStat>,
+        last_seen: &HashMap<String, DateTime<Utc>>,
+        now: DateTime<Utc>,
+        include: impl Fn(&str) -> bool,
+    ) {
+        for topic in topics.iter_mut() {
+            let seen = last_seen.get(&topic.topic).copied().unwrap_or(topic.last_message_time);
+            topic.silent_for = self.silence(&topic.topic, seen, now);
+        }
+
+        let mut missing: Vec<TopicStat> = last_seen
+            .iter()
+            .filter(|(topic, _)| !topics.iter().any(|stat| &stat.topic == *topic) && include(topic))
+            .filter_map(|(topic, &seen)| {
+                let silent_for = self.silence(topic, seen, now)?;
+                Some(TopicStat {
+                    topic: topic.clone(),
+                    message_count: 0,
+                    last_message_time: seen,
+                    first_message_time: seen,
+                    latest_payload: None,
+                    quick_filter_counts: Vec::new(),
+                    total_bytes: 0,
+                    rates: TopicRates::default(),
+                    silent_for: Some(silent_for),
+                    muted: false,
+                })
+            })
+            .collect();
+        missing.sort_by_key(|topic| std::cmp::Reverse(topic.last_message_time));
+        topics.extend(missing);
+    }
+}
+
+/// 沉寂時間取最大的兩個單位：3d 4h、2h 5m、4m 10s、35s
+pub fn format_silence(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (days, hours, minutes, seconds) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(topic: &str, expected_interval: &str) -> StaleTopicRule {
+        StaleTopicRule {
+            topic: topic.to_string(),
+            expected_interval: expected_interval.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_mark_stale_topics() {
+        let (rules, errors) = StaleRules::from_config(&[
+            rule("sensors/+/status", "5m"),
+            rule("sensors/#", "1h"),
+            rule("broken", "soon"),
+        ]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(rules.expected_interval("sensors/a/status"), Some(Duration::minutes(5)));
+        assert_eq!(rules.expected_interval("sensors/a/temp"), Some(Duration::hours(1)));
+        assert_eq!(rules.expected_interval("app/log"), None);
+
+        let now = Utc::now();
+        let stat = |topic: &str, last: DateTime<Utc>| TopicStat {
+            topic: topic.to_string(),
+            message_count: 1,
+            last_message_time: last,
+            first_message_time: last,
+            latest_payload: None,
+            quick_filter_counts: Vec::new(),
+            total_bytes: 0,
+            rates: TopicRates::default(),
+            silent_for: None,
+            muted: false,
+        };
+        let mut topics = vec![
+            stat("sensors/a/status", now - Duration::minutes(10)),
+            stat("sensors/a/temp", now - Duration::minutes(10)),
+            stat("app/log", now - Duration::days(1)),
+        ];
+        // sensors/b/status 在時間範圍外，sensors/c/status 被 topic 過濾排除
+        let last_seen: HashMap<String, DateTime<Utc>> = [
+            ("sensors/a/status", now - Duration::minutes(10)),
+            ("sensors/b/status", now - Duration::days(2)),
+            ("sensors/c/status", now - Duration::days(2)),
+        ]
+        .into_iter()
+        .map(|(topic, time)| (topic.to_string(), time))
+        .collect();
+        rules.mark_topics(&mut topics, &last_seen, now, |topic| topic != "sensors/c/status");
+
+        let silent: Vec<(&str, Option<i64>)> = topics
+            .iter()
+            .map(|topic| (topic.topic.as_str(), topic.silent_for.map(|d| d.num_minutes())))
+            .collect();
+        assert_eq!(silent, vec![
+            ("sensors/a/status", Some(10)),
+            ("sensors/a/temp", None),
+            ("app/log", None),
+            ("sensors/b/status", Some(2 * 24 * 60)),
+        ]);
+        assert_eq!(topics[3].message_count, 0);
+
+        assert_eq!(format_silence(Duration::seconds(35)), "35s");
+        assert_eq!(format_silence(Duration::seconds(250)), "4m 10s");
+        assert_eq!(format_silence(Duration::minutes(3 * 24 * 60 + 250)), "3d 4h");
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+// 時間過濾欄位的預設值：最近 24 小時，隨時間滑動
+pub const DEFAULT_TIME_FROM: &str = "-24h";
+pub const DEFAULT_TIME_TO: &str = "now";
+
+/// 時間過濾欄位的內容；相對時間在每次查詢時依當下時間重新計算
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeExpr {
+    Absolute(DateTime<Local>),
+    Now,
+    Ago(Duration),   // -15m、last 2h、2h ago
+    Ahead(Duration), // +1h
+    Today,           // 今天 00:00
+    Yesterday,       // 昨天 00:00
+}
+
+impl TimeExpr {
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let lower = text.to_ascii_lowercase();
+
+        match lower.as_str() {
+            "now" => return Ok(TimeExpr::Now),
+            "today" => return Ok(TimeExpr::Today),
+            "yesterday" => return Ok(TimeExpr::Yesterday),
+            _ => {}
+        }
+
+        let relative = if let Some(rest) = lower.strip_prefix('-') {
+            Some(TimeExpr::Ago(parse_duration(rest)?))
+        } else if let Some(rest) = lower.strip_prefix('+') {
+            Some(TimeExpr::Ahead(parse_duration(rest)?))
+        } else if let Some(rest) = lower.strip_prefix("last ") {
+            Some(TimeExpr::Ago(parse_duration(rest)?))
+        } else if let Some(rest) = lower.strip_suffix(" ago") {
+            Some(TimeExpr::Ago(parse_duration(rest)?))
+        } else {
+            None
+        };
+        if let Some(expr) = relative {
+            return Ok(expr);
+        }
+
+        parse_absolute(text)
+            .map(TimeExpr::Absolute)
+            .ok_or_else(|| anyhow!("Invalid time '{}' (use YYYY-MM-DD HH:MM:SS, now, today, -15m, last 2h)", text))
+    }
+
+    pub fn resolve(&self, now: DateTime<Local>) -> DateTime<Local> {
+        match self {
+            TimeExpr::Absolute(time) => *time,
+            TimeExpr::Now => now,
+            TimeExpr::Ago(duration) => now - *duration,
+            TimeExpr::Ahead(duration) => now + *duration,
+            TimeExpr::Today => start_of_day(now.date_naive()).unwrap_or(now),
+            TimeExpr::Yesterday => start_of_day(now.date_naive() - Duration::days(1)).unwrap_or(now),
+        }
+    }
+}
+
+/// 解析時間欄位，空字串表示不限制
+pub fn resolve_time_input(text: &str, now: DateTime<Local>) -> Result<Option<DateTime<Utc>>> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(TimeExpr::parse(text)?.resolve(now).with_timezone(&Utc)))
+}
+
+/// 15m、2h30m、1 hour、3 days
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    let mut total = Duration::zero();
+    let mut rest = text;
+
+    if rest.is_empty() {
+        bail!("Missing duration");
+    }
+
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            bail!("Invalid duration '{}'", text);
+        }
+        let amount: i64 = rest[..digits].parse()?;
+        let after = rest[digits..].trim_start();
+        let unit_len = after.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(after.len());
+        let unit = &after[..unit_len];
+
+        total += match unit {
+            "s" | "sec" | "secs" | "second" | "seconds" => Duration::seconds(amount),
+            "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(amount),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(amount),
+            "d" | "day" | "days" => Duration::days(amount),
+            "w" | "week" | "weeks" => Duration::weeks(amount),
+            _ => bail!("Unknown time unit '{}' in '{}'", unit, text),
+        };
+        rest = after[unit_len..].trim_start();
+    }
+
+    Ok(total)
+}
+
+fn parse_absolute(text: &str) -> Option<DateTime<Local>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Local));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(text, format) {
+            return Local.from_local_datetime(&naive).earliest();
+        }
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().and_then(start_of_day)
+}
+
+fn start_of_day(date: NaiveDate) -> Option<DateTime<Local>> {
+    date.and_hms_opt(0, 0, 0).and_then(|naive| Local.from_local_datetime(&naive).earliest())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let resolve = |text: &str| TimeExpr::parse(text).unwrap().resolve(now).format("%Y-%m-%d %H:%M:%S").to_string();
+
+        assert_eq!(resolve("-15m"), "2024-05-01 11:45:00");
+        assert_eq!(resolve("last 2h"), "2024-05-01 10:00:00");
+        assert_eq!(resolve("1h 30m ago"), "2024-05-01 10:30:00");
+        assert_eq!(resolve("+1d"), "2024-05-02 12:00:00");
+        assert_eq!(resolve("today"), "2024-05-01 00:00:00");
+        assert_eq!(resolve("Yesterday"), "2024-04-30 00:00:00");
+        assert_eq!(resolve("NOW"), "2024-05-01 12:00:00");
+        assert_eq!(resolve("2024-01-02 03:04:05"), "2024-01-02 03:04:05");
+        assert_eq!(resolve("2024-01-02"), "2024-01-02 00:00:00");
+
+        assert!(TimeExpr::parse("-15x").is_err());
+        assert!(TimeExpr::parse("last").is_err());
+        assert!(TimeExpr::parse("2024-13-01").is_err());
+    }
+}
@@ -1,22 +1,43 @@
+mod alerts;
+mod cli;
 mod config;
 mod db;
+mod decoders;
+mod filter;
 mod mqtt;
 mod ui;
 mod utils;
 
+use std::sync::Arc;
 use anyhow::Result;
-use tokio::sync::mpsc;
 use tracing::{info, error, Level};
 use tracing_subscriber;
 
+use alerts::{AlertDispatcher, AlertEngine};
+use cli::CliArgs;
 use config::Config;
 use db::MessageRepository;
-use mqtt::{MqttClient, MessageHandler, MqttMessage, ConnectionEvent};
-use std::sync::{Arc, Mutex};
+use decoders::DecoderRegistry;
+use filter::{MuteRules, Query, SeverityExtractor};
+use mqtt::{MqttClient, MessageHandler, IngestQueue, ConnectionEvent};
 use ui::App;
 
+// How often expired trash is purged (and old messages trashed when database.auto_cleanup is enabled)
+const RETENTION_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = CliArgs::parse()?;
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    
+    // Headless query: print matching messages and exit, leaving the log file untouched
+    if let Some(query) = &args.query {
+        return run_query(&args, query).await;
+    }
+    
     // Initialize logging to file - clear existing log on startup
     let log_file = std::fs::OpenOptions::new()
         .create(true)
@@ -36,33 +57,77 @@ async fn main() -> Result<()> {
     let config = Config::load()?;
     info!("Configuration loaded from: {:?}", config);
 
+    // Offline browse mode: no MQTT client, no ingest, read-only database
+    if let Some(path) = &args.open {
+        return run_archive(config, path).await;
+    }
+
     // Initialize database
-    let repository = MessageRepository::new(&config.database.path).await?;
+    let repository = MessageRepository::new(
+        &config.database.path,
+        config.database.busy_timeout(),
+    ).await?;
     info!("Database initialized");
 
-    // Create message channel
-    let (message_sender, message_receiver) = mpsc::unbounded_channel::<MqttMessage>();
+    // Bounded queue between MQTT and the database writer
+    let ingest_queue = IngestQueue::from_config(&config.performance);
+
+    // Payload decoders (MessagePack / CBOR / Protobuf) mapped by topic pattern
+    let decoders = Arc::new(DecoderRegistry::from_config(&config.decoders)?);
 
-    // Initialize MQTT client with connection status callback
-    let (mqtt_client, event_receiver) = MqttClient::new(&config.mqtt, message_sender)?;
+    // Mute rules are shared with the UI so topics muted there stop being stored right away
+    let mutes = MuteRules::new(&config.mutes);
+
+    // Initialize MQTT client; connection state changes arrive on connection_events
+    let (mqtt_client, event_receiver, connection_events) = MqttClient::new(
+        &config.mqtt,
+        ingest_queue.clone(),
+        decoders,
+        mutes.clone(),
+        config.performance.event_channel_capacity,
+    )?;
     info!("MQTT client initialized");
 
+    // Alert rules are evaluated as messages are written; fired alerts are shown in the UI.
+    // Without rules there is no dispatcher and no silence check at all
+    let alert_engine = AlertEngine::from_config(&config.alerts)?;
+    let mut alert_receiver = None;
+    let alerts = if alert_engine.is_empty() {
+        None
+    } else {
+        let (alert_sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        alert_receiver = Some(receiver);
+        let alert_dispatcher = AlertDispatcher::new(
+            repository.clone(),
+            Some(mqtt_client.get_client().clone()),
+            Some(alert_sender),
+        );
+        Some((alert_engine, alert_dispatcher))
+    };
+
     // Initialize message handler
-    let mut message_handler = MessageHandler::new(repository.clone(), message_receiver);
+    let mut message_handler = MessageHandler::new(
+        repository.clone(),
+        ingest_queue.clone(),
+        config.performance.batch_size,
+        SeverityExtractor::from_config(&config.severity)?,
+        alerts,
+    );
 
-    // Create shared connection status
-    let connection_status = Arc::new(Mutex::new(false));
-    
     // Initialize UI application
-    let mut app = App::new(config.clone()).await?;
-    app.update_connection_status_from_mqtt(false); // Start as disconnected
+    let mut app = App::new(config.clone(), repository.clone()).await?;
+    app.apply_connection_event(ConnectionEvent::Connecting);
+    app.set_ingest_queue(ingest_queue.clone());
+    if let Some(receiver) = alert_receiver {
+        app.set_alert_receiver(receiver);
+    }
+    app.set_mute_rules(mutes);
 
     // Spawn background tasks
     let mqtt_handle = {
         let client = mqtt_client.clone();
-        let status = connection_status.clone();
         tokio::spawn(async move {
-            client.handle_events_with_status(event_receiver, status).await;
+            client.handle_events(event_receiver).await;
         })
     };
 
@@ -72,6 +137,28 @@ async fn main() -> Result<()> {
         }
     });
 
+    // Retention: purge trash older than cleanup_days at startup and then hourly.
+    // Old messages are only moved to trash when auto_cleanup is enabled.
+    let retention_handle = (config.database.cleanup_days > 0).then(|| {
+        let repository = repository.clone();
+        let days = config.database.cleanup_days;
+        let auto_cleanup = config.database.auto_cleanup;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+            loop {
+                interval.tick().await;
+                if auto_cleanup {
+                    if let Err(e) = repository.cleanup_old_messages(days).await {
+                        error!("Moving old messages to trash failed: {}", e);
+                    }
+                }
+                if let Err(e) = repository.purge_expired_trash(days).await {
+                    error!("Purging expired trash failed: {}", e);
+                }
+            }
+        })
+    });
+
     // Don't spawn connection task separately - let the event handler manage it
     // Just trigger the initial connection
     tokio::spawn({
@@ -84,7 +171,7 @@ async fn main() -> Result<()> {
     });
 
     // Run the UI application with connection status monitoring
-    let app_result = app.run_with_connection_status(connection_status).await;
+    let app_result = app.run_with_connection_status(Some(connection_events)).await;
 
     // Cleanup
     info!("Shutting down...");
@@ -94,9 +181,15 @@ async fn main() -> Result<()> {
         error!("Error disconnecting MQTT client: {}", e);
     }
 
-    // Cancel background tasks
+    // Cancel background tasks; give the handler a moment to flush what is still queued
     mqtt_handle.abort();
-    handler_handle.abort();
+    if let Some(handle) = retention_handle {
+        handle.abort();
+    }
+    ingest_queue.close();
+    if tokio::time::timeout(std::time::Duration::from_secs(2), handler_handle).await.is_err() {
+        error!("Timed out flushing ingest queue, {} messages not written", ingest_queue.stats().queued);
+    }
 
     match app_result {
         Ok(_) => {
@@ -109,3 +202,37 @@ async fn main() -> Result<()> {
         }
     }
 }
+
+async fn run_archive(config: Config, path: &str) -> Result<()> {
+    let repository = MessageRepository::open_read_only(path, config.database.busy_timeout()).await?;
+    info!("Opened archived database read-only: {}", path);
+
+    let mut app = App::new(config, repository).await?;
+    app.show_archive(path).await?;
+
+    let result = app.run_with_connection_status(None).await;
+    if let Err(e) = &result {
+        error!("Application error: {}", e);
+    }
+    result
+}
+
+async fn run_query(args: &CliArgs, query: &str) -> Result<()> {
+    let query = Query::parse(query).map_err(|e| anyhow::anyhow!("Query error: {}", e))?;
+    let config = Config::load()?;
+    let path = args.open.clone().unwrap_or_else(|| config.database.path.clone());
+    let repository = MessageRepository::open_read_only(&path, config.database.busy_timeout()).await?;
+
+    // 取最新的 N 筆，依時間先後輸出
+    let mut messages = repository.query_messages(&query, args.limit).await?;
+    messages.reverse();
+    for message in messages {
+        println!(
+            "{}\t{}\t{}",
+            message.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S%.3f"),
+            message.topic,
+            message.payload.replace('\n', " "),
+        );
+    }
+    Ok(())
+}
@@ -1,31 +1,161 @@
-use rumqttc::{AsyncClient, MqttOptions, Event, Packet, QoS};
+use rumqttc::{AsyncClient, ConnectReturnCode, ConnectionError, MqttOptions, Event, Outgoing, Packet, QoS};
 use tokio::sync::mpsc;
 use tracing::{info, warn, error, debug};
-use anyhow::Result;
-use std::time::Duration;
+use anyhow::{anyhow, Result};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use crate::config::MqttConfig;
+use crate::config::{MqttConfig, ReconnectConfig};
+use crate::decoders::DecoderRegistry;
+use crate::filter::MuteRules;
 use super::handler::MqttMessage;
+use super::queue::IngestQueue;
 
 // Connection status events that can be sent to UI
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionEvent {
-    Connected,
+    Connecting,
+    Connected { session_present: bool },
     Disconnected,
+    Reconnecting {
+        attempt: u32,
+        delay: Duration,
+        error: String,
+    },
+    AuthFailed {
+        attempt: u32,
+        delay: Duration,
+        error: String,
+    },
     Error(String),
 }
 
+// 指數退避：每次失敗後延遲乘上 multiplier，直到 max_delay 為止
+#[derive(Debug, Clone)]
+struct Backoff {
+    initial: Duration,
+    max: Duration,
+    multiplier: f64,
+    attempt: u32,
+}
+
+impl Backoff {
+    fn new(config: &ReconnectConfig) -> Self {
+        Self {
+            initial: Duration::from_millis(config.initial_delay_ms),
+            max: Duration::from_millis(config.max_delay_ms.max(config.initial_delay_ms)),
+            multiplier: config.multiplier.max(1.0),
+            attempt: 0,
+        }
+    }
+
+    // 回傳 (第幾次重試, 本次等待時間)
+    fn next_delay(&mut self) -> (u32, Duration) {
+        self.attempt = self.attempt.saturating_add(1);
+        let factor = self.multiplier.powi(self.attempt.saturating_sub(1).min(64) as i32);
+        let millis = (self.initial.as_millis() as f64 * factor).min(self.max.as_millis() as f64);
+        (self.attempt, Duration::from_millis(millis as u64))
+    }
+
+    fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+fn is_auth_error(error: &ConnectionError) -> bool {
+    matches!(
+        error,
+        ConnectionError::ConnectionRefused(ConnectReturnCode::BadUserNamePassword)
+            | ConnectionError::ConnectionRefused(ConnectReturnCode::NotAuthorized)
+    )
+}
+
+// 補送的離線訊息結束後，broker 停止送訊息多久才視為已開始接收即時訊息
+const BACKLOG_QUIET_PERIOD: Duration = Duration::from_millis(500);
+
+/// 推測哪些訊息是 broker 補送的離線訊息。
+///
+/// MQTT 沒有標示補送結束的封包，這裡假設補送是恢復 session 後的一段連續訊息：
+/// 本次連線送出的訂閱都收到 SubAck，且訊息停頓超過 quiet_period 之後才算即時訊息。
+/// 因為 session 中原有的訂閱在重連後立刻生效，補送期間收到的即時訊息仍會被標示為 backlog；
+/// 補送中途停頓超過 quiet_period 時，之後的補送訊息則會被當成即時訊息。
+#[derive(Debug)]
+struct BacklogTracker {
+    active: bool,
+    pending_subscriptions: usize, // 已送出但尚未收到 SubAck 的訂閱
+    last_backlog_at: Instant,     // 最後一筆補送訊息 (或 ConnAck) 的時間
+    quiet_period: Duration,
+}
+
+impl BacklogTracker {
+    fn new(quiet_period: Duration) -> Self {
+        Self {
+            active: false,
+            pending_subscriptions: 0,
+            last_backlog_at: Instant::now(),
+            quiet_period,
+        }
+    }
+
+    fn on_connack(&mut self, session_present: bool, now: Instant) {
+        self.active = session_present;
+        self.pending_subscriptions = 0;
+        self.last_backlog_at = now;
+    }
+
+    fn on_subscribe(&mut self) {
+        self.pending_subscriptions += 1;
+    }
+
+    fn on_suback(&mut self) {
+        self.pending_subscriptions = self.pending_subscriptions.saturating_sub(1);
+    }
+
+    fn on_disconnect(&mut self) {
+        self.active = false;
+    }
+
+    /// 收到 PUBLISH 時判斷是否為補送的訊息
+    fn is_backlog(&mut self, now: Instant) -> bool {
+        if !self.active {
+            return false;
+        }
+        if self.pending_subscriptions == 0 && now.duration_since(self.last_backlog_at) >= self.quiet_period {
+            self.active = false;
+            info!("Backlog delivery finished, receiving live traffic");
+            return false;
+        }
+        self.last_backlog_at = now;
+        true
+    }
+}
+
 #[derive(Clone)]
 pub struct MqttClient {
     client: AsyncClient,
-    message_sender: mpsc::UnboundedSender<MqttMessage>,
+    queue: IngestQueue,
+    decoders: Arc<DecoderRegistry>,
+    mutes: MuteRules, // mode = drop 的 topic 不放進寫入佇列
+    subscribe_qos: QoS,
+    // 恢復持久 session 後，標示 broker 補送的離線訊息
+    backlog: Arc<Mutex<BacklogTracker>>,
 }
 
 impl MqttClient {
     pub fn new(
         config: &MqttConfig,
-        message_sender: mpsc::UnboundedSender<MqttMessage>,
-    ) -> Result<(Self, mpsc::UnboundedReceiver<rumqttc::Event>)> {
+        queue: IngestQueue,
+        decoders: Arc<DecoderRegistry>,
+        mutes: MuteRules,
+        event_capacity: usize,
+    ) -> Result<(
+        Self,
+        mpsc::Receiver<rumqttc::Event>,
+        mpsc::UnboundedReceiver<ConnectionEvent>,
+    )> {
+        let subscribe_qos = rumqttc::qos(config.subscribe_qos)
+            .map_err(|_| anyhow!("Invalid mqtt.subscribe_qos {} (expected 0, 1 or 2)", config.subscribe_qos))?;
+        
         let mut mqtt_options = MqttOptions::new(&config.client_id, &config.host, config.port);
         
         // Set authentication if provided
@@ -35,25 +165,57 @@ impl MqttClient {
         
         // Configure connection options
         mqtt_options.set_keep_alive(Duration::from_secs(30));
-        mqtt_options.set_clean_session(true);
+        mqtt_options.set_clean_session(config.clean_session);
         mqtt_options.set_max_packet_size(1024 * 1024, 1024 * 1024); // 1MB
         
         let (client, mut eventloop) = AsyncClient::new(mqtt_options, 100);
-        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        // 有上限的 channel：事件處理跟不上時 eventloop 會在 send 等待，不會無限制累積
+        let (event_sender, event_receiver) = mpsc::channel(event_capacity.max(1));
+        let (status_sender, status_receiver) = mpsc::unbounded_channel();
+        let mut backoff = Backoff::new(&config.reconnect);
         
         // Spawn eventloop task
         tokio::spawn(async move {
+            // UI 可能已經關閉，狀態事件送不出去時直接忽略
+            let _ = status_sender.send(ConnectionEvent::Connecting);
+            
             loop {
                 match eventloop.poll().await {
                     Ok(event) => {
-                        if let Err(_) = event_sender.send(event.clone()) {
+                        match &event {
+                            Event::Incoming(Packet::ConnAck(connack)) if connack.code == ConnectReturnCode::Success => {
+                                backoff.reset();
+                                let _ = status_sender.send(ConnectionEvent::Connected {
+                                    session_present: connack.session_present,
+                                });
+                            }
+                            Event::Incoming(Packet::Disconnect) | Event::Outgoing(Outgoing::Disconnect) => {
+                                let _ = status_sender.send(ConnectionEvent::Disconnected);
+                            }
+                            _ => {}
+                        }
+                        
+                        if event_sender.send(event).await.is_err() {
                             error!("Failed to send MQTT event to handler");
+                            let _ = status_sender.send(ConnectionEvent::Error("event handler stopped".to_string()));
                             break;
                         }
                     }
                     Err(e) => {
-                        error!("MQTT eventloop error: {}", e);
-                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        let (attempt, delay) = backoff.next_delay();
+                        let error_text = e.to_string();
+                        
+                        let status = if is_auth_error(&e) {
+                            error!("MQTT authentication failed (attempt {}): {}", attempt, error_text);
+                            ConnectionEvent::AuthFailed { attempt, delay, error: error_text }
+                        } else {
+                            error!("MQTT eventloop error (attempt {}), retrying in {:?}: {}", attempt, delay, error_text);
+                            ConnectionEvent::Reconnecting { attempt, delay, error: error_text }
+                        };
+                        let _ = status_sender.send(status);
+                        
+                        tokio::time::sleep(delay).await;
+                        let _ = status_sender.send(ConnectionEvent::Connecting);
                     }
                 }
             }
@@ -61,23 +223,27 @@ impl MqttClient {
         
         let mqtt_client = Self {
             client,
-            message_sender,
+            queue,
+            decoders,
+            mutes,
+            subscribe_qos,
+            backlog: Arc::new(Mutex::new(BacklogTracker::new(BACKLOG_QUIET_PERIOD))),
         };
         
-        Ok((mqtt_client, event_receiver))
+        Ok((mqtt_client, event_receiver, status_receiver))
     }
     
     pub async fn connect_and_subscribe(&self) -> Result<()> {
         info!("Connecting to MQTT broker and subscribing to all topics...");
         
         // Subscribe to all topics using wildcard
-        self.client.subscribe("#", QoS::AtMostOnce).await?;
+        self.client.subscribe("#", self.subscribe_qos).await?;
         
-        info!("Successfully subscribed to all topics (#)");
+        info!("Successfully subscribed to all topics (#) with {:?}", self.subscribe_qos);
         Ok(())
     }
     
-    pub async fn handle_events(&self, mut event_receiver: mpsc::UnboundedReceiver<rumqttc::Event>) {
+    pub async fn handle_events(&self, mut event_receiver: mpsc::Receiver<rumqttc::Event>) {
         info!("Starting MQTT event handler...");
         
         while let Some(event) = event_receiver.recv().await {
@@ -87,47 +253,19 @@ impl MqttClient {
         }
     }
     
-    pub async fn handle_events_with_status(
-        &self, 
-        mut event_receiver: mpsc::UnboundedReceiver<rumqttc::Event>,
-        connection_status: std::sync::Arc<std::sync::Mutex<bool>>
-    ) {
-        info!("Starting MQTT event handler with status monitoring...");
-        
-        while let Some(event) = event_receiver.recv().await {
-            // Update connection status based on event
-            match &event {
-                Event::Incoming(Packet::ConnAck(connack)) if connack.code == rumqttc::ConnectReturnCode::Success => {
-                    if let Ok(mut status) = connection_status.lock() {
-                        *status = true;
-                        info!("Connection status updated to: connected");
-                    }
-                }
-                Event::Incoming(Packet::Disconnect) | Event::Incoming(Packet::PingResp) => {
-                    if matches!(event, Event::Incoming(Packet::Disconnect)) {
-                        if let Ok(mut status) = connection_status.lock() {
-                            *status = false;
-                            info!("Connection status updated to: disconnected");
-                        }
-                    }
-                }
-                _ => {}
-            }
-            
-            if let Err(e) = self.process_event(event).await {
-                error!("Error processing MQTT event: {}", e);
-            }
-        }
-    }
-    
     async fn process_event(&self, event: Event) -> Result<()> {
         match event {
             Event::Incoming(Packet::Connect(_)) => {
                 info!("MQTT Connected");
             }
             Event::Incoming(Packet::ConnAck(connack)) => {
-                info!("MQTT Connection acknowledged: {:?}", connack.code);
+                info!("MQTT Connection acknowledged: {:?} (session_present={})", connack.code, connack.session_present);
                 if connack.code == rumqttc::ConnectReturnCode::Success {
+                    self.backlog.lock().unwrap().on_connack(connack.session_present, Instant::now());
+                    if connack.session_present {
+                        info!("Resumed persistent session, receiving queued backlog");
+                    }
+                    
                     // Subscribe after successful connection
                     if let Err(e) = self.connect_and_subscribe().await {
                         error!("Failed to subscribe after connection: {}", e);
@@ -135,24 +273,40 @@ impl MqttClient {
                 }
             }
             Event::Incoming(Packet::SubAck(suback)) => {
-                info!("Subscription acknowledged for packet ID: {}", suback.pkid);
+                info!("Subscription acknowledged for packet ID: {} ({:?})", suback.pkid, suback.return_codes);
+                self.backlog.lock().unwrap().on_suback();
             }
             Event::Incoming(Packet::Publish(publish)) => {
                 debug!("Received message on topic: {}", publish.topic);
+                if self.mutes.should_drop(&publish.topic) {
+                    debug!("Dropped message on muted topic: {}", publish.topic);
+                    return Ok(());
+                }
                 
+                let decoded = self.decoders.decode(&publish.topic, &publish.payload);
                 let message = MqttMessage {
                     topic: publish.topic.clone(),
-                    payload: String::from_utf8_lossy(&publish.payload).to_string(),
+                    raw_payload: decoded.raw_payload(&publish.payload),
+                    payload: decoded.text,
+                    encoding: decoded.encoding,
+                    marker: decoded.marker,
                     qos: publish.qos as i32,
                     retain: publish.retain,
+                    backlog: self.backlog.lock().unwrap().is_backlog(Instant::now()),
                 };
                 
-                if let Err(e) = self.message_sender.send(message) {
-                    error!("Failed to send message to handler: {}", e);
+                // 依 overflow_policy 可能在此等待或丟棄
+                if !self.queue.push(message).await {
+                    error!("Failed to queue message: ingest queue closed");
                 }
             }
             Event::Incoming(Packet::Disconnect) => {
                 warn!("MQTT Disconnected");
+                self.backlog.lock().unwrap().on_disconnect();
+            }
+            Event::Outgoing(Outgoing::Subscribe(pkid)) => {
+                debug!("Outgoing MQTT subscribe: {}", pkid);
+                self.backlog.lock().unwrap().on_subscribe();
             }
             Event::Outgoing(packet) => {
                 debug!("Outgoing MQTT packet: {:?}", packet);
@@ -174,4 +328,75 @@ impl MqttClient {
     pub fn get_client(&self) -> &AsyncClient {
         &self.client
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let mut backoff = Backoff::new(&ReconnectConfig {
+            initial_delay_ms: 1000,
+            max_delay_ms: 5000,
+            multiplier: 2.0,
+        });
+
+        assert_eq!(backoff.next_delay(), (1, Duration::from_millis(1000)));
+        assert_eq!(backoff.next_delay(), (2, Duration::from_millis(2000)));
+        assert_eq!(backoff.next_delay(), (3, Duration::from_millis(4000)));
+        assert_eq!(backoff.next_delay(), (4, Duration::from_millis(5000)));
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), (1, Duration::from_millis(1000)));
+    }
+
+    #[tokio::test]
+    async fn test_backlog_flag_follows_session_and_subscriptions() {
+        use rumqttc::{ConnAck, Publish, SubAck, SubscribeReasonCode};
+
+        let (client, _eventloop) = AsyncClient::new(MqttOptions::new("test", "127.0.0.1", 1883), 10);
+        let queue = IngestQueue::new(100, 1024 * 1024, crate::config::OverflowPolicy::Block);
+        let mqtt = MqttClient {
+            client,
+            queue: queue.clone(),
+            decoders: Arc::new(DecoderRegistry::default()),
+            mutes: MuteRules::default(),
+            subscribe_qos: QoS::AtLeastOnce,
+            backlog: Arc::new(Mutex::new(BacklogTracker::new(Duration::from_millis(50)))),
+        };
+        let publish = |topic: &str| Event::Incoming(Packet::Publish(Publish::new(topic, QoS::AtLeastOnce, "1")));
+        let connack = |session_present| Event::Incoming(Packet::ConnAck(ConnAck {
+            session_present,
+            code: ConnectReturnCode::Success,
+        }));
+        let suback = || Event::Incoming(Packet::SubAck(SubAck::new(1, vec![SubscribeReasonCode::Success(QoS::AtLeastOnce)])));
+
+        // 恢復 session：SubAck 前後的連續訊息都是補送，停頓之後才是即時訊息
+        mqtt.process_event(connack(true)).await.unwrap();
+        mqtt.process_event(Event::Outgoing(Outgoing::Subscribe(1))).await.unwrap();
+        mqtt.process_event(publish("queued/1")).await.unwrap();
+        mqtt.process_event(suback()).await.unwrap();
+        mqtt.process_event(publish("queued/2")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        mqtt.process_event(publish("live/1")).await.unwrap();
+
+        // 訂閱尚未確認時不因停頓結束補送
+        mqtt.process_event(connack(true)).await.unwrap();
+        mqtt.process_event(Event::Outgoing(Outgoing::Subscribe(2))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        mqtt.process_event(publish("queued/3")).await.unwrap();
+
+        // 新的 session 沒有補送
+        mqtt.process_event(connack(false)).await.unwrap();
+        mqtt.process_event(publish("live/2")).await.unwrap();
+
+        let mut flags = Vec::new();
+        for _ in 0..5 {
+            let message = queue.pop().await.unwrap();
+            flags.push((message.topic, message.backlog));
+        }
+        let expected = [("queued/1", true), ("queued/2", true), ("live/1", false), ("queued/3", true), ("live/2", false)];
+        assert_eq!(flags, expected.map(|(topic, backlog)| (topic.to_string(), backlog)));
+    }
+}
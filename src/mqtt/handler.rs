@@ -1,31 +1,46 @@
-use tokio::sync::mpsc;
 use tracing::{info, error, debug};
 use anyhow::Result;
 use chrono::Utc;
 
+use crate::alerts::{AlertDispatcher, AlertEngine};
 use crate::db::{MessageRepository, Message};
+use crate::filter::SeverityExtractor;
+use super::queue::IngestQueue;
 
 #[derive(Debug, Clone)]
 pub struct MqttMessage {
     pub topic: String,
     pub payload: String,
+    pub encoding: Option<String>, // 經解碼器轉成 JSON 時的原始編碼
+    pub raw_payload: Option<String>, // 解碼或非 UTF-8 時的原始位元組 (base64)
+    pub marker: Option<String>,
     pub qos: i32,
     pub retain: bool,
+    pub backlog: bool,
 }
 
 pub struct MessageHandler {
     repository: MessageRepository,
-    message_receiver: mpsc::UnboundedReceiver<MqttMessage>,
+    queue: IngestQueue,
+    batch_size: usize,
+    severity: SeverityExtractor,
+    alerts: Option<(AlertEngine, AlertDispatcher)>, // 沒有告警規則時為 None
 }
 
 impl MessageHandler {
     pub fn new(
         repository: MessageRepository,
-        message_receiver: mpsc::UnboundedReceiver<MqttMessage>,
+        queue: IngestQueue,
+        batch_size: usize,
+        severity: SeverityExtractor,
+        alerts: Option<(AlertEngine, AlertDispatcher)>,
     ) -> Self {
         Self {
             repository,
-            message_receiver,
+            queue,
+            batch_size: batch_size.max(1),
+            severity,
+            alerts,
         }
     }
     
@@ -33,23 +48,37 @@ impl MessageHandler {
         info!("Starting MQTT message handler...");
         
         let mut batch = Vec::new();
-        let batch_size = 100;
+        let batch_size = self.batch_size;
         let mut batch_timeout = tokio::time::interval(std::time::Duration::from_millis(1000));
         
+        // silence 規則從資料庫中各 topic 的最後訊息開始計算
+        if let Some((engine, _)) = self.alerts.as_mut().filter(|(engine, _)| engine.has_silence_rules()) {
+            match self.repository.get_last_seen_times().await {
+                Ok(last_seen) => engine.seed_last_seen(&last_seen),
+                Err(e) => error!("Failed to load last seen times for alerts: {}", e),
+            }
+        }
+        
         loop {
             tokio::select! {
                 // Process incoming messages
-                message = self.message_receiver.recv() => {
+                message = self.queue.pop() => {
                     match message {
                         Some(mqtt_msg) => {
                             debug!("Received message on topic: {}", mqtt_msg.topic);
                             
-                            let db_message = Message::new(
+                            let level = self.severity.extract(&mqtt_msg.topic, &mqtt_msg.payload);
+                            let mut db_message = Message::new(
                                 mqtt_msg.topic,
                                 mqtt_msg.payload,
                                 mqtt_msg.qos,
                                 mqtt_msg.retain,
                             );
+                            db_message.backlog = mqtt_msg.backlog;
+                            db_message.encoding = mqtt_msg.encoding;
+                            db_message.raw_payload = mqtt_msg.raw_payload;
+                            db_message.marker = mqtt_msg.marker;
+                            db_message.level = level.map(|l| l.as_str().to_string());
                             
                             batch.push(db_message);
                             
@@ -61,7 +90,7 @@ impl MessageHandler {
                             }
                         }
                         None => {
-                            info!("Message channel closed, shutting down handler");
+                            info!("Ingest queue closed, shutting down handler");
                             break;
                         }
                     }
@@ -74,6 +103,11 @@ impl MessageHandler {
                             error!("Failed to process timed batch: {}", e);
                         }
                     }
+                    if let Some((engine, dispatcher)) = self.alerts.as_mut().filter(|(engine, _)| engine.has_silence_rules()) {
+                        for fired in engine.check_silence(Utc::now()) {
+                            dispatcher.dispatch(fired).await;
+                        }
+                    }
                 }
             }
         }
@@ -89,24 +123,32 @@ impl MessageHandler {
         Ok(())
     }
     
-    async fn process_batch(&self, batch: &mut Vec<Message>) -> Result<()> {
+    async fn process_batch(&mut self, batch: &mut Vec<Message>) -> Result<()> {
         if batch.is_empty() {
             return Ok(());
         }
         
         debug!("Processing batch of {} messages", batch.len());
         
-        for message in batch.drain(..) {
+        for mut message in batch.drain(..) {
             match self.repository.insert_message(&message).await {
                 Ok(id) => {
                     debug!("Successfully inserted message with ID {}: topic={}, payload_len={}", 
                           id, message.topic, message.payload.len());
+                    message.id = Some(id);
                 }
                 Err(e) => {
                     error!("Failed to insert message for topic '{}': {}", message.topic, e);
                     // Continue processing other messages even if one fails
                 }
             }
+            
+            // 寫入後才評估告警，告警可以連到觸發的訊息
+            if let Some((engine, dispatcher)) = self.alerts.as_mut() {
+                for fired in engine.on_message(&message) {
+                    dispatcher.dispatch(fired).await;
+                }
+            }
         }
         
         Ok(())
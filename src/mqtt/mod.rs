@@ -1,5 +1,7 @@
 pub mod client;
 pub mod handler;
+pub mod queue;
 
 pub use client::*;
-pub use handler::*;
\ No newline at end of file
+pub use handler::*;
+pub use queue::*;
\ No newline at end of file
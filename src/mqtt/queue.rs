@@ -0,0 +1,220 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tracing::warn;
+
+use crate::config::{OverflowPolicy, PerformanceConfig};
+use super::handler::MqttMessage;
+
+/// 佇列目前狀態，給 status bar 顯示
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QueueStats {
+    pub queued: usize,
+    pub queued_bytes: usize,
+    pub dropped: u64,
+}
+
+struct QueueState {
+    items: VecDeque<MqttMessage>,
+    bytes: usize,
+    closed: bool,
+}
+
+struct Inner {
+    state: Mutex<QueueState>,
+    capacity: usize,
+    max_bytes: usize,
+    policy: OverflowPolicy,
+    not_empty: Notify,
+    not_full: Notify,
+    dropped: AtomicU64,
+}
+
+/// MQTT 與 MessageHandler 之間有上限的待寫入佇列
+#[derive(Clone)]
+pub struct IngestQueue {
+    inner: Arc<Inner>,
+}
+
+fn message_size(message: &MqttMessage) -> usize {
+    message.topic.len() + message.payload.len()
+}
+
+impl IngestQueue {
+    pub fn new(capacity: usize, max_bytes: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(QueueState {
+                    items: VecDeque::new(),
+                    bytes: 0,
+                    closed: false,
+                }),
+                capacity: capacity.max(1),
+                max_bytes: max_bytes.max(1),
+                policy,
+                not_empty: Notify::new(),
+                not_full: Notify::new(),
+                dropped: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub fn from_config(config: &PerformanceConfig) -> Self {
+        let max_bytes = (config.max_memory_mb as usize).saturating_mul(1024 * 1024);
+        Self::new(config.queue_capacity, max_bytes, config.overflow_policy)
+    }
+
+    /// 放入一筆訊息；Block 模式下佇列滿時會等待，佇列關閉後回傳 false
+    pub async fn push(&self, message: MqttMessage) -> bool {
+        let size = message_size(&message);
+        let mut message = Some(message);
+
+        loop {
+            {
+                let mut state = self.inner.state.lock().unwrap();
+                if state.closed {
+                    return false;
+                }
+
+                // 單筆超過位元組上限時仍允許放入空佇列，避免永遠卡住
+                let full = |state: &QueueState| {
+                    !state.items.is_empty()
+                        && (state.items.len() >= self.inner.capacity
+                            || state.bytes + size > self.inner.max_bytes)
+                };
+
+                if full(&state) {
+                    match self.inner.policy {
+                        OverflowPolicy::Block => {}
+                        OverflowPolicy::DropNewest => {
+                            self.record_drop();
+                            return true;
+                        }
+                        OverflowPolicy::DropOldest => {
+                            while full(&state) {
+                                if let Some(old) = state.items.pop_front() {
+                                    state.bytes -= message_size(&old);
+                                    self.record_drop();
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if !full(&state) {
+                    state.bytes += size;
+                    state.items.push_back(message.take().unwrap());
+                    drop(state);
+                    self.inner.not_empty.notify_one();
+                    return true;
+                }
+            }
+
+            self.inner.not_full.notified().await;
+        }
+    }
+
+    /// 取出一筆訊息，佇列為空時等待；佇列關閉且清空後回傳 None
+    pub async fn pop(&self) -> Option<MqttMessage> {
+        loop {
+            {
+                let mut state = self.inner.state.lock().unwrap();
+                if let Some(message) = state.items.pop_front() {
+                    state.bytes -= message_size(&message);
+                    drop(state);
+                    self.inner.not_full.notify_one();
+                    return Some(message);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+
+            self.inner.not_empty.notified().await;
+        }
+    }
+
+    pub fn close(&self) {
+        self.inner.state.lock().unwrap().closed = true;
+        self.inner.not_empty.notify_one();
+        self.inner.not_full.notify_one();
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        let state = self.inner.state.lock().unwrap();
+        QueueStats {
+            queued: state.items.len(),
+            queued_bytes: state.bytes,
+            dropped: self.inner.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record_drop(&self) {
+        let dropped = self.inner.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        // 避免大量丟棄時洗版，只在特定次數記錄
+        if dropped.is_power_of_two() {
+            warn!("Ingest queue full ({:?}), {} messages dropped so far", self.inner.policy, dropped);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(payload: &str) -> MqttMessage {
+        MqttMessage {
+            topic: "t".to_string(),
+            payload: payload.to_string(),
+            encoding: None,
+            raw_payload: None,
+            marker: None,
+            qos: 0,
+            retain: false,
+            backlog: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_keeps_newest() {
+        let queue = IngestQueue::new(2, 1024, OverflowPolicy::DropOldest);
+        for payload in ["a", "b", "c"] {
+            assert!(queue.push(message(payload)).await);
+        }
+
+        assert_eq!(queue.stats().dropped, 1);
+        assert_eq!(queue.pop().await.unwrap().payload, "b");
+        assert_eq!(queue.pop().await.unwrap().payload, "c");
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest_keeps_oldest() {
+        let queue = IngestQueue::new(2, 1024, OverflowPolicy::DropNewest);
+        for payload in ["a", "b", "c"] {
+            assert!(queue.push(message(payload)).await);
+        }
+
+        assert_eq!(queue.stats().queued, 2);
+        assert_eq!(queue.stats().dropped, 1);
+        assert_eq!(queue.pop().await.unwrap().payload, "a");
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_space() {
+        let queue = IngestQueue::new(1, 1024, OverflowPolicy::Block);
+        assert!(queue.push(message("a")).await);
+
+        let producer = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.push(message("b")).await })
+        };
+        tokio::task::yield_now().await;
+        assert_eq!(queue.stats().queued, 1);
+
+        assert_eq!(queue.pop().await.unwrap().payload, "a");
+        assert!(producer.await.unwrap());
+        assert_eq!(queue.pop().await.unwrap().payload, "b");
+        assert_eq!(queue.stats().dropped, 0);
+    }
+}
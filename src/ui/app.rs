@@ -24,7 +24,10 @@ pub enum AppEvent {
     Delete,
     Paste(String),
     Space,  // 空白鍵事件
-    QuickFilter(usize),  // F1-F5快速過濾器
+    QuickFilter(usize),  // 快速過濾器，快捷鍵由設定檔的 hotkey 決定
+    OpenFile,  // Ctrl+O 開啟資料庫檔案
+    Presets,  // Ctrl+P 選擇過濾 preset
+    SavePreset,  // Ctrl+S 將目前的過濾條件存成 preset
 }
 
 impl From<KeyEvent> for AppEvent {
@@ -35,11 +38,6 @@ impl From<KeyEvent> for AppEvent {
         }
         
         match key_event.code {
-            KeyCode::F(1) => AppEvent::QuickFilter(0),  // F1-F5快速過濾器
-            KeyCode::F(2) => AppEvent::QuickFilter(1),
-            KeyCode::F(3) => AppEvent::QuickFilter(2),
-            KeyCode::F(4) => AppEvent::QuickFilter(3),
-            KeyCode::F(5) => AppEvent::QuickFilter(4),
             KeyCode::F(6) => AppEvent::Refresh,
             KeyCode::Char('/') => AppEvent::Filter,
             KeyCode::F(7) => AppEvent::JsonToggle,
@@ -59,6 +57,9 @@ impl From<KeyEvent> for AppEvent {
                 // 這裡我們先返回一個空的Paste事件，實際的剪貼簿內容需要在app.rs中獲取
                 AppEvent::Paste(String::new())
             },
+            KeyCode::Char('o') if key_event.modifiers.contains(KeyModifiers::CONTROL) => AppEvent::OpenFile,
+            KeyCode::Char('p') if key_event.modifiers.contains(KeyModifiers::CONTROL) => AppEvent::Presets,
+            KeyCode::Char('s') if key_event.modifiers.contains(KeyModifiers::CONTROL) => AppEvent::SavePreset,
             KeyCode::Tab => AppEvent::Tab,
             KeyCode::Char(' ') => AppEvent::Space,  // 空白鍵特殊處理
             KeyCode::Char(c) => AppEvent::Input(c),
@@ -88,7 +89,7 @@ use crossterm::{
     event::{self, Event, KeyCode, KeyEvent, KeyboardEnhancementFlags, PushKeyboardEnhancementFlags, KeyEventKind, KeyModifiers},
     terminal::{disable_raw_mode, enable_raw_mode, size, Clear, ClearType},
     cursor::{Hide, Show, MoveTo},
-    style::{Print, SetForegroundColor, ResetColor},
+    style::Print,
     ExecutableCommand, QueueableCommand,
 };
 use tokio::sync::mpsc;
@@ -102,23 +103,46 @@ use winapi::um::winuser::{GetAsyncKeyState, VK_ESCAPE};
 use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(windows)]
 use std::sync::Arc;
+#[cfg(windows)]
+use crate::filter::Hotkey;
 
 use crate::config::Config;
-use crate::db::{MessageRepository, FilterCriteria};
-use crate::ui::widgets::{FilterState, FilterBar, StatusBarState, StatusBar, ViewType, ConnectionStatus};
-use crate::mqtt::MqttClient;
-use crate::ui::views::{TopicListState, TopicListView, MessageListState};
+use crate::filter::{resolve_time_input, FilterPreset, MuteRules, Query, QuickFilterSet, StaleRules, TimeExpr};
+use crate::db::{Alert, Message, MessageRepository, FilterCriteria, TopicStat, TrashEntry};
+use crate::ui::widgets::{FilterState, FilterBar, StatusBarState, StatusBar, ViewType, ConnectionStatus, QuickFilterStatus};
+use crate::mqtt::{ConnectionEvent, IngestQueue};
+use crate::ui::views::{TopicListState, TopicListView, MessageListState, FilePickerState, FileEntryKind, PresetPickerState, ColumnEditorState, ColumnInput, MessageDiffState, ChartState, first_numeric_path, TopicColumnEditorState, AlertListState, BookmarkListState, MuteListState, TrashListState};
+use crate::ui::columns::{ColumnSet, PayloadColumn};
 
+// 告警清單最多載入的筆數
+const ALERT_LIST_LIMIT: i64 = 1000;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AppState {
     TopicList,
     MessageList,
     PayloadDetail,
+    FilePicker,
+    PresetPicker,
+    ColumnEditor,
+    TopicColumnEditor,
+    MessageDiff,
+    Chart,
+    Alerts,
+    Bookmarks,
+    MuteList,
+    Trash,
     Help,
     Quit,
 }
 
+// 編輯中的書籤備註
+#[derive(Debug, Clone)]
+struct NoteInput {
+    message_id: i64,
+    text: String,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PayloadDetailSelection {
     Topic,
@@ -161,6 +185,51 @@ pub struct App {
     // MQTT connection info
     mqtt_host: String,
     mqtt_port: u16,
+    ingest_queue: Option<IngestQueue>,
+    live_repository: Option<MessageRepository>, // 開啟唯讀檔案時保留即時資料庫
+    live_connection_status: ConnectionStatus,
+    
+    // 開啟資料庫檔案的選擇畫面
+    file_picker_state: Option<FilePickerState>,
+    state_before_picker: AppState,
+    
+    // 過濾 preset 的選擇畫面
+    preset_picker_state: Option<PresetPickerState>,
+    
+    // 訊息清單的 JSON 欄位 (資料庫的在前，其後為設定檔) 與編輯畫面
+    column_sets: Vec<ColumnSet>,
+    column_editor_state: Option<ColumnEditorState>,
+    
+    // Topic 清單的欄位設定畫面，變更寫回設定檔的 [topic_list]
+    topic_column_editor_state: Option<TopicColumnEditorState>,
+    
+    // 兩筆訊息的差異畫面，離開時回到開啟前的畫面 (訊息列表或 payload 詳細)
+    message_diff_state: Option<MessageDiffState>,
+    state_before_diff: AppState,
+    
+    // 目前 topic 的 JSON 數值圖表
+    chart_state: Option<ChartState>,
+    
+    // 設定檔 [[stale_topics]] 的預期發佈間隔
+    stale_rules: StaleRules,
+    
+    // 寫入訊息時觸發的告警 (提示音與狀態列橫幅) 與告警清單畫面
+    alert_receiver: Option<mpsc::UnboundedReceiver<Alert>>,
+    alert_list_state: Option<AlertListState>,
+    unseen_alerts: usize,
+    
+    // 靜音規則 (與 MQTT 接收端共用)、是否顯示被隱藏的 topic 與規則清單畫面
+    mute_rules: MuteRules,
+    show_muted: bool,
+    mute_list_state: Option<MuteListState>,
+    
+    // 最近一次刪除 (可按 z 復原) 與垃圾桶畫面
+    last_delete: Option<TrashEntry>,
+    trash_list_state: Option<TrashListState>,
+    
+    // 書籤清單畫面與編輯中的備註 (訊息列表、payload 詳細與書籤清單共用)
+    bookmark_list_state: Option<BookmarkListState>,
+    note_input: Option<NoteInput>,
     
     // Previous state for diff detection
     prev_filter_state: Option<FilterState>,
@@ -171,6 +240,11 @@ pub struct App {
     last_refresh: Instant,
     refresh_interval: Duration,
     
+    // 設定檔變更偵測 (重新載入快速過濾器)
+    config_path: String,
+    config_modified: Option<std::time::SystemTime>,
+    last_config_check: Instant,
+    
     // Terminal
     terminal_width: u16,
     terminal_height: u16,
@@ -188,8 +262,8 @@ pub struct App {
 }
 
 impl App {
-    pub async fn new(config: Config) -> Result<Self> {
-        let repository = MessageRepository::new(&config.database.path).await?;
+    pub async fn new(config: Config, repository: MessageRepository) -> Result<Self> {
+        let live_repository = if repository.is_read_only() { None } else { Some(repository.clone()) };
         let (width, height) = size()?;
         
         let mut app = Self {
@@ -204,11 +278,37 @@ impl App {
             payload_detail_selection: PayloadDetailSelection::Payload, // 預設選擇payload
             mqtt_host: config.mqtt.host.clone(),
             mqtt_port: config.mqtt.port,
+            ingest_queue: None,
+            live_repository,
+            live_connection_status: ConnectionStatus::Disconnected,
+            file_picker_state: None,
+            state_before_picker: AppState::TopicList,
+            preset_picker_state: None,
+            column_sets: Vec::new(),
+            column_editor_state: None,
+            topic_column_editor_state: None,
+            message_diff_state: None,
+            state_before_diff: AppState::MessageList,
+            chart_state: None,
+            stale_rules: StaleRules::default(),
+            alert_receiver: None,
+            alert_list_state: None,
+            unseen_alerts: 0,
+            mute_rules: MuteRules::new(&config.mutes),
+            show_muted: false,
+            mute_list_state: None,
+            last_delete: None,
+            trash_list_state: None,
+            bookmark_list_state: None,
+            note_input: None,
             prev_filter_state: None,
             prev_status_bar_state: None,
             prev_topic_list_state: None,
             last_refresh: Instant::now(),
             refresh_interval: Duration::from_millis(config.ui.refresh_interval_ms),
+            config_path: Config::path(),
+            config_modified: std::fs::metadata(Config::path()).and_then(|m| m.modified()).ok(),
+            last_config_check: Instant::now(),
             terminal_width: width,
             terminal_height: height,
             needs_full_redraw: true,
@@ -234,13 +334,20 @@ impl App {
         StatusBar::set_help_text_for_view(&mut app.status_bar_state, &ViewType::TopicList);
         
         // 初始化快速過濾器狀態
-        if app.config.quick_filters.enabled {
-            app.status_bar_state.quick_filter_states = app.config.quick_filters.filters
-                .iter()
-                .take(5) // 只取前5個
-                .map(|f| (f.name.clone(), f.color.clone(), false))
-                .collect();
+        let (quick_filters, errors) = QuickFilterSet::from_config(&app.config.quick_filters);
+        for error in &errors {
+            warn!("{}", error);
+        }
+        app.message_list_state.set_quick_filters(quick_filters);
+        let (stale_rules, errors) = StaleRules::from_config(&app.config.stale_topics);
+        for error in &errors {
+            warn!("{}", error);
         }
+        app.stale_rules = stale_rules;
+        app.topic_list_state.set_layout(&app.config.topic_list);
+        app.sync_topic_count_columns();
+        app.sync_quick_filter_status();
+        app.column_sets = app.load_column_sets().await;
         
         Ok(app)
     }
@@ -283,7 +390,7 @@ impl App {
     
     pub async fn run_with_connection_status(
         &mut self, 
-        connection_status: std::sync::Arc<std::sync::Mutex<bool>>
+        connection_events: Option<mpsc::UnboundedReceiver<ConnectionEvent>>
     ) -> Result<()> {
         info!("Starting MQTT Log Viewer application with connection monitoring");
 
@@ -310,7 +417,7 @@ impl App {
             }
         }
 
-        let result = self.main_loop_with_status(connection_status).await;
+        let result = self.main_loop_with_status(connection_events).await;
         
         // Cleanup terminal
         std::io::stdout().execute(Show)?;
@@ -322,7 +429,7 @@ impl App {
     
     async fn main_loop_with_status(
         &mut self, 
-        connection_status: std::sync::Arc<std::sync::Mutex<bool>>
+        mut connection_events: Option<mpsc::UnboundedReceiver<ConnectionEvent>>
     ) -> Result<()> {
         let mut last_refresh = Instant::now();
         
@@ -334,10 +441,26 @@ impl App {
         let exit_flag = Arc::new(AtomicBool::new(false));
         
         loop {
-            // Check and update connection status
-            if let Ok(is_connected) = connection_status.lock() {
-                self.update_connection_status_from_mqtt(*is_connected);
+            // Drain pending connection state changes from the MQTT eventloop (none when browsing offline)
+            if let Some(receiver) = connection_events.as_mut() {
+                while let Ok(connection_event) = receiver.try_recv() {
+                    self.apply_connection_event(connection_event);
+                }
+            }
+            
+            // 新觸發的告警
+            let mut alerts = Vec::new();
+            if let Some(receiver) = self.alert_receiver.as_mut() {
+                while let Ok(alert) = receiver.try_recv() {
+                    alerts.push(alert);
+                }
             }
+            if !alerts.is_empty() {
+                self.show_alerts(alerts).await?;
+            }
+            
+            // 設定檔修改後重新載入快速過濾器
+            self.check_config_reload().await;
             
             // 每0.25秒刷新資料 (第一層和第二層)
             let now = Instant::now();
@@ -466,42 +589,29 @@ impl App {
                     self.render()?;
                 }
                 
-                // 檢測 F1-F5 快速過濾器（在MessageList和TopicList狀態下）
+                // 檢測設定為 F1-F12 的快速過濾器（在MessageList和TopicList狀態下）
                 if self.state == AppState::MessageList || self.state == AppState::TopicList {
-                    if self.is_key_just_pressed(0x70) { // VK_F1
-                        tracing::info!("F1 key detected via Windows API - Quick Filter 0");
-                        if self.handle_event(AppEvent::QuickFilter(0)).await? {
-                            break;
-                        }
-                        self.render()?;
-                    }
-                    if self.is_key_just_pressed(0x71) { // VK_F2
-                        tracing::info!("F2 key detected via Windows API - Quick Filter 1");
-                        if self.handle_event(AppEvent::QuickFilter(1)).await? {
-                            break;
-                        }
-                        self.render()?;
-                    }
-                    if self.is_key_just_pressed(0x72) { // VK_F3
-                        tracing::info!("F3 key detected via Windows API - Quick Filter 2");
-                        if self.handle_event(AppEvent::QuickFilter(2)).await? {
-                            break;
-                        }
-                        self.render()?;
-                    }
-                    if self.is_key_just_pressed(0x73) { // VK_F4
-                        tracing::info!("F4 key detected via Windows API - Quick Filter 3");
-                        if self.handle_event(AppEvent::QuickFilter(3)).await? {
-                            break;
+                    let function_hotkeys: Vec<(usize, u8)> = self.message_list_state.quick_filters.filters
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(index, filter)| match filter.hotkey {
+                            Some(Hotkey::F(n)) => Some((index, n)),
+                            _ => None,
+                        })
+                        .collect();
+                    let mut quit = false;
+                    for (index, n) in function_hotkeys {
+                        if self.is_key_just_pressed(0x70 + n as i32 - 1) { // VK_F1..VK_F12
+                            tracing::info!("F{} key detected via Windows API - Quick Filter {}", n, index);
+                            if self.handle_event(AppEvent::QuickFilter(index)).await? {
+                                quit = true;
+                                break;
+                            }
+                            self.render()?;
                         }
-                        self.render()?;
                     }
-                    if self.is_key_just_pressed(0x74) { // VK_F5
-                        tracing::info!("F5 key detected via Windows API - Quick Filter 4");
-                        if self.handle_event(AppEvent::QuickFilter(4)).await? {
-                            break;
-                        }
-                        self.render()?;
+                    if quit {
+                        break;
                     }
                 }
                 
@@ -516,7 +626,11 @@ impl App {
                 match event::read()? {
                     Event::Key(key_event) => {
                         tracing::debug!("Raw key event detected: {:?}", key_event);
-                        let app_event = AppEvent::from(key_event);
+                        // 設定檔指定的快速過濾器快捷鍵優先
+                        let app_event = match self.message_list_state.quick_filters.hotkey_index(&key_event) {
+                            Some(index) if key_event.kind == KeyEventKind::Press => AppEvent::QuickFilter(index),
+                            _ => AppEvent::from(key_event),
+                        };
                         tracing::debug!("Converted to AppEvent: {:?}", app_event);
 
                         #[cfg(not(windows))]
@@ -537,8 +651,10 @@ impl App {
                         {
                             // On Windows: only handle character input, Backspace, and Copy via crossterm
                             // (navigation keys are handled by WinAPI above)
-                            if matches!(app_event, AppEvent::Input(c) if c != '\0') || matches!(app_event, AppEvent::Backspace) || matches!(app_event, AppEvent::Copy) {
-                                tracing::debug!("Input/Backspace/Copy event detected: {:?}", app_event);
+                            if matches!(app_event, AppEvent::Input(c) if c != '\0') || matches!(app_event, AppEvent::Backspace) || matches!(app_event, AppEvent::Copy) || matches!(app_event, AppEvent::OpenFile)
+                                || matches!(app_event, AppEvent::Presets | AppEvent::SavePreset)
+                                || (matches!(app_event, AppEvent::QuickFilter(_)) && !matches!(key_event.code, KeyCode::F(_))) {
+                                tracing::debug!("Input/Backspace/Copy/OpenFile event detected: {:?}", app_event);
                                 if self.handle_event(app_event).await? {
                                     break;
                                 }
@@ -575,6 +691,9 @@ impl App {
         let exit_flag = Arc::new(AtomicBool::new(false));
         
         loop {
+            // 設定檔修改後重新載入快速過濾器
+            self.check_config_reload().await;
+            
             // 每0.25秒刷新資料 (第一層和第二層)
             let now = Instant::now();
             if now.duration_since(last_refresh) >= self.refresh_interval 
@@ -715,7 +834,11 @@ impl App {
                 match event::read()? {
                     Event::Key(key_event) => {
                         tracing::debug!("Raw key event detected: {:?}", key_event);
-                        let app_event = AppEvent::from(key_event);
+                        // 設定檔指定的快速過濾器快捷鍵優先
+                        let app_event = match self.message_list_state.quick_filters.hotkey_index(&key_event) {
+                            Some(index) if key_event.kind == KeyEventKind::Press => AppEvent::QuickFilter(index),
+                            _ => AppEvent::from(key_event),
+                        };
                         tracing::debug!("Converted to AppEvent: {:?}", app_event);
 
                         #[cfg(not(windows))]
@@ -736,8 +859,10 @@ impl App {
                         {
                             // On Windows: only handle character input, Backspace, and Copy via crossterm
                             // (navigation keys are handled by WinAPI above)
-                            if matches!(app_event, AppEvent::Input(c) if c != '\0') || matches!(app_event, AppEvent::Backspace) || matches!(app_event, AppEvent::Copy) {
-                                tracing::debug!("Input/Backspace/Copy event detected: {:?}", app_event);
+                            if matches!(app_event, AppEvent::Input(c) if c != '\0') || matches!(app_event, AppEvent::Backspace) || matches!(app_event, AppEvent::Copy) || matches!(app_event, AppEvent::OpenFile)
+                                || matches!(app_event, AppEvent::Presets | AppEvent::SavePreset)
+                                || (matches!(app_event, AppEvent::QuickFilter(_)) && !matches!(key_event.code, KeyCode::F(_))) {
+                                tracing::debug!("Input/Backspace/Copy/OpenFile event detected: {:?}", app_event);
                                 if self.handle_event(app_event).await? {
                                     break;
                                 }
@@ -770,8 +895,68 @@ impl App {
             self.message_list_state.delete_confirmation = false; // 清除刪除確認狀態
             self.topic_list_state.delete_confirmation = false; // 清除刪除確認狀態
         }
+        
+        // 檔案選擇畫面自行處理所有按鍵
+        if self.note_input.is_some() {
+            self.handle_note_input_event(event).await?;
+            return Ok(false);
+        }
+        if self.state == AppState::FilePicker {
+            self.handle_file_picker_event(event).await?;
+            return Ok(false);
+        }
+        if self.state == AppState::PresetPicker {
+            self.handle_preset_picker_event(event).await?;
+            return Ok(false);
+        }
+        if self.state == AppState::ColumnEditor {
+            self.handle_column_editor_event(event).await?;
+            return Ok(false);
+        }
+        if self.state == AppState::TopicColumnEditor {
+            self.handle_topic_column_editor_event(event);
+            return Ok(false);
+        }
+        if self.state == AppState::MessageDiff {
+            self.handle_message_diff_event(event);
+            return Ok(false);
+        }
+        if self.state == AppState::Chart {
+            self.handle_chart_event(event).await?;
+            return Ok(false);
+        }
+        if self.state == AppState::Alerts {
+            self.handle_alert_list_event(event).await?;
+            return Ok(false);
+        }
+        if self.state == AppState::Bookmarks {
+            self.handle_bookmark_list_event(event).await?;
+            return Ok(false);
+        }
+        if self.state == AppState::Trash {
+            self.handle_trash_list_event(event).await?;
+            return Ok(false);
+        }
+        if self.state == AppState::MuteList {
+            self.handle_mute_list_event(event).await?;
+            return Ok(false);
+        }
+        
         match event {
             AppEvent::Quit => return Ok(true),
+            
+            AppEvent::OpenFile => {
+                if !self.filter_state.is_editing && !self.message_list_state.is_editing {
+                    self.open_file_picker();
+                }
+            }
+            
+            AppEvent::Presets | AppEvent::SavePreset => {
+                if matches!(self.state, AppState::TopicList | AppState::MessageList)
+                    && !self.filter_state.is_editing && !self.message_list_state.is_editing {
+                    self.open_preset_picker(matches!(event, AppEvent::SavePreset)).await;
+                }
+            }
 
             AppEvent::Copy => {
                 // Ctrl+C/Alt+C 的行為根據當前狀態決定：
@@ -779,7 +964,7 @@ impl App {
                 // - MessageList: 複製當前選中訊息的 payload
                 // - PayloadDetail: 由 handle_payload_detail_event 處理（複製選中的內容）
                 match self.state {
-                    AppState::TopicList | AppState::Help | AppState::Quit => {
+                    AppState::TopicList | AppState::FilePicker | AppState::PresetPicker | AppState::ColumnEditor | AppState::TopicColumnEditor | AppState::MessageDiff | AppState::Chart | AppState::Alerts | AppState::Bookmarks | AppState::MuteList | AppState::Trash | AppState::Help | AppState::Quit => {
                         // 在 TopicList/Help/Quit 按 Ctrl+C 不做任何事
                         tracing::info!("Ctrl+C pressed in {:?} - ignoring", self.state);
                     }
@@ -863,18 +1048,16 @@ impl App {
                     }
                     
                     // 更新狀態欄的快速過濾器狀態
-                    if self.config.quick_filters.enabled && index < self.status_bar_state.quick_filter_states.len() {
-                        self.status_bar_state.quick_filter_states[index].2 = self.message_list_state.get_quick_filter_state(index);
-                    }
+                    self.sync_quick_filter_status();
                 } else if self.state == AppState::TopicList {
                     // 在TopicList狀態下也允許切換快速過濾器
                     self.message_list_state.toggle_quick_filter(index);
                     tracing::info!("Toggled quick filter {} in TopicList - new state: {}", 
                                  index, self.message_list_state.get_quick_filter_state(index));
+                    // 重新統計 topic，套用新的過濾器狀態
+                    self.refresh_data().await?;
                     // 更新狀態欄的快速過濾器狀態
-                    if self.config.quick_filters.enabled && index < self.status_bar_state.quick_filter_states.len() {
-                        self.status_bar_state.quick_filter_states[index].2 = self.message_list_state.get_quick_filter_state(index);
-                    }
+                    self.sync_quick_filter_status();
                 }
             }
             
@@ -971,6 +1154,58 @@ impl App {
                 tracing::debug!("End key pressed in topic list - moving to bottom");
                 self.topic_list_state.move_to_bottom();
             },
+            AppEvent::Input('s') => {
+                self.topic_list_state.cycle_sort_key();
+                tracing::info!("Topic list sorted by {:?}", self.topic_list_state.sort_key);
+                self.save_topic_list_layout();
+            },
+            AppEvent::Input('o') => {
+                self.topic_list_state.toggle_sort_direction();
+                self.save_topic_list_layout();
+            },
+            AppEvent::Input('!') => {
+                self.topic_list_state.toggle_stale_only();
+                tracing::info!("Topic list stale only: {}", self.topic_list_state.stale_only);
+                self.refresh_data().await?;
+            },
+            AppEvent::Input('p') => {
+                self.topic_list_state.toggle_pin();
+                self.save_topic_list_layout();
+            },
+            AppEvent::Input('f') => {
+                self.topic_list_state.toggle_pinned_only();
+                tracing::info!("Topic list pinned only: {}", self.topic_list_state.pinned_only);
+                self.refresh_data().await?;
+            },
+            AppEvent::Input('u') => {
+                if let Some(topic) = self.topic_list_state.get_selected_topic().map(|topic| topic.topic.clone()) {
+                    let mode = self.mute_rules.cycle(&topic);
+                    tracing::info!("Mute rule for {}: {:?}", topic, mode);
+                    self.save_mute_rules();
+                    self.refresh_data().await?;
+                }
+            },
+            AppEvent::Input('v') => {
+                self.show_muted = !self.show_muted;
+                self.refresh_data().await?;
+            },
+            AppEvent::Input('M') => {
+                self.mute_list_state = Some(MuteListState::new(self.mute_rules.rules()));
+                self.state = AppState::MuteList;
+                self.needs_full_redraw = true;
+            },
+            AppEvent::Input('a') => self.open_alert_list().await,
+            AppEvent::Input('b') => self.open_bookmark_list().await,
+            AppEvent::Input('t') => self.open_trash_list().await,
+            AppEvent::Input('z') => self.undo_last_delete().await?,
+            AppEvent::Input('c') => {
+                self.topic_column_editor_state = Some(TopicColumnEditorState::new(&self.topic_list_state.columns));
+                self.state = AppState::TopicColumnEditor;
+                self.needs_full_redraw = true;
+            },
+            AppEvent::Delete if self.repository.is_read_only() => {
+                tracing::info!("Delete ignored - database is opened read-only");
+            }
             AppEvent::Delete => {
                 // 刪除選中的topic的所有記錄
                 if let Some(selected_topic) = self.topic_list_state.get_selected_topic() {
@@ -983,8 +1218,9 @@ impl App {
                         tracing::info!("Second Delete press detected - executing deletion for topic: {}", topic);
                         // 執行刪除
                         match self.repository.delete_messages_by_topic(&topic).await {
-                            Ok(deleted_count) => {
-                                tracing::info!("Successfully deleted {} messages for topic: {}", deleted_count, topic);
+                            Ok(deleted) => {
+                                tracing::info!("Successfully moved {} messages for topic {} to trash", deleted.as_ref().map_or(0, |entry| entry.count), topic);
+                                self.set_last_delete(deleted);
                                 // 重新載入資料
                                 self.refresh_data().await?;
                                 self.topic_list_state.delete_confirmation = false;
@@ -1126,6 +1362,28 @@ impl App {
                     self.message_list_state.load_messages(&self.repository).await?;
                 }
             }
+            AppEvent::Input('c') => {
+                if matches!(self.message_list_state.get_focus(), crate::ui::views::message_list::FocusTarget::MessageList) {
+                    self.open_column_editor();
+                }
+            }
+            AppEvent::Input('g') => {
+                if matches!(self.message_list_state.get_focus(), crate::ui::views::message_list::FocusTarget::MessageList) {
+                    self.open_chart().await?;
+                }
+            }
+            AppEvent::Input('m') => self.message_list_state.toggle_mark(),
+            AppEvent::Input('d') => {
+                if let Some(message) = self.message_list_state.get_selected_message().cloned() {
+                    self.open_message_diff(message).await?;
+                }
+            }
+            AppEvent::Input('b') => self.toggle_bookmark().await,
+            AppEvent::Input('n') => self.start_note_input(),
+            AppEvent::Input('z') => self.undo_last_delete().await?,
+            AppEvent::Delete if self.repository.is_read_only() => {
+                tracing::info!("Delete ignored - database is opened read-only");
+            }
             AppEvent::Delete => {
                 // 刪除選中的單筆訊息
                 if let Some(selected_msg) = self.message_list_state.get_selected_message() {
@@ -1149,9 +1407,10 @@ impl App {
                         };
                         
                         match delete_result {
-                            Ok(success) => {
-                                if success {
+                            Ok(deleted) => {
+                                if deleted.is_some() {
                                     tracing::info!("Successfully deleted message: topic={}, timestamp={}", topic, timestamp);
+                                    self.set_last_delete(deleted);
                                     // 重新載入當前頁面
                                     self.message_list_state.load_messages(&self.repository).await?;
                                 } else {
@@ -1426,7 +1685,23 @@ impl App {
         self.message_list_state.filter_error = None;
         
         // 更新message list的filter criteria
-        if !self.message_list_state.payload_filter_input.is_empty() {
+        let payload_input = &self.message_list_state.payload_filter_input;
+        self.message_list_state.filter.query = None;
+        if Query::looks_like_query(payload_input) {
+            // 查詢語法
+            match Query::parse(payload_input) {
+                Ok(query) => {
+                    self.message_list_state.filter.payload_regex = None;
+                    self.message_list_state.filter.query = Some(query);
+                }
+                Err(e) => {
+                    self.message_list_state.filter_error = Some(format!("Query error: {}", e));
+                    self.message_list_state.filter.payload_regex = None;
+                    tracing::warn!("Invalid filter query: {}", e);
+                    return Ok(());
+                }
+            }
+        } else if !self.message_list_state.payload_filter_input.is_empty() {
             // 驗證regex語法
             if let Err(e) = regex::Regex::new(&self.message_list_state.payload_filter_input) {
                 self.message_list_state.filter_error = Some(format!("Regex error: {}", e));
@@ -1443,25 +1718,15 @@ impl App {
             tracing::info!("Clearing payload_regex filter");
         }
         
-        // 處理時間過濾器
-        if !self.message_list_state.time_from_input.is_empty() {
-            if let Ok(parsed_time) = chrono::NaiveDateTime::parse_from_str(&self.message_list_state.time_from_input, "%Y-%m-%d %H:%M:%S") {
-                self.message_list_state.filter.start_time = Some(chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(parsed_time, chrono::Utc));
-            } else {
-                self.message_list_state.filter.start_time = None;
-            }
-        } else {
-            self.message_list_state.filter.start_time = None;
-        }
-        
-        if !self.message_list_state.time_to_input.is_empty() {
-            if let Ok(parsed_time) = chrono::NaiveDateTime::parse_from_str(&self.message_list_state.time_to_input, "%Y-%m-%d %H:%M:%S") {
-                self.message_list_state.filter.end_time = Some(chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(parsed_time, chrono::Utc));
-            } else {
-                self.message_list_state.filter.end_time = None;
+        // 驗證時間過濾器；實際時間在每次載入時由 update_filter_from_inputs 依當下時間計算
+        for input in [&self.message_list_state.time_from_input, &self.message_list_state.time_to_input] {
+            if !input.trim().is_empty() {
+                if let Err(e) = TimeExpr::parse(input) {
+                    self.message_list_state.filter_error = Some(format!("Time error: {}", e));
+                    tracing::warn!("Invalid time filter: {}", e);
+                    return Ok(());
+                }
             }
-        } else {
-            self.message_list_state.filter.end_time = None;
         }
         
         tracing::info!("Final filter state: {:?}", self.message_list_state.filter);
@@ -1477,6 +1742,14 @@ impl App {
 
     async fn handle_payload_detail_event(&mut self, event: AppEvent) -> Result<()> {
         match event {
+            AppEvent::Input('m') => self.message_list_state.toggle_mark(),
+            AppEvent::Input('d') => {
+                if let Some(message) = self.get_selected_message().cloned() {
+                    self.open_message_diff(message).await?;
+                }
+            }
+            AppEvent::Input('b') => self.toggle_bookmark().await,
+            AppEvent::Input('n') => self.start_note_input(),
             AppEvent::NavigateLeft => {
                 tracing::debug!("Navigate left from payload detail - returning to message list");
                 self.navigate_back()?;
@@ -1805,15 +2078,7 @@ impl App {
                     );
                     
                     // 同步快速過濾器狀態到狀態欄
-                    if self.config.quick_filters.enabled {
-                        for index in 0..self.status_bar_state.quick_filter_states.len().min(5) {
-                            if index < self.config.quick_filters.filters.len() {
-                                self.status_bar_state.quick_filter_states[index].0 = self.config.quick_filters.filters[index].name.clone();
-                                self.status_bar_state.quick_filter_states[index].1 = self.config.quick_filters.filters[index].color.clone();
-                                self.status_bar_state.quick_filter_states[index].2 = self.message_list_state.get_quick_filter_state(index);
-                            }
-                        }
-                    }
+                    self.sync_quick_filter_status();
                     
                     // 立即渲染第二層UI
                     info!("About to call render() for MessageList state");
@@ -1863,13 +2128,33 @@ impl App {
     }
 
     async fn refresh_data(&mut self) -> Result<()> {
+        if let Some(queue) = &self.ingest_queue {
+            self.status_bar_state.queue_stats = queue.stats();
+        }
+        
         match self.state {
             AppState::TopicList => {
                 let criteria = self.build_filter_criteria();
-                match self.repository.get_topic_stats(&criteria).await {
-                    Ok(topics) => {
+                let topic_stats = self.repository.get_topic_stats(
+                    &criteria,
+                    &self.message_list_state.quick_filters,
+                    &self.message_list_state.quick_filter_states,
+                ).await;
+                match topic_stats {
+                    Ok(mut topics) => {
+                        self.mark_stale_topics(&mut topics, &criteria).await;
+                        for topic in topics.iter_mut() {
+                            topic.muted = self.mute_rules.mode_for(&topic.topic).is_some();
+                        }
+                        self.status_bar_state.muted_topics = topics.iter().filter(|topic| topic.muted).count();
+                        self.status_bar_state.show_muted = self.show_muted;
+                        if !self.show_muted {
+                            topics.retain(|topic| !topic.muted);
+                        }
+                        self.status_bar_state.stale_topics = topics.iter().filter(|topic| topic.silent_for.is_some()).count();
                         self.topic_list_state.update_topics(topics);
                         self.status_bar_state.total_topics = self.topic_list_state.topics.len();
+                        self.sync_quick_filter_status();
                         // TODO: Update total messages count
                         self.status_bar_state.last_update = Some(chrono::Utc::now());
                     }
@@ -1894,37 +2179,64 @@ impl App {
         Ok(())
     }
     
-    fn build_filter_criteria(&self) -> FilterCriteria {
+    /// 依 [[stale_topics]] 標記沉寂的 topic；唯讀開啟的錄製檔以其中最新的訊息時間為現在
+    async fn mark_stale_topics(&self, topics: &mut Vec<TopicStat>, criteria: &FilterCriteria) {
+        if self.stale_rules.is_empty() {
+            return;
+        }
+        let last_seen = match self.repository.get_last_seen_times().await {
+            Ok(last_seen) => last_seen,
+            Err(e) => {
+                error!("Failed to load last seen times: {}", e);
+                return;
+            }
+        };
+        let now = if self.repository.is_read_only() {
+            last_seen.values().copied().max().unwrap_or_else(chrono::Utc::now)
+        } else {
+            chrono::Utc::now()
+        };
+        // 不在清單中的沉寂 topic 仍需符合 topic 過濾
+        let topic_regex = criteria.topic_regex.as_deref().and_then(|pattern| regex::Regex::new(pattern).ok());
+        self.stale_rules.mark_topics(topics, &last_seen, now, |topic| {
+            topic_regex.as_ref().is_none_or(|regex| regex.is_match(topic))
+        });
+    }
+    
+    fn build_filter_criteria(&mut self) -> FilterCriteria {
         let mut criteria = FilterCriteria::default();
+        self.filter_state.filter_error = None;
         
         if !self.filter_state.topic_filter.is_empty() {
             criteria.topic_regex = Some(self.filter_state.topic_filter.clone());
         }
         
-        if !self.filter_state.payload_filter.is_empty() {
+        if Query::looks_like_query(&self.filter_state.payload_filter) {
+            match Query::parse(&self.filter_state.payload_filter) {
+                Ok(query) => criteria.query = Some(query),
+                Err(e) => {
+                    tracing::warn!("Ignoring filter query: {}", e);
+                    self.filter_state.filter_error = Some(format!("Query error: {}", e));
+                }
+            }
+        } else if !self.filter_state.payload_filter.is_empty() {
             criteria.payload_regex = Some(self.filter_state.payload_filter.clone());
         }
         
-        // 解析時間過濾器
-        if !self.filter_state.start_time.is_empty() {
-            // 嘗試解析開始時間
-            if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(&self.filter_state.start_time, "%Y-%m-%d %H:%M:%S") {
-                use chrono::{TimeZone, Local};
-                if let Some(dt) = Local.from_local_datetime(&naive_dt).single() {
-                    criteria.start_time = Some(dt.with_timezone(&chrono::Utc));
-                    tracing::debug!("Applied start time filter: {}", dt);
-                }
+        // 解析時間過濾器；相對時間 (-15m、today...) 每次刷新都依當下時間重新計算
+        let now = chrono::Local::now();
+        match resolve_time_input(&self.filter_state.start_time, now) {
+            Ok(start_time) => criteria.start_time = start_time,
+            Err(e) => {
+                tracing::warn!("Ignoring start time filter: {}", e);
+                self.filter_state.filter_error.get_or_insert(format!("Time error: {}", e));
             }
         }
-        
-        if !self.filter_state.end_time.is_empty() {
-            // 嘗試解析結束時間
-            if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(&self.filter_state.end_time, "%Y-%m-%d %H:%M:%S") {
-                use chrono::{TimeZone, Local};
-                if let Some(dt) = Local.from_local_datetime(&naive_dt).single() {
-                    criteria.end_time = Some(dt.with_timezone(&chrono::Utc));
-                    tracing::debug!("Applied end time filter: {}", dt);
-                }
+        match resolve_time_input(&self.filter_state.end_time, now) {
+            Ok(end_time) => criteria.end_time = end_time,
+            Err(e) => {
+                tracing::warn!("Ignoring end time filter: {}", e);
+                self.filter_state.filter_error.get_or_insert(format!("Time error: {}", e));
             }
         }
         
@@ -1945,150 +2257,1444 @@ impl App {
     }
     
     pub fn set_connection_status(&mut self, status: ConnectionStatus) {
-        self.status_bar_state.connection_status = status.clone();
-        
-        // Also update connection status in the UI based on current state
-        match status {
-            ConnectionStatus::Connected(_) => {
-                self.status_bar_state.connection_status = ConnectionStatus::Connected(
-                    format!("{}:{}", self.mqtt_host, self.mqtt_port)
-                );
-            }
-            _ => {
-                self.status_bar_state.connection_status = status;
-            }
-        }
+        self.status_bar_state.connection_status = status;
     }
     
-    pub fn update_connection_status_from_mqtt(&mut self, is_connected: bool) {
-        if is_connected {
-            self.set_connection_status(ConnectionStatus::Connected(
-                format!("{}:{}", self.mqtt_host, self.mqtt_port)
-            ));
-        } else {
-            self.set_connection_status(ConnectionStatus::Disconnected);
-        }
+    fn open_file_picker(&mut self) {
+        // 從目前開啟的資料庫所在目錄開始
+        let current_path = match &self.status_bar_state.connection_status {
+            ConnectionStatus::Archive(path) => std::path::PathBuf::from(path),
+            _ => std::path::PathBuf::from(&self.config.database.path),
+        };
+        let dir = current_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+        
+        let show_live_entry = self.live_repository.is_some() && self.repository.is_read_only();
+        self.file_picker_state = Some(FilePickerState::new(&dir, show_live_entry));
+        self.state_before_picker = self.state;
+        self.state = AppState::FilePicker;
+        self.needs_full_redraw = true;
     }
     
-    // State comparison methods for incremental rendering
-    fn states_equal_filter(&self, prev: &FilterState, current: &FilterState) -> bool {
-        prev.topic_filter == current.topic_filter &&
-        prev.payload_filter == current.payload_filter &&
-        prev.start_time == current.start_time &&
-        prev.end_time == current.end_time &&
-        prev.active_field == current.active_field &&
-        prev.is_editing == current.is_editing
+    fn close_file_picker(&mut self) {
+        self.file_picker_state = None;
+        self.state = self.state_before_picker;
+        self.needs_full_redraw = true;
     }
     
-    fn states_equal_topics(&self, prev: &TopicListState, current: &TopicListState) -> bool {
-        if prev.topics.len() != current.topics.len() {
-            return false;
-        }
+    async fn handle_file_picker_event(&mut self, event: AppEvent) -> Result<()> {
+        let page = crate::ui::widgets::ListPanel::visible_rows(self.terminal_height);
+        let picker = match self.file_picker_state.as_mut() {
+            Some(picker) => picker,
+            None => return Ok(()),
+        };
         
-        if prev.selected_index != current.selected_index {
-            return false;
+        match event {
+            AppEvent::NavigateUp => picker.move_up(),
+            AppEvent::NavigateDown => picker.move_down(),
+            AppEvent::PageUp => picker.page_up(page),
+            AppEvent::PageDown => picker.page_down(page),
+            AppEvent::Home => picker.selected_index = 0,
+            AppEvent::End => picker.selected_index = picker.entries.len().saturating_sub(1),
+            AppEvent::Escape | AppEvent::NavigateLeft | AppEvent::OpenFile => self.close_file_picker(),
+            AppEvent::Enter | AppEvent::NavigateRight => {
+                let entry = match picker.selected() {
+                    Some(entry) => entry.clone(),
+                    None => return Ok(()),
+                };
+                
+                match entry.kind {
+                    FileEntryKind::Parent | FileEntryKind::Directory => picker.enter_directory(entry.path),
+                    FileEntryKind::Live => {
+                        self.file_picker_state = None;
+                        self.return_to_live().await?;
+                    }
+                    FileEntryKind::Database => {
+                        let path = entry.path.to_string_lossy().to_string();
+                        if let Err(e) = self.open_archive(&path).await {
+                            error!("Failed to open database {}: {}", path, e);
+                            if let Some(picker) = self.file_picker_state.as_mut() {
+                                picker.error = Some(format!("無法開啟 {}: {}", path, e));
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
         }
         
-        if prev.scroll_offset != current.scroll_offset {
-            return false;
-        }
+        Ok(())
+    }
+    
+    /// 以唯讀方式開啟資料庫檔案瀏覽歷史資料
+    pub async fn open_archive(&mut self, path: &str) -> Result<()> {
+        let repository = MessageRepository::open_read_only(path, self.config.database.busy_timeout()).await?;
+        self.repository = repository;
+        self.show_archive(path).await
+    }
+    
+    /// 目前的 repository 為唯讀檔案時，切換成離線瀏覽的畫面
+    pub async fn show_archive(&mut self, path: &str) -> Result<()> {
+        info!("Browsing archived database: {}", path);
         
-        // Compare topic contents (simplified comparison)
-        for (prev_topic, current_topic) in prev.topics.iter().zip(current.topics.iter()) {
-            if prev_topic.topic != current_topic.topic ||
-               prev_topic.message_count != current_topic.message_count ||
-               prev_topic.last_message_time != current_topic.last_message_time ||
-               prev_topic.latest_payload != current_topic.latest_payload {
-                return false;
-            }
+        // 時間範圍預設涵蓋整個檔案，否則舊資料會被預設的「昨天到明天」濾掉
+        if let Some((first, last)) = self.repository.get_time_range().await? {
+            let from = first.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string();
+            let to = (last + chrono::Duration::seconds(1)).with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string();
+            self.set_time_range(from, to);
         }
         
-        true
+        self.set_connection_status(ConnectionStatus::Archive(path.to_string()));
+        self.reset_views().await
     }
     
-    fn states_equal_status(&self, prev: &StatusBarState, current: &StatusBarState) -> bool {
-        // Compare connection status
-        let connection_equal = match (&prev.connection_status, &current.connection_status) {
-            (ConnectionStatus::Disconnected, ConnectionStatus::Disconnected) => true,
-            (ConnectionStatus::Connecting, ConnectionStatus::Connecting) => true,
-            (ConnectionStatus::Connected(a), ConnectionStatus::Connected(b)) => a == b,
-            _ => false,
+    async fn return_to_live(&mut self) -> Result<()> {
+        let repository = match &self.live_repository {
+            Some(repository) => repository.clone(),
+            None => return Ok(()),
         };
         
-        connection_equal &&
-        prev.total_topics == current.total_topics &&
-        prev.total_messages == current.total_messages &&
-        prev.last_update == current.last_update &&
-        prev.help_text == current.help_text &&
-        prev.quick_filter_states == current.quick_filter_states
+        info!("Returning to live database");
+        self.repository = repository;
+        let defaults = FilterState::default();
+        self.set_time_range(defaults.start_time, defaults.end_time);
+        self.set_connection_status(self.live_connection_status.clone());
+        self.reset_views().await
     }
     
-    #[cfg(windows)]
-    fn is_key_just_pressed(&mut self, vk_code: i32) -> bool {
-        let is_pressed = unsafe { GetAsyncKeyState(vk_code) & (0x8000u16 as i16) != 0 };
-        let was_pressed = self.last_key_state.get(&vk_code).copied().unwrap_or(false);
-        
-        // 更新按鍵狀態
-        self.last_key_state.insert(vk_code, is_pressed);
+    fn set_time_range(&mut self, from: String, to: String) {
+        self.filter_state.start_time = from.clone();
+        self.filter_state.end_time = to.clone();
+        self.message_list_state.time_from_input = from;
+        self.message_list_state.time_to_input = to;
+    }
+    
+    // 換資料庫後回到第一層並重新載入
+    async fn reset_views(&mut self) -> Result<()> {
+        let visible_rows = self.topic_list_state.visible_rows;
+        self.topic_list_state = TopicListState::default();
+        self.topic_list_state.set_visible_rows(visible_rows);
+        self.message_list_state.messages.clear();
+        self.message_list_state.current_topic = None;
+        self.message_list_state.selected_index = 0;
+        self.message_list_state.page = 1;
         
-        // 只有從未按下到按下的狀態轉換才返回true（邊沿觸發）
-        is_pressed && !was_pressed
+        self.file_picker_state = None;
+        self.state = AppState::TopicList;
+        self.prev_topic_list_state = None;
+        self.prev_status_bar_state = None;
+        self.needs_full_redraw = true;
+        StatusBar::set_help_text_for_view(&mut self.status_bar_state, &ViewType::TopicList);
+        self.refresh_data().await
     }
     
-    #[cfg(windows)]
-    fn is_key_pressed(&self, vk_code: i32) -> bool {
-        unsafe { GetAsyncKeyState(vk_code) & (0x8000u16 as i16) != 0 }
+    pub fn get_file_picker_state(&self) -> Option<&FilePickerState> {
+        self.file_picker_state.as_ref()
     }
     
-    // Key repeat handling methods
-    async fn handle_key_repeat_start(&mut self, key: crossterm::event::KeyCode) -> Result<bool> {
-        let now = Instant::now();
-        
-        // If this is a new key press
-        if self.key_repeat_state.current_key != Some(key) {
-            self.key_repeat_state.current_key = Some(key);
-            self.key_repeat_state.key_pressed_at = Some(now);
-            self.key_repeat_state.last_repeat_at = None;
-            self.key_repeat_state.is_repeating = false;
-            
-            // Handle the initial key press immediately
-            let event = match key {
-                crossterm::event::KeyCode::Up => AppEvent::NavigateUp,
-                crossterm::event::KeyCode::Down => AppEvent::NavigateDown,
-                crossterm::event::KeyCode::PageUp => AppEvent::PageUp,
-                crossterm::event::KeyCode::PageDown => AppEvent::PageDown,
-                _ => return Ok(false),
-            };
-            
-            if self.handle_event(event).await? {
-                return Ok(true);
-            }
-            self.render()?;
+    pub fn get_preset_picker_state(&self) -> Option<&PresetPickerState> {
+        self.preset_picker_state.as_ref()
+    }
+    
+    // preset 與欄位設定一律存在即時資料庫，瀏覽唯讀檔案時也一樣
+    fn settings_repository(&self) -> &MessageRepository {
+        self.live_repository.as_ref().unwrap_or(&self.repository)
+    }
+    
+    /// 設定檔的 preset 在前，其後為資料庫中的 preset
+    async fn load_presets(&self) -> Vec<FilterPreset> {
+        let mut presets: Vec<FilterPreset> = self.config.presets
+            .iter()
+            .cloned()
+            .map(|mut preset| {
+                preset.from_config = true;
+                preset
+            })
+            .collect();
+        match self.settings_repository().list_presets().await {
+            Ok(mut stored) => presets.append(&mut stored),
+            Err(e) => warn!("Failed to load filter presets: {}", e),
         }
-        
-        Ok(false)
+        presets
     }
     
-    fn handle_key_release(&mut self, key: crossterm::event::KeyCode) {
-        if self.key_repeat_state.current_key == Some(key) {
-            self.key_repeat_state.current_key = None;
-            self.key_repeat_state.key_pressed_at = None;
-            self.key_repeat_state.last_repeat_at = None;
-            self.key_repeat_state.is_repeating = false;
+    async fn open_preset_picker(&mut self, save: bool) {
+        let mut picker = PresetPickerState::new(self.load_presets().await);
+        if save {
+            picker.selected_index = 0;
+            picker.start_name_input();
         }
+        self.preset_picker_state = Some(picker);
+        self.state_before_picker = self.state;
+        self.state = AppState::PresetPicker;
+        self.needs_full_redraw = true;
     }
     
-    async fn handle_key_repeat(&mut self) -> Result<()> {
-        let now = Instant::now();
+    fn close_preset_picker(&mut self) {
+        self.preset_picker_state = None;
+        self.state = self.state_before_picker;
+        self.needs_full_redraw = true;
+    }
+    
+    async fn handle_preset_picker_event(&mut self, event: AppEvent) -> Result<()> {
+        let page = crate::ui::widgets::ListPanel::visible_rows(self.terminal_height);
+        let picker = match self.preset_picker_state.as_mut() {
+            Some(picker) => picker,
+            None => return Ok(()),
+        };
         
-        if let (Some(key), Some(pressed_at)) = (self.key_repeat_state.current_key, self.key_repeat_state.key_pressed_at) {
-            // Check if we should start repeating (after 0.5 seconds)
-            if !self.key_repeat_state.is_repeating && now.duration_since(pressed_at) >= Duration::from_millis(500) {
-                self.key_repeat_state.is_repeating = true;
-                self.key_repeat_state.last_repeat_at = Some(now);
+        // 輸入 preset 名稱
+        if let Some(name) = picker.name_input.as_mut() {
+            match event {
+                AppEvent::Input(c) if c != '\0' => name.push(c),
+                AppEvent::Filter => name.push('/'),
+                AppEvent::Space => name.push(' '),
+                AppEvent::Backspace => {
+                    name.pop();
+                }
+                AppEvent::Escape => picker.name_input = None,
+                AppEvent::Enter => {
+                    let name = name.trim().to_string();
+                    if name.is_empty() {
+                        picker.message = Some("Preset name cannot be empty".to_string());
+                        picker.name_input = None;
+                    } else if self.config.presets.iter().any(|p| p.name == name) {
+                        picker.message = Some(format!("'{}' is defined in the config file", name));
+                        picker.name_input = None;
+                    } else {
+                        let preset = self.current_filters_as_preset(name);
+                        let result = self.settings_repository().save_preset(&preset).await;
+                        let presets = self.load_presets().await;
+                        if let Some(picker) = self.preset_picker_state.as_mut() {
+                            picker.name_input = None;
+                            match result {
+                                Ok(()) => {
+                                    picker.selected_index = presets.iter().position(|p| p.name == preset.name).map_or(0, |i| i + 1);
+                                    picker.message = Some(format!("Saved preset '{}'", preset.name));
+                                }
+                                Err(e) => picker.message = Some(format!("無法儲存 preset: {}", e)),
+                            }
+                            picker.presets = presets;
+                        }
+                    }
+                }
+                _ => {}
             }
-            
-            // If we're in repeat mode, check if it's time for the next repeat (every 0.2 seconds)
+            return Ok(());
+        }
+        
+        let is_delete = matches!(event, AppEvent::Delete);
+        if !is_delete {
+            picker.delete_confirmation = false;
+        }
+        
+        match event {
+            AppEvent::NavigateUp => picker.move_up(),
+            AppEvent::NavigateDown => picker.move_down(),
+            AppEvent::PageUp => picker.page_up(page),
+            AppEvent::PageDown => picker.page_down(page),
+            AppEvent::Home => picker.selected_index = 0,
+            AppEvent::End => picker.selected_index = picker.row_count() - 1,
+            AppEvent::Escape | AppEvent::NavigateLeft | AppEvent::Presets => self.close_preset_picker(),
+            AppEvent::SavePreset => picker.start_name_input(),
+            AppEvent::Enter | AppEvent::NavigateRight => match picker.selected().cloned() {
+                Some(preset) => {
+                    self.close_preset_picker();
+                    self.apply_preset(&preset).await?;
+                }
+                None => picker.start_name_input(),
+            },
+            AppEvent::Delete => {
+                let preset = match picker.selected() {
+                    Some(preset) => preset.clone(),
+                    None => return Ok(()),
+                };
+                if preset.from_config {
+                    picker.message = Some("Presets from the config file can only be removed there".to_string());
+                } else if !picker.delete_confirmation {
+                    picker.delete_confirmation = true;
+                } else {
+                    picker.delete_confirmation = false;
+                    let result = self.settings_repository().delete_preset(&preset.name).await;
+                    let presets = self.load_presets().await;
+                    if let Some(picker) = self.preset_picker_state.as_mut() {
+                        picker.message = Some(match result {
+                            Ok(_) => format!("Deleted preset '{}'", preset.name),
+                            Err(e) => format!("無法刪除 preset: {}", e),
+                        });
+                        picker.presets = presets;
+                        picker.selected_index = picker.selected_index.min(picker.row_count() - 1);
+                    }
+                }
+            }
+            _ => {}
+        }
+        
+        Ok(())
+    }
+    
+    /// 與標記的訊息比較；沒有標記 (或標記的就是這筆) 時與同 topic 的前一筆比較
+    async fn open_message_diff(&mut self, message: Message) -> Result<()> {
+        let other = match self.message_list_state.marked_message.clone() {
+            Some(marked) if !self.message_list_state.is_marked(&message) => Some(marked),
+            _ => self.repository.get_previous_message(&message).await?,
+        };
+        self.message_diff_state = Some(MessageDiffState::new(message, other));
+        self.state_before_diff = self.state;
+        self.state = AppState::MessageDiff;
+        self.needs_full_redraw = true;
+        Ok(())
+    }
+    
+    pub fn get_message_diff_state(&self) -> Option<&MessageDiffState> {
+        self.message_diff_state.as_ref()
+    }
+    
+    fn handle_message_diff_event(&mut self, event: AppEvent) {
+        let page = crate::ui::widgets::ListPanel::visible_rows(self.terminal_height);
+        let Some(diff) = self.message_diff_state.as_mut() else {
+            return;
+        };
+        match event {
+            AppEvent::NavigateUp => diff.move_up(),
+            AppEvent::NavigateDown => diff.move_down(),
+            AppEvent::PageUp => diff.page_up(page),
+            AppEvent::PageDown => diff.page_down(page),
+            AppEvent::Home => diff.selected_index = 0,
+            AppEvent::End => diff.selected_index = diff.rows.len().saturating_sub(1),
+            AppEvent::Input('s') => diff.swap(),
+            AppEvent::Escape | AppEvent::NavigateLeft | AppEvent::Input('d') => {
+                self.message_diff_state = None;
+                self.state = self.state_before_diff;
+                self.needs_full_redraw = true;
+            }
+            _ => {}
+        }
+    }
+    
+    /// 以目前的過濾結果開啟圖表；預設欄位為訊息欄位或選取訊息中第一個數值欄位
+    async fn open_chart(&mut self) -> Result<()> {
+        let Some(topic) = self.message_list_state.current_topic.clone() else {
+            return Ok(());
+        };
+        let selected = self.message_list_state.get_selected_message().cloned();
+        let json = selected.as_ref().and_then(|message| serde_json::from_str::<serde_json::Value>(&message.payload).ok());
+        let path = self.get_message_columns()
+            .iter()
+            .find(|column| column.value(json.as_ref()).parse::<f64>().is_ok())
+            .map(|column| column.path.clone())
+            .or_else(|| selected.as_ref().and_then(|message| first_numeric_path(&message.payload)));
+
+        let messages = self.message_list_state.fetch_filtered_messages(&self.repository).await?;
+        let mut chart = ChartState::new(topic, path.clone().unwrap_or_default(), &messages);
+        match (&path, &selected) {
+            (Some(_), Some(message)) => chart.select(message),
+            (None, _) => chart.path_input = Some("$.".to_string()),
+            _ => {}
+        }
+        self.chart_state = Some(chart);
+        self.state = AppState::Chart;
+        self.needs_full_redraw = true;
+        Ok(())
+    }
+    
+    pub fn get_chart_state(&self) -> Option<&ChartState> {
+        self.chart_state.as_ref()
+    }
+    
+    async fn reload_chart(&mut self) -> Result<()> {
+        let messages = self.message_list_state.fetch_filtered_messages(&self.repository).await?;
+        if let Some(chart) = self.chart_state.as_mut() {
+            chart.set_messages(&messages);
+            chart.message = Some(format!("{} points from {} messages", chart.points.len(), messages.len()));
+        }
+        Ok(())
+    }
+    
+    async fn handle_chart_event(&mut self, event: AppEvent) -> Result<()> {
+        let Some(chart) = self.chart_state.as_mut() else {
+            return Ok(());
+        };
+        
+        // 輸入 JSON path
+        if let Some(text) = chart.path_input.as_mut() {
+            match event {
+                AppEvent::Input(c) if c != '\0' => text.push(c),
+                AppEvent::Backspace => {
+                    text.pop();
+                }
+                AppEvent::Escape => chart.path_input = None,
+                AppEvent::Enter => match crate::utils::json_path::JsonPath::parse(text) {
+                    Ok(_) => {
+                        chart.path = text.trim().to_string();
+                        chart.path_input = None;
+                        self.reload_chart().await?;
+                    }
+                    Err(e) => chart.message = Some(format!("Invalid JSON path: {}", e)),
+                },
+                _ => {}
+            }
+            return Ok(());
+        }
+        
+        chart.message = None;
+        match event {
+            AppEvent::NavigateLeft => chart.move_cursor(-1),
+            AppEvent::NavigateRight => chart.move_cursor(1),
+            AppEvent::Home => chart.move_cursor_to(0),
+            AppEvent::End => chart.move_cursor_to(chart.points.len().saturating_sub(1)),
+            AppEvent::Input('+') | AppEvent::Input('=') => chart.zoom(0.5),
+            AppEvent::Input('-') => chart.zoom(2.0),
+            AppEvent::Input('[') | AppEvent::PageUp => chart.pan(-0.25),
+            AppEvent::Input(']') | AppEvent::PageDown => chart.pan(0.25),
+            AppEvent::Input('0') => chart.reset_zoom(),
+            AppEvent::Input('f') => chart.path_input = Some(chart.path.clone()),
+            AppEvent::Input('r') => self.reload_chart().await?,
+            AppEvent::Enter => {
+                // 跳到游標所在的訊息
+                if let Some(message) = chart.cursor_point().map(|point| point.message.clone()) {
+                    if self.message_list_state.select_message(&self.repository, &message).await? {
+                        self.chart_state = None;
+                        self.state = AppState::MessageList;
+                        self.needs_full_redraw = true;
+                    } else if let Some(chart) = self.chart_state.as_mut() {
+                        chart.message = Some("Message no longer matches the current filters".to_string());
+                    }
+                }
+            }
+            AppEvent::Escape | AppEvent::Input('g') => {
+                self.chart_state = None;
+                self.state = AppState::MessageList;
+                self.needs_full_redraw = true;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    
+    /// 將 Topic 清單的排序與欄位寫回設定檔，並記下修改時間以免觸發重新載入
+    fn save_topic_list_layout(&mut self) -> Option<String> {
+        let layout = self.topic_list_state.layout();
+        if layout == self.config.topic_list {
+            return None;
+        }
+        self.config.topic_list = layout;
+        match Config::save_topic_list(&self.config_path, &self.config.topic_list) {
+            Ok(()) => {
+                self.config_modified = std::fs::metadata(&self.config_path).and_then(|m| m.modified()).ok();
+                None
+            }
+            Err(e) => {
+                warn!("Failed to save topic list layout to {}: {}", self.config_path, e);
+                Some(format!("無法寫入設定檔: {}", e))
+            }
+        }
+    }
+    
+    pub fn get_topic_column_editor_state(&self) -> Option<&TopicColumnEditorState> {
+        self.topic_column_editor_state.as_ref()
+    }
+    
+    fn handle_topic_column_editor_event(&mut self, event: AppEvent) {
+        let Some(editor) = self.topic_column_editor_state.as_mut() else {
+            return;
+        };
+        editor.message = None;
+        let changed = match event {
+            AppEvent::NavigateUp => {
+                editor.move_up();
+                false
+            }
+            AppEvent::NavigateDown => {
+                editor.move_down();
+                false
+            }
+            AppEvent::Space | AppEvent::Enter => {
+                let toggled = editor.toggle_selected();
+                if !toggled {
+                    editor.message = Some("At least one column must stay visible".to_string());
+                }
+                toggled
+            }
+            AppEvent::Input('-') => editor.move_selected(-1),
+            AppEvent::Input('+') | AppEvent::Input('=') => editor.move_selected(1),
+            AppEvent::Input('<') | AppEvent::Input(',') => editor.resize_selected(Some(-1)),
+            AppEvent::Input('>') | AppEvent::Input('.') => editor.resize_selected(Some(1)),
+            AppEvent::Input('a') => editor.resize_selected(None),
+            AppEvent::Input('r') => {
+                editor.reset();
+                true
+            }
+            AppEvent::Escape | AppEvent::NavigateLeft | AppEvent::Input('c') => {
+                self.topic_column_editor_state = None;
+                self.state = AppState::TopicList;
+                self.needs_full_redraw = true;
+                return;
+            }
+            _ => false,
+        };
+        
+        if changed {
+            self.topic_list_state.columns = editor.visible_columns();
+            let error = self.save_topic_list_layout();
+            if let Some(editor) = self.topic_column_editor_state.as_mut() {
+                editor.message = error;
+            }
+        }
+    }
+    
+    /// 資料庫中的欄位設定在前 (程式內修改過的優先)，其後為設定檔
+    async fn load_column_sets(&self) -> Vec<ColumnSet> {
+        let mut sets = match self.settings_repository().list_column_sets().await {
+            Ok(sets) => sets,
+            Err(e) => {
+                warn!("Failed to load message columns: {}", e);
+                Vec::new()
+            }
+        };
+        sets.extend(self.config.message_columns.iter().cloned().map(|mut set| {
+            set.from_config = true;
+            set
+        }));
+        sets
+    }
+    
+    /// 目前訊息清單 topic 套用的欄位
+    pub fn get_message_columns(&self) -> &[PayloadColumn] {
+        self.message_list_state.current_topic
+            .as_deref()
+            .and_then(|topic| ColumnSet::for_topic(&self.column_sets, topic))
+            .map_or(&[], |set| set.columns.as_slice())
+    }
+    
+    pub fn get_column_editor_state(&self) -> Option<&ColumnEditorState> {
+        self.column_editor_state.as_ref()
+    }
+    
+    fn open_column_editor(&mut self) {
+        let Some(topic) = self.message_list_state.current_topic.clone() else {
+            return;
+        };
+        let current = ColumnSet::for_topic(&self.column_sets, &topic);
+        self.column_editor_state = Some(ColumnEditorState::new(current, &topic));
+        self.state = AppState::ColumnEditor;
+        self.needs_full_redraw = true;
+    }
+    
+    fn close_column_editor(&mut self) {
+        self.column_editor_state = None;
+        self.state = AppState::MessageList;
+        self.needs_full_redraw = true;
+    }
+    
+    /// 將編輯中的欄位存到資料庫 (pattern 改變時刪除舊的)，並重新載入
+    async fn save_column_editor(&mut self, message: String) {
+        let Some(editor) = self.column_editor_state.as_ref() else {
+            return;
+        };
+        let set = editor.set.clone();
+        let previous = editor.original_topic.clone().filter(|topic| *topic != set.topic);
+        let repository = self.settings_repository();
+        let mut result = repository.save_column_set(&set).await;
+        if let (Ok(()), Some(previous)) = (&result, previous) {
+            result = repository.delete_column_set(&previous).await.map(|_| ());
+        }
+        self.column_sets = self.load_column_sets().await;
+        
+        if let Some(editor) = self.column_editor_state.as_mut() {
+            match result {
+                Ok(()) => {
+                    editor.set.from_config = false;
+                    editor.original_topic = Some(set.topic);
+                    editor.message = Some(message);
+                }
+                Err(e) => editor.message = Some(format!("無法儲存欄位設定: {}", e)),
+            }
+        }
+    }
+    
+    async fn handle_column_editor_event(&mut self, event: AppEvent) -> Result<()> {
+        let editor = match self.column_editor_state.as_mut() {
+            Some(editor) => editor,
+            None => return Ok(()),
+        };
+        
+        // 輸入欄位或 topic pattern
+        if let Some(input) = editor.input.as_mut() {
+            let text = match input {
+                ColumnInput::Column(text) | ColumnInput::Pattern(text) => text,
+            };
+            match event {
+                AppEvent::Input(c) if c != '\0' => text.push(c),
+                AppEvent::Filter => text.push('/'),
+                AppEvent::Space => text.push(' '),
+                AppEvent::Backspace => {
+                    text.pop();
+                }
+                AppEvent::Escape => editor.input = None,
+                AppEvent::Enter => {
+                    let input = editor.input.take();
+                    match input {
+                        Some(ColumnInput::Column(text)) => match PayloadColumn::parse(&text) {
+                            Ok(column) => {
+                                let path = column.path.clone();
+                                editor.add_column(column);
+                                self.save_column_editor(format!("Added column {}", path)).await;
+                            }
+                            Err(e) => editor.message = Some(format!("Invalid column: {}", e)),
+                        },
+                        Some(ColumnInput::Pattern(text)) => {
+                            let pattern = text.trim().to_string();
+                            if pattern.is_empty() {
+                                editor.message = Some("Topic pattern cannot be empty".to_string());
+                            } else {
+                                editor.set.topic = pattern.clone();
+                                self.save_column_editor(format!("Columns now apply to {}", pattern)).await;
+                            }
+                        }
+                        None => {}
+                    }
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+        
+        editor.message = None;
+        match event {
+            AppEvent::NavigateUp => editor.move_up(),
+            AppEvent::NavigateDown => editor.move_down(),
+            AppEvent::Home => editor.selected_index = 0,
+            AppEvent::End => editor.selected_index = editor.row_count() - 1,
+            AppEvent::Escape | AppEvent::NavigateLeft | AppEvent::Input('c') => self.close_column_editor(),
+            AppEvent::Input('a') => editor.input = Some(ColumnInput::Column(String::new())),
+            AppEvent::Enter if editor.selected_column().is_none() => editor.input = Some(ColumnInput::Column(String::new())),
+            AppEvent::Input('p') => editor.input = Some(ColumnInput::Pattern(editor.set.topic.clone())),
+            AppEvent::Input('-') | AppEvent::Input('+') => {
+                let offset = if matches!(event, AppEvent::Input('-')) { -1 } else { 1 };
+                if editor.move_selected(offset) {
+                    self.save_column_editor("Moved column".to_string()).await;
+                }
+            }
+            AppEvent::Delete => {
+                if let Some(column) = editor.remove_selected() {
+                    self.save_column_editor(format!("Removed column {}", column.path)).await;
+                }
+            }
+            AppEvent::Input('r') => {
+                // 刪除資料庫中的設定，回到設定檔的欄位 (若有)
+                let topic = editor.original_topic.clone();
+                if let Some(topic) = topic {
+                    if let Err(e) = self.settings_repository().delete_column_set(&topic).await {
+                        warn!("Failed to delete message columns for {}: {}", topic, e);
+                    }
+                    self.column_sets = self.load_column_sets().await;
+                }
+                let current_topic = self.message_list_state.current_topic.clone().unwrap_or_default();
+                let current = ColumnSet::for_topic(&self.column_sets, &current_topic);
+                let mut editor = ColumnEditorState::new(current, &current_topic);
+                editor.message = Some("Columns reset".to_string());
+                self.column_editor_state = Some(editor);
+            }
+            _ => {}
+        }
+        
+        Ok(())
+    }
+    
+    /// 目前畫面上的過濾條件；訊息清單使用自己的 payload 與時間欄位
+    fn current_filters_as_preset(&self, name: String) -> FilterPreset {
+        let (payload_regex, start_time, end_time) = if self.state_before_picker == AppState::MessageList {
+            (
+                self.message_list_state.payload_filter_input.clone(),
+                self.message_list_state.time_from_input.clone(),
+                self.message_list_state.time_to_input.clone(),
+            )
+        } else {
+            (
+                self.filter_state.payload_filter.clone(),
+                self.filter_state.start_time.clone(),
+                self.filter_state.end_time.clone(),
+            )
+        };
+        
+        FilterPreset {
+            name,
+            topic_regex: self.filter_state.topic_filter.clone(),
+            payload_regex,
+            start_time,
+            end_time,
+            quick_filters: self.message_list_state.quick_filters
+                .filters
+                .iter()
+                .enumerate()
+                .map(|(index, filter)| (filter.name.clone(), self.message_list_state.get_quick_filter_state(index)))
+                .collect(),
+            from_config: false,
+        }
+    }
+    
+    /// 套用 preset 到兩層的過濾欄位並重新載入目前的畫面
+    async fn apply_preset(&mut self, preset: &FilterPreset) -> Result<()> {
+        info!("Applying filter preset: {}", preset.name);
+        self.filter_state.topic_filter = preset.topic_regex.clone();
+        self.filter_state.payload_filter = preset.payload_regex.clone();
+        self.message_list_state.payload_filter_input = preset.payload_regex.clone();
+        self.set_time_range(preset.start_time.clone(), preset.end_time.clone());
+        
+        for (index, filter) in self.message_list_state.quick_filters.filters.iter().enumerate() {
+            if let Some(&enabled) = preset.quick_filters.get(&filter.name) {
+                self.message_list_state.quick_filter_states[index] = enabled;
+            }
+        }
+        self.sync_quick_filter_status();
+        
+        if self.state == AppState::MessageList {
+            self.message_list_state.update_filter_from_inputs();
+            self.message_list_state.page = 1;
+            self.message_list_state.selected_index = 0;
+            self.message_list_state.load_messages(&self.repository).await?;
+        } else {
+            self.refresh_data().await?;
+        }
+        self.needs_full_redraw = true;
+        Ok(())
+    }
+    
+    /// 狀態列上各快速過濾器的快捷鍵、名稱與開關
+    pub fn quick_filter_statuses(&self) -> Vec<QuickFilterStatus> {
+        let quick_filters = &self.message_list_state.quick_filters;
+        quick_filters
+            .filters
+            .iter()
+            .enumerate()
+            .map(|(index, filter)| QuickFilterStatus {
+                label: quick_filters.hotkey_label(index),
+                name: filter.name.clone(),
+                color: filter.color,
+                enabled: self.message_list_state.get_quick_filter_state(index),
+                // Topic 清單顯示所有 topic 的加總
+                count: (self.state == AppState::TopicList).then(|| {
+                    self.topic_list_state
+                        .topics
+                        .iter()
+                        .map(|topic| topic.quick_filter_counts.get(index).copied().unwrap_or(0))
+                        .sum()
+                }),
+            })
+            .collect()
+    }
+    
+    fn sync_quick_filter_status(&mut self) {
+        self.status_bar_state.quick_filter_states = self.quick_filter_statuses();
+    }
+    
+    // 快速過濾器變更後更新 Topic 清單的計數欄
+    fn sync_topic_count_columns(&mut self) {
+        let count_columns = self.message_list_state.quick_filters
+            .filters
+            .iter()
+            .enumerate()
+            .filter(|(_, filter)| filter.show_count)
+            .map(|(index, filter)| (index, filter.name.clone()))
+            .collect();
+        self.topic_list_state.set_count_columns(count_columns);
+    }
+    
+    /// 每秒檢查一次設定檔的修改時間，變更後重新載入快速過濾器
+    async fn check_config_reload(&mut self) {
+        if self.last_config_check.elapsed() < Duration::from_secs(1) {
+            return;
+        }
+        self.last_config_check = Instant::now();
+        
+        let modified = match std::fs::metadata(&self.config_path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(_) => return,
+        };
+        if self.config_modified == Some(modified) {
+            return;
+        }
+        self.config_modified = Some(modified);
+        
+        let config = match Config::load_from(&self.config_path) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Failed to reload config {}: {}", self.config_path, e);
+                return;
+            }
+        };
+        
+        info!("Config {} changed, reloading quick filters, presets and columns", self.config_path);
+        let (quick_filters, errors) = QuickFilterSet::from_config(&config.quick_filters);
+        for error in &errors {
+            warn!("{}", error);
+        }
+        self.message_list_state.filter_error = errors.first().cloned();
+        self.config.quick_filters = config.quick_filters;
+        self.config.presets = config.presets;
+        self.config.message_columns = config.message_columns;
+        self.config.topic_list = config.topic_list;
+        self.topic_list_state.set_layout(&self.config.topic_list);
+        let (stale_rules, errors) = StaleRules::from_config(&config.stale_topics);
+        for error in &errors {
+            warn!("{}", error);
+        }
+        self.stale_rules = stale_rules;
+        self.config.stale_topics = config.stale_topics;
+        self.mute_rules.set(&config.mutes);
+        self.config.mutes = config.mutes;
+        self.column_sets = self.load_column_sets().await;
+        self.message_list_state.set_quick_filters(quick_filters);
+        self.sync_topic_count_columns();
+        self.sync_quick_filter_status();
+        
+        if self.state == AppState::MessageList {
+            if let Err(e) = self.message_list_state.reload_after_filter_change(&self.repository).await {
+                error!("Failed to reload messages after config change: {}", e);
+            }
+        } else if self.state == AppState::TopicList {
+            if let Err(e) = self.refresh_data().await {
+                error!("Failed to reload topics after config change: {}", e);
+            }
+        }
+        self.needs_full_redraw = true;
+    }
+    
+    /// 記住最近一次刪除，狀態列提示可以按 z 復原
+    fn set_last_delete(&mut self, deleted: Option<TrashEntry>) {
+        self.status_bar_state.undo_hint = deleted.as_ref().map(|entry| {
+            format!("Deleted {} from {}", entry.count, entry.topic)
+        });
+        self.last_delete = deleted;
+        self.needs_full_redraw = true;
+    }
+    
+    pub fn get_last_delete(&self) -> Option<&TrashEntry> {
+        self.last_delete.as_ref()
+    }
+    
+    /// 將最近一次刪除的訊息從垃圾桶還原
+    async fn undo_last_delete(&mut self) -> Result<()> {
+        let Some(entry) = self.last_delete.clone() else {
+            return Ok(());
+        };
+        match self.repository.restore_trash(&entry.batch).await {
+            Ok(restored) => {
+                info!("Undo delete: restored {} messages for topic {}", restored, entry.topic);
+                self.set_last_delete(None);
+                match self.state {
+                    AppState::MessageList => self.message_list_state.load_messages(&self.repository).await?,
+                    _ => self.refresh_data().await?,
+                }
+                self.needs_full_redraw = true;
+            }
+            Err(e) => error!("Failed to undo delete for topic {}: {}", entry.topic, e),
+        }
+        Ok(())
+    }
+    
+    async fn open_trash_list(&mut self) {
+        self.trash_list_state = Some(TrashListState::default());
+        self.reload_trash_list().await;
+        self.state = AppState::Trash;
+        self.needs_full_redraw = true;
+    }
+    
+    async fn reload_trash_list(&mut self) {
+        if self.trash_list_state.is_none() {
+            return;
+        }
+        let entries = self.repository.list_trash().await;
+        let Some(list) = self.trash_list_state.as_mut() else {
+            return;
+        };
+        match entries {
+            Ok(entries) => list.set_entries(entries),
+            Err(e) => {
+                // 唯讀開啟的舊資料庫檔案沒有 trash 資料表
+                error!("Failed to load trash: {}", e);
+                list.message = Some(format!("Failed to load trash: {}", e));
+            }
+        }
+    }
+    
+    pub fn get_trash_list_state(&self) -> Option<&TrashListState> {
+        self.trash_list_state.as_ref()
+    }
+    
+    async fn handle_trash_list_event(&mut self, event: AppEvent) -> Result<()> {
+        let page = crate::ui::widgets::ListPanel::visible_rows(self.terminal_height);
+        let read_only = self.repository.is_read_only();
+        let Some(list) = self.trash_list_state.as_mut() else {
+            return Ok(());
+        };
+        list.message = None;
+        let confirm_purge = std::mem::take(&mut list.purge_confirmation);
+        match event {
+            AppEvent::NavigateUp => list.move_up(),
+            AppEvent::NavigateDown => list.move_down(),
+            AppEvent::PageUp => list.page_up(page),
+            AppEvent::PageDown => list.page_down(page),
+            AppEvent::Home => list.selected_index = 0,
+            AppEvent::End => list.selected_index = list.entries.len().saturating_sub(1),
+            AppEvent::Enter | AppEvent::Input('r') | AppEvent::Delete if read_only => {
+                list.message = Some("Database is opened read-only".to_string());
+            }
+            AppEvent::Enter | AppEvent::Input('r') => {
+                let Some(entry) = list.selected().cloned() else {
+                    return Ok(());
+                };
+                let message = match self.repository.restore_trash(&entry.batch).await {
+                    Ok(restored) => format!("Restored {} messages to {}", restored, entry.topic),
+                    Err(e) => {
+                        error!("Failed to restore trash batch {}: {}", entry.batch, e);
+                        format!("Failed to restore: {}", e)
+                    }
+                };
+                self.forget_last_delete(&entry.batch);
+                self.reload_trash_list().await;
+                if let Some(list) = self.trash_list_state.as_mut() {
+                    list.message = Some(message);
+                }
+            }
+            AppEvent::Delete => {
+                let Some(entry) = list.selected().cloned() else {
+                    return Ok(());
+                };
+                if confirm_purge {
+                    if let Err(e) = self.repository.purge_trash(&entry.batch).await {
+                        error!("Failed to purge trash batch {}: {}", entry.batch, e);
+                    }
+                    self.forget_last_delete(&entry.batch);
+                    self.reload_trash_list().await;
+                } else {
+                    list.purge_confirmation = true;
+                    list.message = Some(format!(
+                        "Permanently delete {} messages of {}? Press Delete again to confirm",
+                        entry.count, entry.topic
+                    ));
+                }
+            }
+            AppEvent::Escape | AppEvent::NavigateLeft | AppEvent::Input('t') => {
+                self.trash_list_state = None;
+                self.state = AppState::TopicList;
+                self.needs_full_redraw = true;
+                self.refresh_data().await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    
+    /// 垃圾桶中已還原或永久刪除的批次不能再復原
+    fn forget_last_delete(&mut self, batch: &str) {
+        if self.last_delete.as_ref().is_some_and(|entry| entry.batch == batch) {
+            self.set_last_delete(None);
+        }
+    }
+    
+    /// 與 MQTT 接收端共用的靜音規則
+    pub fn set_mute_rules(&mut self, mute_rules: MuteRules) {
+        self.mute_rules = mute_rules;
+    }
+    
+    /// 靜音規則寫回設定檔的 [[mutes]]
+    fn save_mute_rules(&mut self) {
+        self.config.mutes = self.mute_rules.rules();
+        match Config::save_mutes(&self.config_path, &self.config.mutes) {
+            Ok(()) => {
+                self.config_modified = std::fs::metadata(&self.config_path).and_then(|m| m.modified()).ok();
+            }
+            Err(e) => warn!("Failed to save mute rules to {}: {}", self.config_path, e),
+        }
+    }
+    
+    pub fn get_mute_list_state(&self) -> Option<&MuteListState> {
+        self.mute_list_state.as_ref()
+    }
+    
+    async fn handle_mute_list_event(&mut self, event: AppEvent) -> Result<()> {
+        let Some(list) = self.mute_list_state.as_mut() else {
+            return Ok(());
+        };
+        match event {
+            AppEvent::NavigateUp => list.move_up(),
+            AppEvent::NavigateDown => list.move_down(),
+            AppEvent::Space | AppEvent::Enter => {
+                list.toggle_mode();
+                self.mute_rules.set(&list.rules);
+                self.save_mute_rules();
+            }
+            AppEvent::Delete => {
+                list.remove_selected();
+                self.mute_rules.set(&list.rules);
+                self.save_mute_rules();
+            }
+            AppEvent::Escape | AppEvent::NavigateLeft | AppEvent::Input('M') => {
+                self.mute_list_state = None;
+                self.state = AppState::TopicList;
+                self.needs_full_redraw = true;
+                self.refresh_data().await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    
+    pub fn set_alert_receiver(&mut self, receiver: mpsc::UnboundedReceiver<Alert>) {
+        self.alert_receiver = Some(receiver);
+    }
+    
+    /// 提示音並在狀態列顯示最新的告警，直到開啟告警清單
+    async fn show_alerts(&mut self, alerts: Vec<Alert>) -> Result<()> {
+        stdout().execute(Print("\x07"))?;
+        self.unseen_alerts += alerts.len();
+        if let Some(latest) = alerts.last() {
+            let mut banner = format!("{} on {}: {}", latest.rule, latest.topic, latest.description);
+            if self.unseen_alerts > 1 {
+                banner.push_str(&format!(" (+{} more, [a] to view)", self.unseen_alerts - 1));
+            }
+            self.status_bar_state.alert_banner = Some(banner);
+        }
+        if self.state == AppState::Alerts {
+            self.reload_alert_list().await;
+            self.needs_full_redraw = true;
+            self.render()?;
+        }
+        Ok(())
+    }
+    
+    async fn open_alert_list(&mut self) {
+        self.alert_list_state = Some(AlertListState::default());
+        self.reload_alert_list().await;
+        self.state = AppState::Alerts;
+        self.needs_full_redraw = true;
+    }
+    
+    async fn reload_alert_list(&mut self) {
+        let alerts = self.repository.list_alerts(ALERT_LIST_LIMIT).await;
+        let Some(list) = self.alert_list_state.as_mut() else {
+            return;
+        };
+        match alerts {
+            Ok(alerts) => list.set_alerts(alerts),
+            Err(e) => {
+                // 舊的資料庫檔案沒有 alerts 資料表
+                error!("Failed to load alerts: {}", e);
+                list.message = Some(format!("Failed to load alerts: {}", e));
+            }
+        }
+        self.unseen_alerts = 0;
+        self.status_bar_state.alert_banner = None;
+    }
+    
+    pub fn get_alert_list_state(&self) -> Option<&AlertListState> {
+        self.alert_list_state.as_ref()
+    }
+    
+    async fn handle_alert_list_event(&mut self, event: AppEvent) -> Result<()> {
+        let page = crate::ui::widgets::ListPanel::visible_rows(self.terminal_height);
+        let Some(list) = self.alert_list_state.as_mut() else {
+            return Ok(());
+        };
+        list.message = None;
+        match event {
+            AppEvent::NavigateUp => list.move_up(),
+            AppEvent::NavigateDown => list.move_down(),
+            AppEvent::PageUp => list.page_up(page),
+            AppEvent::PageDown => list.page_down(page),
+            AppEvent::Home => list.selected_index = 0,
+            AppEvent::End => list.selected_index = list.alerts.len().saturating_sub(1),
+            AppEvent::Input('r') => self.reload_alert_list().await,
+            AppEvent::Enter => {
+                if let Some(alert) = list.selected().cloned() {
+                    self.jump_to_alert_message(&alert).await?;
+                }
+            }
+            AppEvent::Escape | AppEvent::NavigateLeft | AppEvent::Input('a') => {
+                self.alert_list_state = None;
+                self.state = AppState::TopicList;
+                self.needs_full_redraw = true;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    
+    /// 開啟告警的 topic 並選取觸發的訊息
+    async fn jump_to_alert_message(&mut self, alert: &Alert) -> Result<()> {
+        let message = match alert.message_id {
+            Some(id) => self.repository.get_message_by_id(id).await?,
+            None => None,
+        };
+        let Some(message) = message else {
+            if let Some(list) = self.alert_list_state.as_mut() {
+                list.message = Some("The triggering message is no longer in the database".to_string());
+            }
+            return Ok(());
+        };
+        
+        if self.open_message(&message).await? {
+            self.alert_list_state = None;
+        } else if let Some(list) = self.alert_list_state.as_mut() {
+            list.message = Some("Message does not match the current message list filters".to_string());
+        }
+        Ok(())
+    }
+    
+    /// 開啟訊息的 topic 並選取該訊息；不符合訊息列表的過濾條件時回傳 false
+    async fn open_message(&mut self, message: &Message) -> Result<bool> {
+        self.message_list_state.set_topic(message.topic.clone());
+        self.message_list_state.update_per_page(self.terminal_height);
+        if !self.message_list_state.select_message(&self.repository, message).await? {
+            return Ok(false);
+        }
+        self.state = AppState::MessageList;
+        StatusBar::set_help_text_for_view(&mut self.status_bar_state, &ViewType::MessageList(message.topic.clone()));
+        self.sync_quick_filter_status();
+        self.needs_full_redraw = true;
+        Ok(true)
+    }
+    
+    /// 選取的訊息加上或移除書籤
+    async fn toggle_bookmark(&mut self) {
+        if self.repository.is_read_only() {
+            tracing::info!("Bookmark ignored - database is opened read-only");
+            return;
+        }
+        let Some(message) = self.get_selected_message() else {
+            return;
+        };
+        let Some(id) = message.id else {
+            return;
+        };
+        let result = if self.message_list_state.bookmark_note(message).is_some() {
+            self.repository.delete_bookmark(id).await.map(|_| ())
+        } else {
+            self.repository.set_bookmark(id, "").await
+        };
+        if let Err(e) = result {
+            error!("Failed to update bookmark for message {}: {}", id, e);
+        }
+        self.message_list_state.reload_bookmarks(&self.repository).await;
+        self.needs_full_redraw = true;
+    }
+    
+    /// 編輯選取訊息的備註，儲存時一併加上書籤
+    fn start_note_input(&mut self) {
+        if self.repository.is_read_only() {
+            tracing::info!("Note ignored - database is opened read-only");
+            return;
+        }
+        let note = match self.state {
+            AppState::Bookmarks => self
+                .bookmark_list_state
+                .as_ref()
+                .and_then(|list| list.selected())
+                .and_then(|bookmark| Some((bookmark.message.id?, bookmark.note.clone()))),
+            _ => self.get_selected_message().and_then(|message| {
+                let note = self.message_list_state.bookmark_note(message).unwrap_or("");
+                Some((message.id?, note.to_string()))
+            }),
+        };
+        if let Some((message_id, text)) = note {
+            self.note_input = Some(NoteInput { message_id, text });
+            self.needs_full_redraw = true;
+        }
+    }
+    
+    async fn handle_note_input_event(&mut self, event: AppEvent) -> Result<()> {
+        let Some(input) = self.note_input.as_mut() else {
+            return Ok(());
+        };
+        match event {
+            AppEvent::Input(c) if c != '\0' => input.text.push(c),
+            AppEvent::Space => input.text.push(' '),
+            AppEvent::Backspace => {
+                input.text.pop();
+            }
+            AppEvent::Escape => self.note_input = None,
+            AppEvent::Enter => {
+                if let Some(input) = self.note_input.take() {
+                    if let Err(e) = self.repository.set_bookmark(input.message_id, input.text.trim()).await {
+                        error!("Failed to save note for message {}: {}", input.message_id, e);
+                    }
+                    self.message_list_state.reload_bookmarks(&self.repository).await;
+                    self.reload_bookmark_list().await;
+                }
+            }
+            _ => {}
+        }
+        self.needs_full_redraw = true;
+        Ok(())
+    }
+    
+    pub fn get_note_input(&self) -> Option<&str> {
+        self.note_input.as_ref().map(|input| input.text.as_str())
+    }
+    
+    async fn open_bookmark_list(&mut self) {
+        self.bookmark_list_state = Some(BookmarkListState::default());
+        self.reload_bookmark_list().await;
+        self.state = AppState::Bookmarks;
+        self.needs_full_redraw = true;
+    }
+    
+    async fn reload_bookmark_list(&mut self) {
+        if self.bookmark_list_state.is_none() {
+            return;
+        }
+        let bookmarks = self.repository.list_bookmarks().await;
+        let Some(list) = self.bookmark_list_state.as_mut() else {
+            return;
+        };
+        match bookmarks {
+            Ok(bookmarks) => list.set_bookmarks(bookmarks),
+            Err(e) => {
+                // 舊的資料庫檔案沒有 bookmarks 資料表
+                error!("Failed to load bookmarks: {}", e);
+                list.message = Some(format!("Failed to load bookmarks: {}", e));
+            }
+        }
+    }
+    
+    pub fn get_bookmark_list_state(&self) -> Option<&BookmarkListState> {
+        self.bookmark_list_state.as_ref()
+    }
+    
+    async fn handle_bookmark_list_event(&mut self, event: AppEvent) -> Result<()> {
+        let page = crate::ui::widgets::ListPanel::visible_rows(self.terminal_height);
+        let read_only = self.repository.is_read_only();
+        let Some(list) = self.bookmark_list_state.as_mut() else {
+            return Ok(());
+        };
+        list.message = None;
+        let confirm_delete = std::mem::take(&mut list.delete_confirmation);
+        match event {
+            AppEvent::NavigateUp => list.move_up(),
+            AppEvent::NavigateDown => list.move_down(),
+            AppEvent::PageUp => list.page_up(page),
+            AppEvent::PageDown => list.page_down(page),
+            AppEvent::Home => list.selected_index = 0,
+            AppEvent::End => list.selected_index = list.bookmarks.len().saturating_sub(1),
+            AppEvent::Input('r') => self.reload_bookmark_list().await,
+            AppEvent::Input('n') => self.start_note_input(),
+            AppEvent::Input('e') => self.export_bookmarks(),
+            AppEvent::Delete if read_only => {
+                list.message = Some("Database is opened read-only".to_string());
+            }
+            AppEvent::Delete => {
+                let Some(id) = list.selected().and_then(|bookmark| bookmark.message.id) else {
+                    return Ok(());
+                };
+                if confirm_delete {
+                    if let Err(e) = self.repository.delete_bookmark(id).await {
+                        error!("Failed to remove bookmark for message {}: {}", id, e);
+                    }
+                    self.reload_bookmark_list().await;
+                } else {
+                    list.delete_confirmation = true;
+                    list.message = Some("Remove this bookmark and its note? Press Delete again to confirm".to_string());
+                }
+            }
+            AppEvent::Enter => {
+                if let Some(bookmark) = list.selected().cloned() {
+                    if self.open_message(&bookmark.message).await? {
+                        self.bookmark_list_state = None;
+                    } else if let Some(list) = self.bookmark_list_state.as_mut() {
+                        list.message = Some("Message does not match the current message list filters".to_string());
+                    }
+                }
+            }
+            AppEvent::Escape | AppEvent::NavigateLeft | AppEvent::Input('b') => {
+                self.bookmark_list_state = None;
+                self.state = AppState::TopicList;
+                self.needs_full_redraw = true;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    
+    /// 書籤與備註匯出到目前目錄的 JSON Lines 檔案
+    fn export_bookmarks(&mut self) {
+        let Some(list) = self.bookmark_list_state.as_mut() else {
+            return;
+        };
+        let path = format!("bookmarks-{}.jsonl", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        list.message = Some(match std::fs::write(&path, list.to_jsonl()) {
+            Ok(()) => {
+                info!("Exported {} bookmarks to {}", list.bookmarks.len(), path);
+                format!("Exported {} bookmarks to {}", list.bookmarks.len(), path)
+            }
+            Err(e) => {
+                error!("Failed to export bookmarks to {}: {}", path, e);
+                format!("Failed to export bookmarks: {}", e)
+            }
+        });
+    }
+    
+    pub fn set_ingest_queue(&mut self, queue: IngestQueue) {
+        self.status_bar_state.queue_stats = queue.stats();
+        self.ingest_queue = Some(queue);
+    }
+    
+    pub fn apply_connection_event(&mut self, event: ConnectionEvent) {
+        let status = match event {
+            ConnectionEvent::Connecting => ConnectionStatus::Connecting,
+            ConnectionEvent::Connected { session_present } => ConnectionStatus::Connected(
+                if session_present {
+                    format!("{}:{}, session resumed", self.mqtt_host, self.mqtt_port)
+                } else {
+                    format!("{}:{}", self.mqtt_host, self.mqtt_port)
+                }
+            ),
+            ConnectionEvent::Disconnected => ConnectionStatus::Disconnected,
+            ConnectionEvent::Reconnecting { attempt, delay, error } => ConnectionStatus::Reconnecting {
+                attempt,
+                delay,
+                error,
+            },
+            ConnectionEvent::AuthFailed { attempt, delay, error } => ConnectionStatus::AuthFailed(
+                format!("{} (retry #{} in {})", error, attempt, StatusBar::format_delay(delay))
+            ),
+            ConnectionEvent::Error(error) => ConnectionStatus::Error(error),
+        };
+        
+        // 瀏覽唯讀檔案時不顯示連線狀態，回到即時資料庫時再還原
+        self.live_connection_status = status.clone();
+        if !self.repository.is_read_only() {
+            self.set_connection_status(status);
+        }
+    }
+    
+    // State comparison methods for incremental rendering
+    fn states_equal_filter(&self, prev: &FilterState, current: &FilterState) -> bool {
+        prev.topic_filter == current.topic_filter &&
+        prev.payload_filter == current.payload_filter &&
+        prev.start_time == current.start_time &&
+        prev.end_time == current.end_time &&
+        prev.filter_error == current.filter_error &&
+        prev.active_field == current.active_field &&
+        prev.is_editing == current.is_editing
+    }
+    
+    fn states_equal_topics(&self, prev: &TopicListState, current: &TopicListState) -> bool {
+        if prev.topics.len() != current.topics.len() {
+            return false;
+        }
+        
+        if prev.selected_index != current.selected_index {
+            return false;
+        }
+        
+        if prev.scroll_offset != current.scroll_offset {
+            return false;
+        }
+        
+        if prev.sort_key != current.sort_key || prev.descending != current.descending || prev.stale_only != current.stale_only ||
+           prev.pinned != current.pinned || prev.pinned_only != current.pinned_only ||
+           prev.columns != current.columns || prev.count_columns != current.count_columns {
+            return false;
+        }
+        
+        // Compare topic contents (simplified comparison)
+        for (prev_topic, current_topic) in prev.topics.iter().zip(current.topics.iter()) {
+            if prev_topic.topic != current_topic.topic ||
+               prev_topic.message_count != current_topic.message_count ||
+               prev_topic.last_message_time != current_topic.last_message_time ||
+               prev_topic.latest_payload != current_topic.latest_payload ||
+               prev_topic.quick_filter_counts != current_topic.quick_filter_counts ||
+               prev_topic.rates != current_topic.rates ||
+               prev_topic.silent_for != current_topic.silent_for ||
+               prev_topic.muted != current_topic.muted {
+                return false;
+            }
+        }
+        
+        true
+    }
+    
+    fn states_equal_status(&self, prev: &StatusBarState, current: &StatusBarState) -> bool {
+        prev.connection_status == current.connection_status &&
+        prev.total_topics == current.total_topics &&
+        prev.total_messages == current.total_messages &&
+        prev.queue_stats == current.queue_stats &&
+        prev.last_update == current.last_update &&
+        prev.help_text == current.help_text &&
+        prev.quick_filter_states == current.quick_filter_states
+    }
+    
+    #[cfg(windows)]
+    fn is_key_just_pressed(&mut self, vk_code: i32) -> bool {
+        let is_pressed = unsafe { GetAsyncKeyState(vk_code) & (0x8000u16 as i16) != 0 };
+        let was_pressed = self.last_key_state.get(&vk_code).copied().unwrap_or(false);
+        
+        // 更新按鍵狀態
+        self.last_key_state.insert(vk_code, is_pressed);
+        
+        // 只有從未按下到按下的狀態轉換才返回true（邊沿觸發）
+        is_pressed && !was_pressed
+    }
+    
+    #[cfg(windows)]
+    fn is_key_pressed(&self, vk_code: i32) -> bool {
+        unsafe { GetAsyncKeyState(vk_code) & (0x8000u16 as i16) != 0 }
+    }
+    
+    // Key repeat handling methods
+    async fn handle_key_repeat_start(&mut self, key: crossterm::event::KeyCode) -> Result<bool> {
+        let now = Instant::now();
+        
+        // If this is a new key press
+        if self.key_repeat_state.current_key != Some(key) {
+            self.key_repeat_state.current_key = Some(key);
+            self.key_repeat_state.key_pressed_at = Some(now);
+            self.key_repeat_state.last_repeat_at = None;
+            self.key_repeat_state.is_repeating = false;
+            
+            // Handle the initial key press immediately
+            let event = match key {
+                crossterm::event::KeyCode::Up => AppEvent::NavigateUp,
+                crossterm::event::KeyCode::Down => AppEvent::NavigateDown,
+                crossterm::event::KeyCode::PageUp => AppEvent::PageUp,
+                crossterm::event::KeyCode::PageDown => AppEvent::PageDown,
+                _ => return Ok(false),
+            };
+            
+            if self.handle_event(event).await? {
+                return Ok(true);
+            }
+            self.render()?;
+        }
+        
+        Ok(false)
+    }
+    
+    fn handle_key_release(&mut self, key: crossterm::event::KeyCode) {
+        if self.key_repeat_state.current_key == Some(key) {
+            self.key_repeat_state.current_key = None;
+            self.key_repeat_state.key_pressed_at = None;
+            self.key_repeat_state.last_repeat_at = None;
+            self.key_repeat_state.is_repeating = false;
+        }
+    }
+    
+    async fn handle_key_repeat(&mut self) -> Result<()> {
+        let now = Instant::now();
+        
+        if let (Some(key), Some(pressed_at)) = (self.key_repeat_state.current_key, self.key_repeat_state.key_pressed_at) {
+            // Check if we should start repeating (after 0.5 seconds)
+            if !self.key_repeat_state.is_repeating && now.duration_since(pressed_at) >= Duration::from_millis(500) {
+                self.key_repeat_state.is_repeating = true;
+                self.key_repeat_state.last_repeat_at = Some(now);
+            }
+            
+            // If we're in repeat mode, check if it's time for the next repeat (every 0.2 seconds)
             if self.key_repeat_state.is_repeating {
                 let last_repeat = self.key_repeat_state.last_repeat_at.unwrap_or(pressed_at);
                 if now.duration_since(last_repeat) >= Duration::from_millis(50) {
@@ -2115,10 +3721,6 @@ impl App {
         self.state
     }
     
-    pub fn get_config(&self) -> &Config {
-        &self.config
-    }
-    
     pub fn get_terminal_size(&self) -> (u16, u16) {
         (self.terminal_width, self.terminal_height)
     }
@@ -9,9 +9,12 @@ use anyhow::Result;
 use tracing::{info, error};
 
 use crate::ui::widgets::{FilterBar, StatusBar};
-use crate::ui::views::{TopicListView};
+use crate::ui::views::{TopicListView, FilePickerView, PresetPickerView, ColumnEditorView, TopicColumnEditorView, MessageDiffView, ChartView, AlertListView, BookmarkListView, MuteListView, TrashListView};
 use crate::ui::app::{App, AppState};
 
+// 訊息列表中書籤備註最多顯示的字元數
+const BOOKMARK_NOTE_WIDTH: usize = 24;
+
 // 用於自動換行的結構體
 #[derive(Debug, Clone)]
 struct WrappedLine {
@@ -53,6 +56,66 @@ impl App {
                 info!("Rendering PayloadDetail");
                 self.render_payload_detail()?;
             },
+            AppState::FilePicker => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(picker) = self.get_file_picker_state() {
+                    FilePickerView::render(picker, terminal_width, terminal_height)?;
+                }
+            },
+            AppState::PresetPicker => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(picker) = self.get_preset_picker_state() {
+                    PresetPickerView::render(picker, terminal_width, terminal_height)?;
+                }
+            },
+            AppState::MessageDiff => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(diff) = self.get_message_diff_state() {
+                    MessageDiffView::render(diff, terminal_width, terminal_height)?;
+                }
+            },
+            AppState::TopicColumnEditor => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(editor) = self.get_topic_column_editor_state() {
+                    TopicColumnEditorView::render(editor, terminal_width, terminal_height)?;
+                }
+            },
+            AppState::Chart => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(chart) = self.get_chart_state() {
+                    ChartView::render(chart, terminal_width, terminal_height)?;
+                }
+            },
+            AppState::Alerts => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(list) = self.get_alert_list_state() {
+                    AlertListView::render(list, terminal_width, terminal_height)?;
+                }
+            },
+            AppState::Trash => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(list) = self.get_trash_list_state() {
+                    TrashListView::render(list, terminal_width, terminal_height)?;
+                }
+            },
+            AppState::MuteList => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(list) = self.get_mute_list_state() {
+                    MuteListView::render(list, terminal_width, terminal_height)?;
+                }
+            },
+            AppState::Bookmarks => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(list) = self.get_bookmark_list_state() {
+                    BookmarkListView::render(list, self.get_note_input(), terminal_width, terminal_height)?;
+                }
+            },
+            AppState::ColumnEditor => {
+                let (terminal_width, terminal_height) = self.get_terminal_size();
+                if let Some(editor) = self.get_column_editor_state() {
+                    ColumnEditorView::render(editor, terminal_width, terminal_height)?;
+                }
+            },
             _ => {
                 panic!("Unhandled state in render");
             }
@@ -119,10 +182,11 @@ impl App {
         // Check if status bar changed
         let status_changed = self.has_status_bar_state_changed();
         
-        if force_redraw || status_changed {
+        // Filter bar 重繪會清掉第 1 列的連線狀態，需一併重畫
+        if force_redraw || filter_changed || status_changed {
             let (terminal_width, terminal_height) = self.get_terminal_size();
             let status_start_row = terminal_height.saturating_sub(status_rows);
-            if force_redraw {
+            if force_redraw || filter_changed {
                 StatusBar::render(self.get_status_bar_state(), status_start_row, terminal_width)?;
             } else {
                 StatusBar::render_incremental(
@@ -200,7 +264,12 @@ impl App {
         stdout.queue(MoveTo(0, 4))?;
         stdout.queue(Clear(crossterm::terminal::ClearType::CurrentLine))?;
         stdout.queue(Print("│ "))?;
-        let header = format!("  {:>5} │ {:<10} │ {:<50}", "No.", "Time", "Payload");
+        let (column_widths, _) = self.message_column_cells();
+        let mut header = format!("  {:>5} │ {:<10} │ ", "No.", "Time");
+        for (column, width) in self.get_message_columns().iter().zip(&column_widths) {
+            header.push_str(&format!("{:<width$} │ ", fit_width(&column.title(), *width), width = *width));
+        }
+        header.push_str("Payload");
         let padded_header = format!("{:<width$}", header, width = terminal_width.saturating_sub(3));
         stdout.queue(Print(&padded_header))?;
         stdout.queue(Print("│"))?;
@@ -228,69 +297,46 @@ impl App {
         stdout.queue(MoveTo(0, status_start_row + 1))?;
         stdout.queue(Clear(crossterm::terminal::ClearType::CurrentLine))?;
         
-        // 先顯示基本操作說明
-        let help_text = "[←]back [Tab]focus [Enter]view [↑↓]navigate [PgUp/PgDn]page";
-        stdout.queue(Print(help_text))?;
+        // 先顯示基本操作說明；編輯書籤備註時改為顯示輸入內容
+        let help_text = match self.get_note_input() {
+            Some(note) => format!("Note: {}█ [Enter]save [Esc]cancel", note),
+            None => match self.get_last_delete() {
+                Some(entry) => format!("[←]back [Tab]focus [Enter]view [↑↓]navigate [b]bookmark [n]note | Deleted {} [z]undo", entry.count),
+                None => "[←]back [Tab]focus [Enter]view [↑↓]navigate [PgUp/PgDn]page [b]bookmark [n]note".to_string(),
+            },
+        };
+        stdout.queue(Print(&help_text))?;
         
         // 計算快速過濾器狀態的位置（右對齊）
-        let config = self.get_config();
-        if config.quick_filters.enabled && !config.quick_filters.filters.is_empty() {
-            let message_state = self.get_message_list_state();
-            let mut filter_status_parts = Vec::new();
-            
-            for (index, filter) in config.quick_filters.filters.iter().enumerate() {
-                if index < 5 { // 只顯示F1-F5
-                    let status_symbol = if message_state.get_quick_filter_state(index) { "✓" } else { "✗" };
-                    let status_text = format!("[F{}:{} {}]", index + 1, filter.name, status_symbol);
-                    filter_status_parts.push((status_text, filter.color.as_str(), message_state.get_quick_filter_state(index)));
-                }
-            }
-            
+        let filter_statuses = self.quick_filter_statuses();
+        if !filter_statuses.is_empty() {
             // 計算所有狀態文字的總長度
-            let total_filter_len: usize = filter_status_parts.iter()
-                .map(|(text, _, _)| text.len() + 1) // +1 for space
+            let total_filter_len: usize = filter_statuses.iter()
+                .map(|status| status.text().chars().count() + 1) // +1 for space
                 .sum();
             
-            if total_filter_len > 0 {
-                // 計算右對齊位置
-                let help_len = help_text.len();
-                let available_space = terminal_width.saturating_sub(help_len + total_filter_len + 3); // +3 for " | "
+            // 計算右對齊位置，放不下時不顯示
+            let help_len = help_text.chars().count();
+            let available_space = terminal_width.saturating_sub(help_len + total_filter_len + 3); // +3 for " | "
+            
+            if available_space > 0 {
+                stdout.queue(Print(&format!("{:<width$}", "", width = available_space)))?;
+                stdout.queue(Print(" | "))?;
                 
-                if available_space > 0 {
-                    stdout.queue(Print(&format!("{:<width$}", "", width = available_space)))?;
-                    stdout.queue(Print(" | "))?;
-                    
-                    // 顯示每個過濾器狀態，使用對應顏色
-                    for (i, (status_text, color_name, is_enabled)) in filter_status_parts.iter().enumerate() {
-                        if i > 0 {
-                            stdout.queue(Print(" "))?;
-                        }
-                        
-                        // 根據狀態和顏色設置顯示顏色
-                        if *is_enabled {
-                            // 啟用時使用過濾器對應的顏色
-                            let color = match *color_name {
-                                "red" => crossterm::style::Color::Red,
-                                "green" => crossterm::style::Color::Green,
-                                "light_green" => crossterm::style::Color::DarkGreen,
-                                "yellow" => crossterm::style::Color::Yellow,
-                                "blue" => crossterm::style::Color::Blue,
-                                "cyan" => crossterm::style::Color::Cyan,
-                                "magenta" => crossterm::style::Color::Magenta,
-                                "white" => crossterm::style::Color::White,
-                                "dark_grey" => crossterm::style::Color::DarkGrey,
-                                "grey" => crossterm::style::Color::Grey,
-                                _ => crossterm::style::Color::White,
-                            };
-                            stdout.queue(SetForegroundColor(color))?;
-                        } else {
-                            // 停用時使用暗灰色
-                            stdout.queue(SetForegroundColor(crossterm::style::Color::DarkGrey))?;
-                        }
-                        
-                        stdout.queue(Print(status_text))?;
-                        stdout.queue(ResetColor)?;
+                // 顯示每個過濾器狀態，啟用時使用過濾器對應的顏色，停用時使用暗灰色
+                for (i, status) in filter_statuses.iter().enumerate() {
+                    if i > 0 {
+                        stdout.queue(Print(" "))?;
                     }
+                    
+                    let color = if status.enabled {
+                        status.color.unwrap_or(crossterm::style::Color::White)
+                    } else {
+                        crossterm::style::Color::DarkGrey
+                    };
+                    stdout.queue(SetForegroundColor(color))?;
+                    stdout.queue(Print(status.text()))?;
+                    stdout.queue(ResetColor)?;
                 }
             }
         }
@@ -401,7 +447,10 @@ impl App {
         // Render help line
         stdout.queue(MoveTo(0, status_start_row + 1))?;
         stdout.queue(Clear(crossterm::terminal::ClearType::CurrentLine))?;
-        stdout.queue(Print("[←]back [Tab]switch [Alt+C]copy [↑↓]scroll [PgUp/PgDn]page [F1]help"))?;
+        match self.get_note_input() {
+            Some(note) => stdout.queue(Print(&format!("Note: {}█ [Enter]save [Esc]cancel", note)))?,
+            None => stdout.queue(Print("[←]back [Tab]switch [Alt+C]copy [↑↓]scroll [PgUp/PgDn]page [m]mark [d]diff [b]bookmark [n]note [F1]help"))?,
+        };
         
         stdout.flush()?;
         info!("render_payload_detail() completed - PayloadDetail UI should now be visible");
@@ -596,42 +645,30 @@ impl App {
     }
     
     
-    // 根據訊息內容返回對應的顏色
+    // 根據訊息內容返回對應的顏色（第一個符合的快速過濾器）
     fn get_message_color(&self, message: &crate::db::Message) -> Option<crossterm::style::Color> {
-        let config = self.get_config();
-        if !config.quick_filters.enabled {
-            return None;
-        }
-        
-        let content = format!("{} {}", message.topic, message.payload);
-        
-        // 檢查每個過濾器的模式並返回對應顏色
-        for filter in &config.quick_filters.filters {
-            let matches = if filter.case_sensitive {
-                content.contains(&filter.pattern)
-            } else {
-                content.to_lowercase().contains(&filter.pattern.to_lowercase())
-            };
-            
-            if matches {
-                // 根據顏色名稱返回對應的crossterm顏色
-                return match filter.color.as_str() {
-                    "red" => Some(crossterm::style::Color::Red),
-                    "green" => Some(crossterm::style::Color::Green),
-                    "light_green" => Some(crossterm::style::Color::DarkGreen),
-                    "yellow" => Some(crossterm::style::Color::Yellow),
-                    "blue" => Some(crossterm::style::Color::Blue),
-                    "cyan" => Some(crossterm::style::Color::Cyan),
-                    "magenta" => Some(crossterm::style::Color::Magenta),
-                    "white" => Some(crossterm::style::Color::White),
-                    "dark_grey" => Some(crossterm::style::Color::DarkGrey),
-                    "grey" => Some(crossterm::style::Color::Grey),
-                    _ => None,
-                };
-            }
+        self.get_message_list_state().quick_filters.color_for(message)
+    }
+    
+    /// 本頁訊息的 JSON 欄位值與欄寬 (沒有設定欄位時為空)
+    fn message_column_cells(&self) -> (Vec<usize>, Vec<Vec<String>>) {
+        let columns = self.get_message_columns();
+        if columns.is_empty() {
+            return (Vec::new(), Vec::new());
         }
-        
-        None
+        let cells: Vec<Vec<String>> = self.get_message_list_state().messages
+            .iter()
+            .map(|msg| {
+                let json = serde_json::from_str::<serde_json::Value>(&msg.payload).ok();
+                columns.iter().map(|column| column.value(json.as_ref())).collect()
+            })
+            .collect();
+        let widths = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| column.display_width(cells.iter().map(|row| row[index].as_str())))
+            .collect();
+        (widths, cells)
     }
     
     pub fn render_message_list_content(&self, stdout: &mut std::io::Stdout, terminal_width: usize, 
@@ -639,6 +676,7 @@ impl App {
         let message_state = self.get_message_list_state();
         let messages = &message_state.messages;
         let selected_index = message_state.selected_index;
+        let (column_widths, column_cells) = self.message_column_cells();
         
         for i in 0..available_height {
             let row = content_start_row + i as u16;
@@ -652,13 +690,19 @@ impl App {
                 if i as usize == selected_index {
                     let is_message_list_focused = matches!(message_state.get_focus(), 
                         crate::ui::views::message_list::FocusTarget::MessageList);
+                    // 標記的訊息 (比較差異用) 以 * 表示
+                    let mark = if message_state.is_marked(msg) { "*" } else { " " };
                     if is_message_list_focused {
                         stdout.queue(SetForegroundColor(crossterm::style::Color::Cyan))?;
-                        stdout.queue(Print(">>"))?;
+                        stdout.queue(Print(if mark == "*" { ">*" } else { ">>" }))?;
                     } else {
                         stdout.queue(SetForegroundColor(crossterm::style::Color::DarkCyan))?;
-                        stdout.queue(Print("> "))?;
+                        stdout.queue(Print(&format!(">{}", mark)))?;
                     }
+                } else if message_state.is_marked(msg) {
+                    stdout.queue(SetForegroundColor(crossterm::style::Color::Magenta))?;
+                    stdout.queue(Print(" *"))?;
+                    stdout.queue(ResetColor)?;
                 } else {
                     stdout.queue(Print("  "))?;
                 }
@@ -697,7 +741,11 @@ impl App {
                     
                     // Format timestamp (HH:MM:SS) - convert from UTC to Local time
                     let local_time = msg.timestamp.with_timezone(&chrono::Local);
-                    let time_str = local_time.format("%H:%M:%S").to_string();
+                    let mut time_str = local_time.format("%H:%M:%S").to_string();
+                    if msg.backlog {
+                        // 重新連線後 broker 補送的離線訊息
+                        time_str.push_str(" B");
+                    }
                     stdout.queue(Print(&format!("{:<10}", time_str)))?;
                     stdout.queue(Print(" │ "))?;
                     
@@ -705,7 +753,51 @@ impl App {
                     // 精確計算寬度：
                     // │ (2) + >> (3) + 4616 (5) +  │  (3) + 22:54:15 (10) +  │  (3) + payload + │ (1)
                     // = 2 + 3 + 5 + 3 + 10 + 3 + payload + 1 = 27 + payload
-                    let max_payload_width = terminal_width.saturating_sub(20);
+                    let mut max_payload_width = terminal_width.saturating_sub(20);
+                    
+                    // 依 topic 設定的 JSON 欄位
+                    if let Some(cells) = column_cells.get(i as usize) {
+                        for (cell, width) in cells.iter().zip(&column_widths) {
+                            stdout.queue(Print(&format!("{:<width$} │ ", fit_width(cell, *width), width = *width)))?;
+                            max_payload_width = max_payload_width.saturating_sub(width + 3);
+                        }
+                    }
+                    
+                    // 書籤與備註
+                    if let Some(note) = message_state.bookmark_note(msg) {
+                        let tag = if note.is_empty() {
+                            "[BM] ".to_string()
+                        } else {
+                            format!("[BM: {}] ", fit_width(note, BOOKMARK_NOTE_WIDTH))
+                        };
+                        stdout.queue(SetForegroundColor(crossterm::style::Color::Yellow))?;
+                        stdout.queue(Print(&tag))?;
+                        stdout.queue(ResetColor)?;
+                        if i as usize == selected_index {
+                            stdout.queue(SetForegroundColor(crossterm::style::Color::Cyan))?;
+                        }
+                        max_payload_width = max_payload_width.saturating_sub(tag.chars().count());
+                    }
+                    
+                    // Sparkplug B 的 BIRTH/DEATH 與 seq 跳號標記
+                    if let Some(marker) = &msg.marker {
+                        let tag = format!("[{}] ", marker);
+                        let tag_color = if marker.contains("GAP") {
+                            crossterm::style::Color::Yellow
+                        } else if marker.contains("DEATH") {
+                            crossterm::style::Color::Red
+                        } else {
+                            crossterm::style::Color::Green
+                        };
+                        stdout.queue(SetForegroundColor(tag_color))?;
+                        stdout.queue(Print(&tag))?;
+                        stdout.queue(ResetColor)?;
+                        if i as usize == selected_index {
+                            stdout.queue(SetForegroundColor(crossterm::style::Color::Cyan))?;
+                        }
+                        max_payload_width = max_payload_width.saturating_sub(tag.chars().count());
+                    }
+                    
                     let payload_display = if msg.payload.chars().count() > max_payload_width {
                         // 使用 Unicode 安全的字符截斷
                         let truncate_len = max_payload_width.saturating_sub(3);
@@ -813,7 +905,21 @@ impl App {
         }
         
         // QoS and Retain info
-        let qos_retain_text = format!(" | QoS: {} | Retain: {}", selected_message.qos, selected_message.retain);
+        let mut qos_retain_text = format!(" | QoS: {} | Retain: {} | Backlog: {}", 
+                                      selected_message.qos, selected_message.retain, selected_message.backlog);
+        if let Some(encoding) = &selected_message.encoding {
+            // 由解碼器轉成 JSON 的二進位 payload
+            qos_retain_text.push_str(&format!(" | Encoding: {}", encoding));
+        }
+        if let Some(level) = &selected_message.level {
+            qos_retain_text.push_str(&format!(" | Level: {}", level));
+        }
+        if let Some(note) = self.get_message_list_state().bookmark_note(selected_message) {
+            qos_retain_text.push_str(" | Bookmarked");
+            if !note.is_empty() {
+                qos_retain_text.push_str(&format!(": {}", note));
+            }
+        }
         stdout.queue(Print(&qos_retain_text))?;
         
         // 簡化padding計算，避免overflow
@@ -1014,4 +1120,15 @@ impl App {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+// 依字元數截斷，超過時以 … 結尾
+fn fit_width(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    }
+}
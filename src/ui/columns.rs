@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::utils::json_path::JsonPath;
+use crate::utils::topic_pattern::topic_matches;
+
+// 未指定寬度時依內容自動調整的上限
+const MAX_AUTO_WIDTH: usize = 24;
+
+/// 訊息清單中以 JSON path 擷取的欄位
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayloadColumn {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<usize>,
+}
+
+impl PayloadColumn {
+    /// 解析「path [標題]」，例如 "$.temperature Temp"
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let text = text.trim();
+        let (path, title) = match text.split_once(char::is_whitespace) {
+            Some((path, title)) => (path, Some(title.trim().to_string())),
+            None => (text, None),
+        };
+        JsonPath::parse(path)?;
+        Ok(Self {
+            path: path.to_string(),
+            title: title.filter(|t| !t.is_empty()),
+            width: None,
+        })
+    }
+
+    /// 欄位標題，未設定時取 path 的最後一段
+    pub fn title(&self) -> String {
+        self.title.clone().unwrap_or_else(|| {
+            let last = self.path.rsplit(['.', '[']).next().unwrap_or(&self.path);
+            last.trim_matches(|c| c == ']' || c == '"' || c == '\'').to_string()
+        })
+    }
+
+    /// 從 payload 取值並轉成顯示文字；不是 JSON 或找不到時為 "-"
+    pub fn value(&self, json: Option<&Value>) -> String {
+        let value = JsonPath::parse(&self.path)
+            .ok()
+            .and_then(|path| json.and_then(|json| path.get(json).cloned()));
+        match value {
+            Some(Value::String(text)) => text,
+            Some(value) => value.to_string(),
+            None => "-".to_string(),
+        }
+    }
+
+    /// 欄寬：設定值，否則依標題與目前顯示的值決定
+    pub fn display_width<'a>(&self, values: impl Iterator<Item = &'a str>) -> usize {
+        self.width.unwrap_or_else(|| {
+            values
+                .map(|v| v.chars().count())
+                .chain(std::iter::once(self.title().chars().count()))
+                .max()
+                .unwrap_or(0)
+                .clamp(1, MAX_AUTO_WIDTH)
+        })
+    }
+}
+
+/// 套用到符合 topic pattern (MQTT 萬用字元) 的訊息清單欄位
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSet {
+    pub topic: String,
+    #[serde(default)]
+    pub columns: Vec<PayloadColumn>,
+    // 設定檔中的欄位；在程式內修改後另存到資料庫並優先使用
+    #[serde(skip)]
+    pub from_config: bool,
+}
+
+impl ColumnSet {
+    /// 第一個符合 topic 的欄位組合 (資料庫的排在設定檔前面)
+    pub fn for_topic<'a>(sets: &'a [ColumnSet], topic: &str) -> Option<&'a ColumnSet> {
+        sets.iter().find(|set| topic_matches(&set.topic, topic))
+    }
+}
+
+/// Topic 清單可顯示的欄位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopicColumn {
+    Number,
+    LastSeen,
+    Silence, // 超過預期間隔未收到訊息的時間 ([[stale_topics]])
+    FirstSeen,
+    Topic,
+    Count,
+    Bytes,
+    #[serde(rename = "rate_1m")]
+    Rate1m,
+    #[serde(rename = "rate_5m")]
+    Rate5m,
+    #[serde(rename = "rate_1h")]
+    Rate1h,
+    Sparkline,
+    QuickFilters, // 設定 show_count 的快速過濾器計數，每個過濾器一欄
+    Payload,
+}
+
+impl TopicColumn {
+    pub const ALL: [TopicColumn; 13] = [
+        TopicColumn::Number,
+        TopicColumn::LastSeen,
+        TopicColumn::Silence,
+        TopicColumn::FirstSeen,
+        TopicColumn::Topic,
+        TopicColumn::Count,
+        TopicColumn::Bytes,
+        TopicColumn::Rate1m,
+        TopicColumn::Rate5m,
+        TopicColumn::Rate1h,
+        TopicColumn::Sparkline,
+        TopicColumn::QuickFilters,
+        TopicColumn::Payload,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            TopicColumn::Number => "No.",
+            TopicColumn::LastSeen => "Last Message",
+            TopicColumn::Silence => "Silent",
+            TopicColumn::FirstSeen => "First Message",
+            TopicColumn::Topic => "Topic",
+            TopicColumn::Count => "Count",
+            TopicColumn::Bytes => "Bytes",
+            TopicColumn::Rate1m => "1m/s",
+            TopicColumn::Rate5m => "5m/s",
+            TopicColumn::Rate1h => "1h/s",
+            TopicColumn::Sparkline => "Last hour",
+            TopicColumn::QuickFilters => "Quick filter counts",
+            TopicColumn::Payload => "Latest Payload",
+        }
+    }
+
+    pub fn default_width(self) -> usize {
+        match self {
+            TopicColumn::Number => 5,
+            TopicColumn::LastSeen => 12,
+            TopicColumn::Silence => 7,
+            TopicColumn::FirstSeen => 14,
+            TopicColumn::Topic => 18,
+            TopicColumn::Count | TopicColumn::Bytes | TopicColumn::Rate1m | TopicColumn::Rate5m | TopicColumn::Rate1h => 6,
+            TopicColumn::Sparkline => 12,
+            TopicColumn::QuickFilters => 5, // 實際寬度依各過濾器名稱
+            TopicColumn::Payload => 25,
+        }
+    }
+
+    /// 數值欄位靠右對齊
+    pub fn right_aligned(self) -> bool {
+        matches!(
+            self,
+            TopicColumn::Number | TopicColumn::Silence | TopicColumn::Bytes | TopicColumn::Rate1m | TopicColumn::Rate5m | TopicColumn::Rate1h
+        )
+    }
+}
+
+/// Topic 清單的一個欄位與寬度 (未設定時使用預設寬度)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TopicColumnConfig {
+    pub column: TopicColumn,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<usize>,
+}
+
+impl TopicColumnConfig {
+    pub fn new(column: TopicColumn) -> Self {
+        Self { column, width: None }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width.unwrap_or_else(|| self.column.default_width())
+    }
+
+    /// 預設顯示的欄位
+    pub fn defaults() -> Vec<Self> {
+        [
+            TopicColumn::Number,
+            TopicColumn::LastSeen,
+            TopicColumn::Silence,
+            TopicColumn::Topic,
+            TopicColumn::Count,
+            TopicColumn::Rate1m,
+            TopicColumn::Rate5m,
+            TopicColumn::Rate1h,
+            TopicColumn::Sparkline,
+            TopicColumn::QuickFilters,
+            TopicColumn::Payload,
+        ]
+        .into_iter()
+        .map(Self::new)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payload_column() {
+        let column = PayloadColumn::parse("$.sensor.temperature  Temp").unwrap();
+        assert_eq!(column.title(), "Temp");
+        assert_eq!(column.value(Some(&json!({"sensor": {"temperature": 21.5}}))), "21.5");
+        assert_eq!(column.value(None), "-");
+
+        let column = PayloadColumn::parse("$.status").unwrap();
+        assert_eq!(column.title(), "status");
+        assert_eq!(column.value(Some(&json!({"status": "fault"}))), "fault");
+        assert_eq!(column.display_width(["fault", "ok"].into_iter()), 6);
+        assert!(PayloadColumn::parse("$.a[x]").is_err());
+
+        let sets = vec![
+            ColumnSet { topic: "plant/+/telemetry".to_string(), columns: vec![column], from_config: false },
+            ColumnSet { topic: "#".to_string(), columns: vec![], from_config: true },
+        ];
+        assert!(!ColumnSet::for_topic(&sets, "plant/a/telemetry").unwrap().from_config);
+        assert!(ColumnSet::for_topic(&sets, "office/a").unwrap().from_config);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod app;
 pub mod app_render;
+pub mod columns;
 pub mod views;
 pub mod widgets;
 
-pub use app::*;
\ No newline at end of file
+pub use app::*;
+pub use columns::*;
\ No newline at end of file
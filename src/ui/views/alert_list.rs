@@ -0,0 +1,91 @@
+use anyhow::Result;
+use crossterm::style::Color;
+
+use crate::db::{Alert, AlertKind};
+use crate::ui::widgets::{ListPanel, ListRow};
+
+/// 已觸發的告警清單，可跳到觸發的訊息
+#[derive(Debug, Clone, Default)]
+pub struct AlertListState {
+    pub alerts: Vec<Alert>, // 新→舊
+    pub selected_index: usize,
+    pub message: Option<String>,
+}
+
+impl AlertListState {
+    /// 重新載入後盡量保持選取同一筆告警
+    pub fn set_alerts(&mut self, alerts: Vec<Alert>) {
+        let selected_id = self.selected().and_then(|alert| alert.id);
+        self.alerts = alerts;
+        self.selected_index = selected_id
+            .and_then(|id| self.alerts.iter().position(|alert| alert.id == Some(id)))
+            .unwrap_or(0);
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index + 1 < self.alerts.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn page_up(&mut self, page: usize) {
+        self.selected_index = self.selected_index.saturating_sub(page.max(1));
+    }
+
+    pub fn page_down(&mut self, page: usize) {
+        self.selected_index = (self.selected_index + page.max(1)).min(self.alerts.len().saturating_sub(1));
+    }
+
+    pub fn selected(&self) -> Option<&Alert> {
+        self.alerts.get(self.selected_index)
+    }
+}
+
+pub struct AlertListView;
+
+impl AlertListView {
+    pub fn render(state: &AlertListState, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let rows: Vec<ListRow> = state.alerts.iter().map(Self::format_alert).collect();
+        let footer = state.message.clone().unwrap_or_else(|| {
+            "[Enter]jump to message [r]reload [↑↓]navigate [Esc]back".to_string()
+        });
+        ListPanel {
+            title: "Alerts",
+            header: Some(&format!(" {:<19} {:<9} {:<16} {:<24} {}", "Time", "Kind", "Rule", "Topic", "Description")),
+            rows: &rows,
+            selected_index: state.selected_index,
+            empty_text: "No alerts. Configure rules in [[alerts]] of the config file.",
+            footer: &footer,
+        }
+        .render(terminal_width, terminal_height)
+    }
+
+    fn format_alert(alert: &Alert) -> ListRow {
+        let text = format!(
+            " {:<19} {:<9} {:<16} {:<24} {}",
+            alert.fired_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+            alert.kind.as_str(),
+            Self::truncate(&alert.rule, 16),
+            Self::truncate(&alert.topic, 24),
+            alert.description,
+        );
+        let color = match alert.kind {
+            AlertKind::Condition => Color::Yellow,
+            AlertKind::Rate => Color::Magenta,
+            AlertKind::Silence => Color::Red,
+        };
+        ListRow::colored(text, color)
+    }
+
+    fn truncate(text: &str, width: usize) -> String {
+        if text.chars().count() > width {
+            format!("{}…", text.chars().take(width - 1).collect::<String>())
+        } else {
+            text.to_string()
+        }
+    }
+}
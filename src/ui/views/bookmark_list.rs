@@ -0,0 +1,146 @@
+use anyhow::Result;
+use crossterm::style::Color;
+
+use crate::db::Bookmark;
+use crate::ui::widgets::{ListPanel, ListRow};
+
+/// 所有 topic 的書籤清單，可跳到訊息、編輯備註與匯出
+#[derive(Debug, Clone, Default)]
+pub struct BookmarkListState {
+    pub bookmarks: Vec<Bookmark>, // 新→舊
+    pub selected_index: usize,
+    pub message: Option<String>,
+    pub delete_confirmation: bool,
+}
+
+impl BookmarkListState {
+    /// 重新載入後盡量保持選取同一筆訊息
+    pub fn set_bookmarks(&mut self, bookmarks: Vec<Bookmark>) {
+        let selected_id = self.selected().and_then(|bookmark| bookmark.message.id);
+        self.bookmarks = bookmarks;
+        self.selected_index = selected_id
+            .and_then(|id| self.bookmarks.iter().position(|bookmark| bookmark.message.id == Some(id)))
+            .unwrap_or(self.selected_index)
+            .min(self.bookmarks.len().saturating_sub(1));
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index + 1 < self.bookmarks.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn page_up(&mut self, page: usize) {
+        self.selected_index = self.selected_index.saturating_sub(page.max(1));
+    }
+
+    pub fn page_down(&mut self, page: usize) {
+        self.selected_index = (self.selected_index + page.max(1)).min(self.bookmarks.len().saturating_sub(1));
+    }
+
+    pub fn selected(&self) -> Option<&Bookmark> {
+        self.bookmarks.get(self.selected_index)
+    }
+
+    /// 匯出成 JSON Lines，每行一筆訊息與備註
+    pub fn to_jsonl(&self) -> String {
+        self.bookmarks
+            .iter()
+            .map(|bookmark| {
+                // payload 為 JSON 時保留結構，否則輸出原始字串
+                let payload = serde_json::from_str::<serde_json::Value>(&bookmark.message.payload)
+                    .unwrap_or_else(|_| serde_json::Value::String(bookmark.message.payload.clone()));
+                let line = serde_json::json!({
+                    "id": bookmark.message.id,
+                    "topic": bookmark.message.topic,
+                    "timestamp": bookmark.message.timestamp.to_rfc3339(),
+                    "payload": payload,
+                    "note": bookmark.note,
+                    "bookmarked_at": bookmark.created_at.to_rfc3339(),
+                });
+                format!("{}\n", line)
+            })
+            .collect()
+    }
+}
+
+pub struct BookmarkListView;
+
+impl BookmarkListView {
+    /// note_input 為編輯中的備註，顯示在最下方
+    pub fn render(state: &BookmarkListState, note_input: Option<&str>, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let rows: Vec<ListRow> = state.bookmarks.iter().map(Self::format_bookmark).collect();
+        let footer = match note_input {
+            Some(note) => format!("Note: {}█ [Enter]save [Esc]cancel", note),
+            None => state.message.clone().unwrap_or_else(|| {
+                "[Enter]jump to message [n]note [Del]remove [e]export [r]reload [↑↓]navigate [Esc]back".to_string()
+            }),
+        };
+        ListPanel {
+            title: "Bookmarks",
+            header: Some(&format!(" {:<19} {:<28} {:<32} {}", "Time", "Topic", "Note", "Payload")),
+            rows: &rows,
+            selected_index: state.selected_index,
+            empty_text: "No bookmarks. Press [b] on a message to bookmark it.",
+            footer: &footer,
+        }
+        .render(terminal_width, terminal_height)
+    }
+
+    fn format_bookmark(bookmark: &Bookmark) -> ListRow {
+        let text = format!(
+            " {:<19} {:<28} {:<32} {}",
+            bookmark.message.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+            Self::truncate(&bookmark.message.topic, 28),
+            Self::truncate(&bookmark.note, 32),
+            bookmark.message.payload.replace(['\n', '\r'], " "),
+        );
+        if bookmark.note.is_empty() {
+            ListRow::new(text)
+        } else {
+            ListRow::colored(text, Color::Yellow)
+        }
+    }
+
+    fn truncate(text: &str, width: usize) -> String {
+        if text.chars().count() > width {
+            format!("{}…", text.chars().take(width - 1).collect::<String>())
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Message;
+
+    #[test]
+    fn test_export_includes_notes() {
+        let mut message = Message::new("plant/a".to_string(), r#"{"temp": 35}"#.to_string(), 0, false);
+        message.id = Some(7);
+        let text = Message::new("plant/b".to_string(), "offline".to_string(), 0, false);
+        let state = BookmarkListState {
+            bookmarks: vec![
+                Bookmark { message, note: "pump tripped".to_string(), created_at: chrono::Utc::now() },
+                Bookmark { message: text, note: String::new(), created_at: chrono::Utc::now() },
+            ],
+            ..Default::default()
+        };
+
+        let lines: Vec<serde_json::Value> = state
+            .to_jsonl()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["note"], "pump tripped");
+        assert_eq!(lines[0]["payload"]["temp"], 35);
+        assert_eq!(lines[1]["payload"], "offline");
+    }
+}
@@ -0,0 +1,436 @@
+use crossterm::{
+    cursor,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
+    QueueableCommand,
+};
+use std::io::{stdout, Write};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, Utc};
+use serde_json::Value;
+
+use crate::db::Message;
+use crate::ui::widgets::BrailleCanvas;
+use crate::utils::json_path::{child_path, json_number, JsonPath};
+
+// 縮放的最小時間範圍
+const MIN_SPAN_MS: i64 = 1000;
+// Y 軸標籤的寬度
+const LABEL_WIDTH: usize = 10;
+
+/// 圖表上的一個點，保留原始訊息以便跳回訊息列表
+#[derive(Debug, Clone)]
+pub struct ChartPoint {
+    pub time: DateTime<Utc>,
+    pub value: f64,
+    pub message: Message,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartStats {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+}
+
+/// 單一 topic 的 JSON 數值欄位對時間的圖表
+#[derive(Debug, Clone)]
+pub struct ChartState {
+    pub topic: String,
+    pub path: String,
+    pub points: Vec<ChartPoint>, // 依時間排序 (舊→新)
+    pub skipped: usize,          // 沒有數值的訊息數
+    pub view_start: DateTime<Utc>,
+    pub view_end: DateTime<Utc>,
+    pub cursor: usize,
+    pub path_input: Option<String>, // 正在輸入新的 JSON path
+    pub message: Option<String>,
+}
+
+impl ChartState {
+    pub fn new(topic: String, path: String, messages: &[Message]) -> Self {
+        let now = Utc::now();
+        let mut state = Self {
+            topic,
+            path,
+            points: Vec::new(),
+            skipped: 0,
+            view_start: now,
+            view_end: now,
+            cursor: 0,
+            path_input: None,
+            message: None,
+        };
+        state.set_messages(messages);
+        state
+    }
+
+    /// 重新擷取數值並顯示全部範圍，游標移到最新的點
+    pub fn set_messages(&mut self, messages: &[Message]) {
+        self.points.clear();
+        self.skipped = 0;
+        if let Ok(path) = JsonPath::parse(&self.path) {
+            for message in messages {
+                match path.extract(&message.payload).as_ref().and_then(json_number) {
+                    Some(value) => self.points.push(ChartPoint {
+                        time: message.timestamp,
+                        value,
+                        message: message.clone(),
+                    }),
+                    None => self.skipped += 1,
+                }
+            }
+        }
+        self.points.sort_by_key(|point| point.time);
+        self.cursor = self.points.len().saturating_sub(1);
+        self.reset_zoom();
+    }
+
+    /// 游標移到指定的訊息 (若它在圖表上)
+    pub fn select(&mut self, message: &Message) {
+        let found = self.points.iter().position(|point| match (point.message.id, message.id) {
+            (Some(a), Some(b)) => a == b,
+            _ => point.time == message.timestamp,
+        });
+        if let Some(index) = found {
+            self.cursor = index;
+        }
+    }
+
+    // 所有點的時間範圍，只有一個時間點時前後各留 30 秒
+    fn full_range(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) if first.time < last.time => (first.time, last.time),
+            (Some(point), _) => (point.time - Duration::seconds(30), point.time + Duration::seconds(30)),
+            _ => (self.view_start, self.view_end),
+        }
+    }
+
+    fn span_ms(&self) -> i64 {
+        (self.view_end - self.view_start).num_milliseconds().max(1)
+    }
+
+    pub fn reset_zoom(&mut self) {
+        (self.view_start, self.view_end) = self.full_range();
+    }
+
+    /// factor < 1 放大、> 1 縮小，以游標 (不在畫面內時以中央) 為中心
+    pub fn zoom(&mut self, factor: f64) {
+        let (full_start, full_end) = self.full_range();
+        let full_span = (full_end - full_start).num_milliseconds().max(1);
+        let span = self.span_ms();
+        let new_span = ((span as f64 * factor) as i64).clamp(MIN_SPAN_MS.min(full_span), full_span);
+
+        let center = match self.cursor_point() {
+            Some(point) if self.is_visible(point.time) => point.time,
+            _ => self.view_start + Duration::milliseconds(span / 2),
+        };
+        let ratio = (center - self.view_start).num_milliseconds() as f64 / span as f64;
+        self.view_start = center - Duration::milliseconds((new_span as f64 * ratio) as i64);
+        self.view_end = self.view_start + Duration::milliseconds(new_span);
+        self.clamp_view();
+    }
+
+    /// 時間軸平移畫面寬度的 fraction 倍 (負值往左)，游標跟著留在畫面內
+    pub fn pan(&mut self, fraction: f64) {
+        let shift = Duration::milliseconds((self.span_ms() as f64 * fraction) as i64);
+        self.view_start += shift;
+        self.view_end += shift;
+        self.clamp_view();
+
+        let visible = self.visible_range();
+        if !visible.is_empty() && !visible.contains(&self.cursor) {
+            self.cursor = self.cursor.clamp(visible.start, visible.end - 1);
+        }
+    }
+
+    // 畫面範圍不超出資料的時間範圍
+    fn clamp_view(&mut self) {
+        let (full_start, full_end) = self.full_range();
+        let span = self.view_end - self.view_start;
+        if span >= full_end - full_start {
+            (self.view_start, self.view_end) = (full_start, full_end);
+        } else if self.view_start < full_start {
+            (self.view_start, self.view_end) = (full_start, full_start + span);
+        } else if self.view_end > full_end {
+            (self.view_start, self.view_end) = (full_end - span, full_end);
+        }
+    }
+
+    /// 移動游標，超出畫面時平移讓它回到畫面中央
+    pub fn move_cursor(&mut self, offset: isize) {
+        if self.points.is_empty() {
+            return;
+        }
+        self.cursor = self.cursor.saturating_add_signed(offset).min(self.points.len() - 1);
+        let time = self.points[self.cursor].time;
+        if !self.is_visible(time) {
+            let half = Duration::milliseconds(self.span_ms() / 2);
+            let span = self.view_end - self.view_start;
+            self.view_start = time - half;
+            self.view_end = self.view_start + span;
+            self.clamp_view();
+        }
+    }
+
+    pub fn move_cursor_to(&mut self, index: usize) {
+        let offset = index as isize - self.cursor as isize;
+        self.move_cursor(offset);
+    }
+
+    fn is_visible(&self, time: DateTime<Utc>) -> bool {
+        time >= self.view_start && time <= self.view_end
+    }
+
+    /// 畫面時間範圍內的點 (points 的索引範圍)
+    pub fn visible_range(&self) -> std::ops::Range<usize> {
+        let start = self.points.partition_point(|point| point.time < self.view_start);
+        let end = self.points.partition_point(|point| point.time <= self.view_end);
+        start..end.max(start)
+    }
+
+    pub fn cursor_point(&self) -> Option<&ChartPoint> {
+        self.points.get(self.cursor)
+    }
+
+    /// 畫面範圍內的最小、最大與平均值
+    pub fn stats(&self) -> Option<ChartStats> {
+        let points = &self.points[self.visible_range()];
+        if points.is_empty() {
+            return None;
+        }
+        let (min, max, sum) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY, 0.0), |(min, max, sum), point| {
+            (min.min(point.value), max.max(point.value), sum + point.value)
+        });
+        Some(ChartStats {
+            count: points.len(),
+            min,
+            max,
+            avg: sum / points.len() as f64,
+        })
+    }
+}
+
+/// payload 中第一個數值欄位的 path，用來當作圖表的預設欄位
+pub fn first_numeric_path(payload: &str) -> Option<String> {
+    fn find(path: String, value: &Value) -> Option<String> {
+        match value {
+            Value::Number(_) => Some(path),
+            Value::Object(map) => map.iter().find_map(|(key, value)| find(child_path(&path, key), value)),
+            Value::Array(items) => items.iter().enumerate().find_map(|(index, value)| find(format!("{}[{}]", path, index), value)),
+            _ => None,
+        }
+    }
+    let value: Value = serde_json::from_str(payload).ok()?;
+    // 整個 payload 就是一個數字時沒有可用的 path
+    if value.is_number() {
+        return None;
+    }
+    find("$".to_string(), &value)
+}
+
+fn format_value(value: f64) -> String {
+    let abs = value.abs();
+    if abs != 0.0 && !(0.001..1_000_000.0).contains(&abs) {
+        return format!("{:.3e}", value);
+    }
+    let text = format!("{:.3}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn format_time(time: DateTime<Utc>, span: Duration) -> String {
+    let local = time.with_timezone(&Local);
+    if span > Duration::days(1) {
+        local.format("%m-%d %H:%M").to_string()
+    } else if span > Duration::minutes(1) {
+        local.format("%H:%M:%S").to_string()
+    } else {
+        local.format("%H:%M:%S%.3f").to_string()
+    }
+}
+
+fn format_span(span: Duration) -> String {
+    let seconds = span.num_seconds();
+    match seconds {
+        s if s >= 86400 => format!("{}d {}h", s / 86400, s % 86400 / 3600),
+        s if s >= 3600 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s if s >= 60 => format!("{}m {}s", s / 60, s % 60),
+        _ => format!("{:.1}s", span.num_milliseconds() as f64 / 1000.0),
+    }
+}
+
+pub struct ChartView;
+
+impl ChartView {
+    pub fn render(state: &ChartState, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let mut stdout = stdout();
+        let width = terminal_width.max(40) as usize;
+        let plot_width = width - LABEL_WIDTH - 1;
+        // 標題、統計、X 軸、時間、游標與底部說明以外都給圖表
+        let plot_height = (terminal_height as usize).saturating_sub(6).max(3);
+        let mut row: u16 = 0;
+
+        let line = |stdout: &mut std::io::Stdout, row: &mut u16| -> Result<()> {
+            stdout.queue(cursor::MoveTo(0, *row))?;
+            stdout.queue(Clear(ClearType::CurrentLine))?;
+            *row += 1;
+            Ok(())
+        };
+
+        // 標題列
+        line(&mut stdout, &mut row)?;
+        let title = format!("── Chart: {}  {} ", state.topic, state.path);
+        let title_len = title.chars().count();
+        stdout.queue(Print(&title))?;
+        stdout.queue(Print(&"─".repeat(width.saturating_sub(title_len))))?;
+
+        // 統計
+        line(&mut stdout, &mut row)?;
+        let span = state.view_end - state.view_start;
+        let stats = state.stats();
+        let summary = match &stats {
+            Some(stats) => format!(
+                " min {}  max {}  avg {}  n={}",
+                format_value(stats.min), format_value(stats.max), format_value(stats.avg), stats.count
+            ),
+            None => " no numeric values".to_string(),
+        };
+        let skipped = if state.skipped > 0 { format!("  (skipped {} non-numeric)", state.skipped) } else { String::new() };
+        stdout.queue(Print(format!("{}{}  window {}", summary, skipped, format_span(span))))?;
+
+        let mut canvas = BrailleCanvas::new(plot_width, plot_height);
+        let (low, high) = match &stats {
+            Some(stats) if stats.max - stats.min > f64::EPSILON => (stats.min, stats.max),
+            Some(stats) => {
+                let pad = (stats.min.abs() * 0.1).max(1.0);
+                (stats.min - pad, stats.max + pad)
+            }
+            None => (0.0, 1.0),
+        };
+        let span_ms = span.num_milliseconds().max(1) as f64;
+        let (dot_width, dot_height) = (canvas.dot_width(), canvas.dot_height());
+        let to_dot = |point: &ChartPoint| -> (i64, i64) {
+            let x = (point.time - state.view_start).num_milliseconds() as f64 / span_ms * (dot_width - 1) as f64;
+            let y = (high - point.value) / (high - low) * (dot_height - 1) as f64;
+            (x.round() as i64, y.round() as i64)
+        };
+        let dots: Vec<(i64, i64)> = state.points[state.visible_range()].iter().map(to_dot).collect();
+        match dots.as_slice() {
+            [single] => canvas.set(single.0, single.1),
+            _ => {
+                for pair in dots.windows(2) {
+                    canvas.line(pair[0], pair[1]);
+                }
+            }
+        }
+        let cursor_col = state
+            .cursor_point()
+            .filter(|point| point.time >= state.view_start && point.time <= state.view_end)
+            .map(|point| (to_dot(point).0 / 2) as usize);
+
+        // 圖表區，Y 軸標籤在最上、中間與最下
+        for plot_row in 0..plot_height {
+            line(&mut stdout, &mut row)?;
+            let label = if plot_row == 0 {
+                format_value(high)
+            } else if plot_row == plot_height - 1 {
+                format_value(low)
+            } else if plot_row == plot_height / 2 {
+                format_value((high + low) / 2.0)
+            } else {
+                String::new()
+            };
+            stdout.queue(Print(format!("{:>width$}┤", label, width = LABEL_WIDTH)))?;
+
+            let cells = canvas.row(plot_row);
+            match cursor_col {
+                Some(col) if col < cells.len() => {
+                    stdout.queue(SetForegroundColor(Color::Cyan))?;
+                    stdout.queue(Print(cells[..col].iter().collect::<String>()))?;
+                    stdout.queue(SetForegroundColor(Color::Yellow))?;
+                    let cell = if canvas.is_blank(col, plot_row) { '│' } else { cells[col] };
+                    stdout.queue(Print(cell))?;
+                    stdout.queue(SetForegroundColor(Color::Cyan))?;
+                    stdout.queue(Print(cells[col + 1..].iter().collect::<String>()))?;
+                }
+                _ => {
+                    stdout.queue(SetForegroundColor(Color::Cyan))?;
+                    stdout.queue(Print(cells.iter().collect::<String>()))?;
+                }
+            }
+            stdout.queue(ResetColor)?;
+        }
+
+        // X 軸與起訖時間
+        line(&mut stdout, &mut row)?;
+        stdout.queue(Print(format!("{}└{}", " ".repeat(LABEL_WIDTH), "─".repeat(plot_width))))?;
+        line(&mut stdout, &mut row)?;
+        let start_label = format_time(state.view_start, span);
+        let end_label = format_time(state.view_end, span);
+        let gap = plot_width.saturating_sub(start_label.chars().count() + end_label.chars().count());
+        stdout.queue(Print(format!("{}{}{}{}", " ".repeat(LABEL_WIDTH + 1), start_label, " ".repeat(gap), end_label)))?;
+
+        // 游標所在的點
+        line(&mut stdout, &mut row)?;
+        if let Some(point) = state.cursor_point() {
+            stdout.queue(SetForegroundColor(Color::Yellow))?;
+            stdout.queue(Print(format!(
+                " ▶ {}  {}  ({}/{})",
+                point.time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S%.3f"),
+                format_value(point.value),
+                state.cursor + 1,
+                state.points.len()
+            )))?;
+            stdout.queue(ResetColor)?;
+        }
+
+        line(&mut stdout, &mut row)?;
+        let footer = match (&state.path_input, &state.message) {
+            (Some(text), _) => format!("JSON path: {}█  [Enter]plot [Esc]cancel", text),
+            (None, Some(message)) => message.clone(),
+            (None, None) => "[←→]cursor [+/-]zoom [[/]]pan [0]reset [f]field [r]reload [Enter]go to message [Esc]back".to_string(),
+        };
+        stdout.queue(Print(footer))?;
+
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(seconds: i64, payload: &str) -> Message {
+        let mut message = Message::new("plant/a".to_string(), payload.to_string(), 0, false);
+        message.timestamp = DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap();
+        message
+    }
+
+    #[test]
+    fn test_chart_zoom_and_pan() {
+        let messages: Vec<Message> = (0..100)
+            .map(|i| message(i, &format!(r#"{{"t": {}}}"#, i)))
+            .chain(std::iter::once(message(100, "offline")))
+            .collect();
+        let mut chart = ChartState::new("plant/a".to_string(), "$.t".to_string(), &messages);
+        assert_eq!((chart.points.len(), chart.skipped, chart.cursor), (100, 1, 99));
+        assert_eq!(chart.stats().unwrap(), ChartStats { count: 100, min: 0.0, max: 99.0, avg: 49.5 });
+
+        // 以最新的點為中心放大，畫面停在資料的右端
+        chart.zoom(0.1);
+        assert_eq!(chart.view_end, chart.points[99].time);
+        assert_eq!(chart.stats().unwrap().min, 90.0);
+
+        chart.pan(-1.0);
+        assert!(chart.visible_range().contains(&chart.cursor));
+        chart.move_cursor_to(0);
+        assert_eq!(chart.view_start, chart.points[0].time);
+        chart.reset_zoom();
+        assert_eq!(chart.visible_range(), 0..100);
+
+        assert_eq!(first_numeric_path(r#"{"name": "a", "sensor": {"temp c": 21.5}}"#).as_deref(), Some("$.sensor[\"temp c\"]"));
+        assert_eq!(first_numeric_path("42"), None);
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::Result;
+use crossterm::style::Color;
+
+use crate::ui::columns::{ColumnSet, PayloadColumn};
+use crate::ui::widgets::{ListPanel, ListRow};
+
+/// 編輯中的文字欄位
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnInput {
+    Column(String),  // 新增欄位：path [標題]
+    Pattern(String), // 套用的 topic pattern
+}
+
+/// 編輯目前 topic 的訊息清單欄位
+#[derive(Debug, Clone)]
+pub struct ColumnEditorState {
+    pub set: ColumnSet,
+    pub original_topic: Option<String>, // 已存在資料庫的 pattern，改 pattern 時需刪除舊的
+    pub selected_index: usize,          // 0 為「新增欄位」
+    pub input: Option<ColumnInput>,
+    pub message: Option<String>,
+}
+
+impl ColumnEditorState {
+    /// 編輯已套用的欄位組合；沒有時以目前 topic 為 pattern 建立新的
+    pub fn new(current: Option<&ColumnSet>, topic: &str) -> Self {
+        let set = current.cloned().unwrap_or_else(|| ColumnSet {
+            topic: topic.to_string(),
+            columns: Vec::new(),
+            from_config: false,
+        });
+        let original_topic = current.filter(|set| !set.from_config).map(|set| set.topic.clone());
+        let selected_index = 1.min(set.columns.len());
+        Self {
+            set,
+            original_topic,
+            selected_index,
+            input: None,
+            message: None,
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.set.columns.len() + 1
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index + 1 < self.row_count() {
+            self.selected_index += 1;
+        }
+    }
+
+    /// 選取的欄位在 columns 中的位置，選在新增選項上時為 None
+    pub fn selected_column(&self) -> Option<usize> {
+        self.selected_index.checked_sub(1).filter(|&index| index < self.set.columns.len())
+    }
+
+    pub fn add_column(&mut self, column: PayloadColumn) {
+        // 加在選取的欄位後面
+        let index = self.selected_column().map_or(self.set.columns.len(), |index| index + 1);
+        self.set.columns.insert(index, column);
+        self.selected_index = index + 1;
+    }
+
+    pub fn remove_selected(&mut self) -> Option<PayloadColumn> {
+        let index = self.selected_column()?;
+        let column = self.set.columns.remove(index);
+        self.selected_index = self.selected_index.min(self.row_count() - 1);
+        Some(column)
+    }
+
+    /// 將選取的欄位往前 (-1) 或往後 (+1) 移
+    pub fn move_selected(&mut self, offset: isize) -> bool {
+        let Some(index) = self.selected_column() else {
+            return false;
+        };
+        let target = index as isize + offset;
+        if target < 0 || target as usize >= self.set.columns.len() {
+            return false;
+        }
+        self.set.columns.swap(index, target as usize);
+        self.selected_index = target as usize + 1;
+        true
+    }
+}
+
+pub struct ColumnEditorView;
+
+impl ColumnEditorView {
+    pub fn render(state: &ColumnEditorState, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let mut rows = vec![ListRow::colored(" + Add column...", Color::Green)];
+        rows.extend(state.set.columns.iter().map(|column| {
+            let width = column.width.map_or("auto".to_string(), |w| w.to_string());
+            ListRow::new(format!(" {:<28} {:<16} {}", column.path, column.title(), width))
+        }));
+
+        let footer = match &state.input {
+            Some(ColumnInput::Column(text)) => format!("Column (path [title]): {}█  [Enter]add [Esc]cancel", text),
+            Some(ColumnInput::Pattern(text)) => format!("Topic pattern (+ and # wildcards): {}█  [Enter]save [Esc]cancel", text),
+            None => match &state.message {
+                Some(message) => message.clone(),
+                None => "[Enter/a]add [Del]remove [-/+]move [p]pattern [r]reset [Esc]close".to_string(),
+            },
+        };
+
+        let source = if state.set.from_config { " (config)" } else { "" };
+        let title = format!("Message Columns: {}{}", state.set.topic, source);
+        ListPanel {
+            title: &title,
+            header: Some(&format!(" {:<28} {:<16} {}", "JSON path", "Title", "Width")),
+            rows: &rows,
+            selected_index: state.selected_index,
+            empty_text: "",
+            footer: &footer,
+        }
+        .render(terminal_width, terminal_height)
+    }
+}
@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use crossterm::style::Color;
+
+use crate::ui::widgets::{ListPanel, ListRow};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileEntryKind {
+    Live,       // 回到即時資料庫
+    Parent,
+    Directory,
+    Database,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub kind: FileEntryKind,
+    pub size: u64,
+    pub modified: Option<chrono::DateTime<chrono::Local>>,
+}
+
+/// 選擇要以唯讀方式開啟的資料庫檔案
+#[derive(Debug, Clone)]
+pub struct FilePickerState {
+    pub current_dir: PathBuf,
+    pub entries: Vec<FileEntry>,
+    pub selected_index: usize,
+    pub show_live_entry: bool,
+    pub error: Option<String>,
+}
+
+fn is_database_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref(),
+        Some("db") | Some("sqlite") | Some("sqlite3")
+    )
+}
+
+impl FilePickerState {
+    pub fn new(dir: &Path, show_live_entry: bool) -> Self {
+        let current_dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let mut state = Self {
+            current_dir,
+            entries: Vec::new(),
+            selected_index: 0,
+            show_live_entry,
+            error: None,
+        };
+        state.reload();
+        state
+    }
+
+    pub fn reload(&mut self) {
+        self.entries.clear();
+        self.selected_index = 0;
+        self.error = None;
+
+        if self.show_live_entry {
+            self.entries.push(FileEntry {
+                path: PathBuf::new(),
+                kind: FileEntryKind::Live,
+                size: 0,
+                modified: None,
+            });
+        }
+
+        if let Some(parent) = self.current_dir.parent() {
+            self.entries.push(FileEntry {
+                path: parent.to_path_buf(),
+                kind: FileEntryKind::Parent,
+                size: 0,
+                modified: None,
+            });
+        }
+
+        match self.read_dir() {
+            Ok(mut entries) => self.entries.append(&mut entries),
+            Err(e) => self.error = Some(format!("無法讀取目錄: {}", e)),
+        }
+    }
+
+    fn read_dir(&self) -> Result<Vec<FileEntry>> {
+        let mut directories = Vec::new();
+        let mut databases = Vec::new();
+
+        for entry in std::fs::read_dir(&self.current_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            let modified = metadata.modified().ok().map(chrono::DateTime::<chrono::Local>::from);
+
+            if metadata.is_dir() {
+                directories.push(FileEntry { path, kind: FileEntryKind::Directory, size: 0, modified });
+            } else if is_database_file(&path) {
+                databases.push(FileEntry { path, kind: FileEntryKind::Database, size: metadata.len(), modified });
+            }
+        }
+
+        directories.sort_by(|a, b| a.path.cmp(&b.path));
+        // 最新的快照排前面
+        databases.sort_by_key(|d| std::cmp::Reverse(d.modified));
+        directories.append(&mut databases);
+        Ok(directories)
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index + 1 < self.entries.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn page_up(&mut self, page: usize) {
+        self.selected_index = self.selected_index.saturating_sub(page.max(1));
+    }
+
+    pub fn page_down(&mut self, page: usize) {
+        self.selected_index = (self.selected_index + page.max(1)).min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn selected(&self) -> Option<&FileEntry> {
+        self.entries.get(self.selected_index)
+    }
+
+    pub fn enter_directory(&mut self, dir: PathBuf) {
+        self.current_dir = dir;
+        self.reload();
+    }
+}
+
+pub struct FilePickerView;
+
+impl FilePickerView {
+    pub fn render(state: &FilePickerState, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let rows: Vec<ListRow> = state.entries.iter().map(Self::format_entry).collect();
+        let title = format!("Open Database (read-only) - {}", state.current_dir.display());
+        let footer = match &state.error {
+            Some(error) => error.clone(),
+            None => "[Enter]open [↑↓]navigate [PgUp/PgDn]page [Esc]cancel".to_string(),
+        };
+
+        ListPanel {
+            title: &title,
+            header: Some(&format!(" {:<50} {:>10}  {:<19}", "Name", "Size", "Modified")),
+            rows: &rows,
+            selected_index: state.selected_index,
+            empty_text: " No database files in this directory",
+            footer: &footer,
+        }
+        .render(terminal_width, terminal_height)
+    }
+
+    fn format_entry(entry: &FileEntry) -> ListRow {
+        let name = entry.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let modified = entry
+            .modified
+            .map(|m| m.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+
+        match entry.kind {
+            FileEntryKind::Live => ListRow::colored(" ● Live database", Color::Green),
+            FileEntryKind::Parent => ListRow::new(" ../"),
+            FileEntryKind::Directory => ListRow::colored(
+                format!(" {:<50} {:>10}  {:<19}", format!("{}/", name), "<DIR>", modified),
+                Color::Cyan,
+            ),
+            FileEntryKind::Database => ListRow::new(format!(
+                " {:<50} {:>10}  {:<19}",
+                name,
+                Self::format_size(entry.size),
+                modified
+            )),
+        }
+    }
+
+    fn format_size(size: u64) -> String {
+        match size {
+            s if s >= 1024 * 1024 * 1024 => format!("{:.1} GB", s as f64 / (1024.0 * 1024.0 * 1024.0)),
+            s if s >= 1024 * 1024 => format!("{:.1} MB", s as f64 / (1024.0 * 1024.0)),
+            s if s >= 1024 => format!("{:.1} KB", s as f64 / 1024.0),
+            s => format!("{} B", s),
+        }
+    }
+}
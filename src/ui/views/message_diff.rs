@@ -0,0 +1,161 @@
+use anyhow::Result;
+use crossterm::style::Color;
+use serde_json::Value;
+
+use crate::db::Message;
+use crate::ui::widgets::{ListPanel, ListRow};
+use crate::utils::diff::{JsonChange, LineChange, PayloadDiff};
+
+/// 兩筆訊息 payload 的差異 (舊 → 新)
+#[derive(Debug, Clone)]
+pub struct MessageDiffState {
+    pub old: Option<Message>, // 沒有可比較的訊息時為 None
+    pub new: Message,
+    pub rows: Vec<ListRow>,
+    pub summary: String,
+    pub selected_index: usize,
+}
+
+impl MessageDiffState {
+    /// 依時間排列，較早的訊息為舊版本
+    pub fn new(a: Message, b: Option<Message>) -> Self {
+        let (old, new) = match b {
+            Some(b) if b.timestamp > a.timestamp => (Some(a), b),
+            Some(b) => (Some(b), a),
+            None => (None, a),
+        };
+        let mut state = Self {
+            old,
+            new,
+            rows: Vec::new(),
+            summary: String::new(),
+            selected_index: 0,
+        };
+        state.compute();
+        state
+    }
+
+    /// 交換比較方向 (新 → 舊)
+    pub fn swap(&mut self) {
+        if let Some(old) = self.old.take() {
+            self.old = Some(std::mem::replace(&mut self.new, old));
+            self.compute();
+        }
+    }
+
+    fn compute(&mut self) {
+        self.selected_index = 0;
+        let Some(old) = &self.old else {
+            self.rows.clear();
+            self.summary.clear();
+            return;
+        };
+
+        let diff = PayloadDiff::compute(&old.payload, &self.new.payload);
+        let (mut added, mut removed, mut changed) = (0, 0, 0);
+        self.rows = match &diff {
+            PayloadDiff::Json(changes) => changes
+                .iter()
+                .map(|change| match change {
+                    JsonChange::Added { path, value } => {
+                        added += 1;
+                        ListRow::colored(format!(" + {}: {}", path, compact(value)), Color::Green)
+                    }
+                    JsonChange::Removed { path, value } => {
+                        removed += 1;
+                        ListRow::colored(format!(" - {}: {}", path, compact(value)), Color::Red)
+                    }
+                    JsonChange::Changed { path, old, new } => {
+                        changed += 1;
+                        ListRow::colored(format!(" ~ {}: {} → {}", path, compact(old), compact(new)), Color::Yellow)
+                    }
+                })
+                .collect(),
+            PayloadDiff::Text(lines) => lines
+                .iter()
+                .map(|line| match line {
+                    LineChange::Same(text) => ListRow::new(format!("   {}", text)),
+                    LineChange::Added(text) => {
+                        added += 1;
+                        ListRow::colored(format!(" + {}", text), Color::Green)
+                    }
+                    LineChange::Removed(text) => {
+                        removed += 1;
+                        ListRow::colored(format!(" - {}", text), Color::Red)
+                    }
+                })
+                .collect(),
+        };
+
+        let kind = if matches!(diff, PayloadDiff::Json(_)) { "JSON" } else { "text" };
+        self.summary = if diff.is_empty() {
+            format!("{}: identical", kind)
+        } else if kind == "JSON" {
+            format!("{}: +{} -{} ~{}", kind, added, removed, changed)
+        } else {
+            format!("{}: +{} -{} lines", kind, added, removed)
+        };
+        // 文字差異從第一個變更的行開始顯示
+        self.selected_index = self.rows.iter().position(|row| row.color.is_some()).unwrap_or(0);
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index + 1 < self.rows.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn page_up(&mut self, page: usize) {
+        self.selected_index = self.selected_index.saturating_sub(page.max(1));
+    }
+
+    pub fn page_down(&mut self, page: usize) {
+        self.selected_index = (self.selected_index + page.max(1)).min(self.rows.len().saturating_sub(1));
+    }
+}
+
+fn compact(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn describe(message: &Message) -> String {
+    let time = message.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S%.3f");
+    match message.id {
+        Some(id) => format!("{} #{}", time, id),
+        None => time.to_string(),
+    }
+}
+
+pub struct MessageDiffView;
+
+impl MessageDiffView {
+    pub fn render(state: &MessageDiffState, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let header = match &state.old {
+            Some(old) if old.topic != state.new.topic => format!(
+                " Old: {} {}  →  New: {} {}   {}",
+                old.topic, describe(old), state.new.topic, describe(&state.new), state.summary
+            ),
+            Some(old) => format!(" Old: {}  →  New: {}   {}", describe(old), describe(&state.new), state.summary),
+            None => format!(" New: {}", describe(&state.new)),
+        };
+        let empty_text = if state.old.is_some() {
+            " Payloads are identical"
+        } else {
+            " No earlier message on this topic to compare with (press [m] on a message to mark it)"
+        };
+
+        ListPanel {
+            title: &format!("Message Diff: {}", state.new.topic),
+            header: Some(&header),
+            rows: &state.rows,
+            selected_index: state.selected_index,
+            empty_text,
+            footer: "[↑↓]scroll [PgUp/PgDn]page [s]swap [←/Esc]back",
+        }
+        .render(terminal_width, terminal_height)
+    }
+}
@@ -1,6 +1,8 @@
 use crate::db::models::{Message, FilterCriteria};
 use crate::db::repository::MessageRepository;
-use chrono::{DateTime, Utc, Local, NaiveDateTime};
+use crate::filter::{resolve_time_input, Query, QuickFilterSet, TimeExpr, DEFAULT_TIME_FROM, DEFAULT_TIME_TO};
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum FocusTarget {
@@ -40,16 +42,14 @@ pub struct MessageListState {
     pub time_edit_mode: bool,  // 是否處於時間編輯模式
     pub time_edit_position: TimeEditPosition,  // 當前編輯的時間部分
     pub temp_datetime: Option<DateTime<Local>>,  // 暫存的時間值
-    pub quick_filter_states: Vec<bool>,  // F1-F5快速過濾器的開關狀態
+    pub quick_filters: QuickFilterSet,
+    pub quick_filter_states: Vec<bool>,  // 各快速過濾器的開關狀態，與 quick_filters 同順序
+    pub marked_message: Option<Message>,  // 按 m 標記、用來比較差異的訊息 (可跨 topic)
+    pub bookmarks: HashMap<i64, String>,  // 本頁有書籤的訊息 id 與備註
 }
 
 impl MessageListState {
     pub fn new() -> Self {
-        use chrono::{Duration, Local};
-        let now = Local::now();
-        let yesterday = now - Duration::days(1);
-        let tomorrow = now + Duration::days(1);
-        
         Self {
             messages: Vec::new(),
             selected_index: 0,
@@ -61,8 +61,8 @@ impl MessageListState {
             per_page: 10, // Default value, will be updated based on terminal size
             focus: FocusTarget::MessageList,
             payload_filter_input: String::new(),
-            time_from_input: yesterday.format("%Y-%m-%d %H:%M:%S").to_string(),
-            time_to_input: tomorrow.format("%Y-%m-%d %H:%M:%S").to_string(),
+            time_from_input: DEFAULT_TIME_FROM.to_string(),
+            time_to_input: DEFAULT_TIME_TO.to_string(),
             is_editing: false,
             filter_error: None,
             cursor_position: 0,
@@ -70,7 +70,10 @@ impl MessageListState {
             time_edit_mode: false,
             time_edit_position: TimeEditPosition::Day,
             temp_datetime: None,
-            quick_filter_states: vec![true; 5],  // 預設全部開啟（F1-F5）
+            quick_filters: QuickFilterSet::default(),
+            quick_filter_states: Vec::new(),
+            marked_message: None,
+            bookmarks: HashMap::new(),
         }
     }
     
@@ -107,58 +110,56 @@ impl MessageListState {
     }
     
     pub fn update_filter_from_inputs(&mut self) {
-        // 更新 payload 過濾
-        if !self.payload_filter_input.is_empty() {
+        // 更新 payload 過濾；查詢語法 (topic:~... AND ...) 解析成 query，否則視為 regex
+        // 查詢無法解析時不套用，錯誤由 apply_message_list_filters 顯示
+        self.filter.payload_regex = None;
+        self.filter.query = None;
+        if Query::looks_like_query(&self.payload_filter_input) {
+            self.filter.query = Query::parse(&self.payload_filter_input).ok();
+        } else if !self.payload_filter_input.is_empty() {
             self.filter.payload_regex = Some(self.payload_filter_input.clone());
-        } else {
-            self.filter.payload_regex = None;
         }
         
-        // 更新時間過濾 - From
-        if !self.time_from_input.is_empty() {
-            if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(&self.time_from_input, "%Y-%m-%d %H:%M:%S") {
-                use chrono::{TimeZone, Local};
-                if let Some(dt) = Local.from_local_datetime(&naive_dt).single() {
-                    self.filter.start_time = Some(dt.with_timezone(&chrono::Utc));
-                }
-            }
-        } else {
-            self.filter.start_time = None;
+        // 更新時間過濾；每次載入都重新計算，相對時間 (-15m、today...) 會隨時間滑動
+        // 無法解析時保留原本的條件，錯誤由 apply_message_list_filters 顯示
+        let now = Local::now();
+        if let Ok(start_time) = resolve_time_input(&self.time_from_input, now) {
+            self.filter.start_time = start_time;
         }
-        
-        // 更新時間過濾 - To
-        if !self.time_to_input.is_empty() {
-            if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(&self.time_to_input, "%Y-%m-%d %H:%M:%S") {
-                use chrono::{TimeZone, Local};
-                if let Some(dt) = Local.from_local_datetime(&naive_dt).single() {
-                    self.filter.end_time = Some(dt.with_timezone(&chrono::Utc));
-                }
-            }
-        } else {
-            self.filter.end_time = None;
+        if let Ok(end_time) = resolve_time_input(&self.time_to_input, now) {
+            self.filter.end_time = end_time;
         }
     }
     
+    /// 目前 topic 符合所有過濾條件的訊息 (新→舊，不分頁)
+    pub async fn fetch_filtered_messages(&mut self, repo: &MessageRepository) -> anyhow::Result<Vec<Message>> {
+        let Some(topic) = self.current_topic.clone() else {
+            return Ok(Vec::new());
+        };
+        // 更新過濾條件，包含時間過濾
+        self.update_filter_from_inputs();
+        
+        // 先取出所有符合時間和payload過濾條件的訊息（不設limit/offset）
+        let base_filter = FilterCriteria {
+            topic_regex: self.filter.topic_regex.clone(),
+            payload_regex: self.filter.payload_regex.clone(),
+            start_time: self.filter.start_time.clone(),
+            end_time: self.filter.end_time.clone(),
+            query: self.filter.query.clone(),
+            limit: None,
+            offset: None,
+        };
+        
+        tracing::info!("load_messages base_filter: {:?}", base_filter);
+        let all_messages = repo.get_messages_by_topic(&topic, &base_filter).await?;
+        
+        // 應用快速過濾器過濾
+        Ok(self.apply_quick_filters(all_messages))
+    }
+    
     pub async fn load_messages(&mut self, repo: &MessageRepository) -> anyhow::Result<()> {
-        if let Some(topic) = self.current_topic.clone() {
-            // 更新過濾條件，包含時間過濾
-            self.update_filter_from_inputs();
-            
-            // 先取出所有符合時間和payload過濾條件的訊息（不設limit/offset）
-            let base_filter = FilterCriteria {
-                topic_regex: self.filter.topic_regex.clone(),
-                payload_regex: self.filter.payload_regex.clone(),
-                start_time: self.filter.start_time.clone(),
-                end_time: self.filter.end_time.clone(),
-                limit: None,
-                offset: None,
-            };
-            
-            tracing::info!("load_messages base_filter: {:?}", base_filter);
-            let all_messages = repo.get_messages_by_topic(&topic, &base_filter).await?;
-            
-            // 應用快速過濾器過濾
-            let filtered_messages = self.apply_quick_filters(all_messages);
+        if self.current_topic.is_some() {
+            let filtered_messages = self.fetch_filtered_messages(repo).await?;
             self.total_count = filtered_messages.len();
             
             // 計算當前頁的訊息（確保頁數至少為1）
@@ -192,10 +193,17 @@ impl MessageListState {
             if self.selected_index >= self.messages.len() && !self.messages.is_empty() {
                 self.selected_index = self.messages.len() - 1;
             }
+            self.reload_bookmarks(repo).await;
         }
         Ok(())
     }
     
+    /// 重新讀取本頁訊息的書籤；舊的資料庫檔案沒有 bookmarks 資料表時視為沒有書籤
+    pub async fn reload_bookmarks(&mut self, repo: &MessageRepository) {
+        let ids: Vec<i64> = self.messages.iter().filter_map(|m| m.id).collect();
+        self.bookmarks = repo.get_bookmark_notes(&ids).await.unwrap_or_default();
+    }
+    
     pub fn move_up(&mut self) {
         if self.selected_index > 0 {
             self.selected_index -= 1;
@@ -313,10 +321,40 @@ impl MessageListState {
         Ok(())
     }
     
+    /// 切換到訊息所在的頁並選取它，不在目前的過濾結果中時回傳 false
+    pub async fn select_message(&mut self, repo: &MessageRepository, message: &Message) -> anyhow::Result<bool> {
+        let messages = self.fetch_filtered_messages(repo).await?;
+        let Some(index) = messages.iter().position(|m| same_message(m, message)) else {
+            return Ok(false);
+        };
+        self.focus = FocusTarget::MessageList;
+        self.page = index / self.per_page.max(1) + 1;
+        self.load_messages(repo).await?;
+        self.selected_index = (index % self.per_page.max(1)).min(self.messages.len().saturating_sub(1));
+        Ok(true)
+    }
+    
     pub fn get_selected_message(&self) -> Option<&Message> {
         self.messages.get(self.selected_index)
     }
     
+    /// 標記或取消標記選取的訊息
+    pub fn toggle_mark(&mut self) {
+        let Some(selected) = self.get_selected_message() else {
+            return;
+        };
+        self.marked_message = if self.is_marked(selected) { None } else { Some(selected.clone()) };
+    }
+    
+    pub fn is_marked(&self, message: &Message) -> bool {
+        self.marked_message.as_ref().is_some_and(|marked| same_message(marked, message))
+    }
+    
+    /// 有書籤時回傳備註 (可能為空字串)
+    pub fn bookmark_note(&self, message: &Message) -> Option<&str> {
+        self.bookmarks.get(&message.id?).map(String::as_str)
+    }
+    
     pub fn next_focus(&mut self) {
         self.focus = match self.focus {
             FocusTarget::PayloadFilter => FocusTarget::TimeFilterFrom,
@@ -505,12 +543,9 @@ impl MessageListState {
                     _ => {}
                 }
             } else {
-                // 解析現有時間
-                if let Ok(naive) = NaiveDateTime::parse_from_str(current_value, "%Y-%m-%d %H:%M:%S") {
-                    use chrono::TimeZone;
-                    if let Some(local_dt) = Local.from_local_datetime(&naive).single() {
-                        self.temp_datetime = Some(local_dt);
-                    }
+                // 解析現有時間；相對時間換算成目前對應的時間再調整
+                if let Ok(expr) = TimeExpr::parse(current_value) {
+                    self.temp_datetime = Some(expr.resolve(Local::now()));
                 }
             }
             
@@ -604,6 +639,23 @@ impl MessageListState {
     }
     
     // 快速過濾器相關方法
+    /// 換上新的過濾器設定；同名過濾器保留目前的開關狀態，其餘使用設定檔的 enabled
+    pub fn set_quick_filters(&mut self, quick_filters: QuickFilterSet) {
+        self.quick_filter_states = quick_filters
+            .filters
+            .iter()
+            .map(|filter| {
+                self.quick_filters
+                    .filters
+                    .iter()
+                    .position(|old| old.name == filter.name)
+                    .and_then(|index| self.quick_filter_states.get(index).copied())
+                    .unwrap_or(filter.enabled_by_default)
+            })
+            .collect();
+        self.quick_filters = quick_filters;
+    }
+    
     pub fn toggle_quick_filter(&mut self, index: usize) {
         if index < self.quick_filter_states.len() {
             self.quick_filter_states[index] = !self.quick_filter_states[index];
@@ -637,85 +689,22 @@ impl MessageListState {
     
     // 應用快速過濾器過濾訊息
     pub fn apply_quick_filters(&self, messages: Vec<Message>) -> Vec<Message> {
-        // 檢查是否有任何過濾器被啟用
-        let has_active_filters = self.quick_filter_states.iter().any(|&state| state);
+        if self.quick_filters.is_empty() {
+            return messages;
+        }
         
         messages.into_iter().filter(|message| {
-            // 檢查訊息是否包含任何log關鍵字
-            let is_log_message = self.message_matches_any_filter(message);
-            
-            if !is_log_message {
-                // 不是log訊息，始終顯示
-                true
-            } else {
-                // 是log訊息，根據過濾器狀態決定
-                if has_active_filters {
-                    // 有過濾器啟用，只顯示匹配啟用過濾器的log訊息
-                    self.message_matches_any_active_filter(message)
-                } else {
-                    // 所有過濾器都停用，隱藏所有log訊息
-                    false
-                }
-            }
+            let matching = self.quick_filters.matching_indices(&message.topic, &message.payload, message.level.as_deref());
+            QuickFilterSet::is_visible(&matching, &self.quick_filter_states)
         }).collect()
     }
-    
-    // 檢查訊息是否匹配任何啟用的過濾器
-    fn message_matches_any_active_filter(&self, message: &Message) -> bool {
-        let content = format!("{} {}", message.topic, message.payload);
-        
-        for (index, &is_enabled) in self.quick_filter_states.iter().enumerate() {
-            if is_enabled {
-                // 使用預設過濾器模式（應該通過參數傳入config，但現在先硬編碼）
-                let (pattern, case_sensitive) = match index {
-                    0 => ("INFO", false),
-                    1 => ("WARN", false),
-                    2 => ("ERROR", false),
-                    3 => ("TRACE", false),
-                    4 => ("DEBUG", false),
-                    _ => continue,
-                };
-                
-                let matches = if case_sensitive {
-                    content.contains(pattern)
-                } else {
-                    content.to_lowercase().contains(&pattern.to_lowercase())
-                };
-                
-                if matches {
-                    return true;
-                }
-            }
-        }
-        
-        false
-    }
-    
-    // 檢查訊息是否匹配任何過濾器（不管是否啟用）
-    fn message_matches_any_filter(&self, message: &Message) -> bool {
-        let content = format!("{} {}", message.topic, message.payload);
-        
-        let patterns = [
-            ("INFO", false),
-            ("WARN", false),
-            ("ERROR", false),
-            ("TRACE", false),
-            ("DEBUG", false),
-        ];
-        
-        for (pattern, case_sensitive) in &patterns {
-            let matches = if *case_sensitive {
-                content.contains(pattern)
-            } else {
-                content.to_lowercase().contains(&pattern.to_lowercase())
-            };
-            
-            if matches {
-                return true;
-            }
-        }
-        
-        false
+}
+
+// 有 id 時比較 id，否則比較 topic 與時間
+fn same_message(a: &Message, b: &Message) -> bool {
+    match (a.id, b.id) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.topic == b.topic && a.timestamp == b.timestamp,
     }
 }
 
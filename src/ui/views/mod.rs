@@ -1,7 +1,27 @@
 pub mod topic_list;
 pub mod message_list;
 pub mod payload_detail;
+pub mod file_picker;
+pub mod preset_picker;
+pub mod column_editor;
+pub mod message_diff;
+pub mod mute_list;
+pub mod chart;
+pub mod topic_column_editor;
+pub mod alert_list;
+pub mod bookmark_list;
+pub mod trash_list;
 
 pub use topic_list::*;
 pub use message_list::*;
-pub use payload_detail::*;
\ No newline at end of file
+pub use payload_detail::*;
+pub use file_picker::*;
+pub use preset_picker::*;
+pub use column_editor::*;
+pub use message_diff::*;
+pub use mute_list::*;
+pub use chart::*;
+pub use topic_column_editor::*;
+pub use alert_list::*;
+pub use bookmark_list::*;
+pub use trash_list::*;
\ No newline at end of file
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use crossterm::style::Color;
+
+use crate::config::{MuteMode, MuteRule};
+use crate::ui::widgets::{ListPanel, ListRow};
+
+/// 靜音規則清單：切換 hide / drop 或移除規則
+#[derive(Debug, Clone, Default)]
+pub struct MuteListState {
+    pub rules: Vec<MuteRule>,
+    pub selected_index: usize,
+    pub message: Option<String>,
+}
+
+impl MuteListState {
+    pub fn new(rules: Vec<MuteRule>) -> Self {
+        Self {
+            rules,
+            ..Default::default()
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index + 1 < self.rules.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn toggle_mode(&mut self) {
+        if let Some(rule) = self.rules.get_mut(self.selected_index) {
+            rule.mode = match rule.mode {
+                MuteMode::Hide => MuteMode::Drop,
+                MuteMode::Drop => MuteMode::Hide,
+            };
+        }
+    }
+
+    pub fn remove_selected(&mut self) {
+        if self.selected_index < self.rules.len() {
+            self.rules.remove(self.selected_index);
+            self.selected_index = self.selected_index.min(self.rules.len().saturating_sub(1));
+        }
+    }
+}
+
+pub struct MuteListView;
+
+impl MuteListView {
+    pub fn render(state: &MuteListState, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let rows: Vec<ListRow> = state
+            .rules
+            .iter()
+            .map(|rule| match rule.mode {
+                MuteMode::Hide => ListRow::new(format!(" {:<6} {}", rule.mode.as_str(), rule.topic)),
+                MuteMode::Drop => ListRow::colored(format!(" {:<6} {}", rule.mode.as_str(), rule.topic), Color::Red),
+            })
+            .collect();
+        let footer = state.message.clone().unwrap_or_else(|| {
+            "hide: not listed  drop: not stored  [Space]hide/drop [Del]remove [↑↓]navigate [Esc]back".to_string()
+        });
+        ListPanel {
+            title: "Muted Topics",
+            header: Some(" Mode   Topic pattern"),
+            rows: &rows,
+            selected_index: state.selected_index,
+            empty_text: "No muted topics. Press [u] on a topic or add [[mutes]] to the config file.",
+            footer: &footer,
+        }
+        .render(terminal_width, terminal_height)
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use crossterm::style::Color;
+
+use crate::filter::FilterPreset;
+use crate::ui::widgets::{ListPanel, ListRow};
+
+/// 選擇、儲存與刪除過濾 preset
+#[derive(Debug, Clone, Default)]
+pub struct PresetPickerState {
+    pub presets: Vec<FilterPreset>,
+    pub selected_index: usize, // 0 為「儲存目前的過濾條件」
+    pub name_input: Option<String>, // 輸入新 preset 名稱中
+    pub delete_confirmation: bool,
+    pub message: Option<String>,
+}
+
+impl PresetPickerState {
+    pub fn new(presets: Vec<FilterPreset>) -> Self {
+        // 預設選第一個 preset；沒有 preset 時選儲存選項
+        let selected_index = 1.min(presets.len());
+        Self {
+            presets,
+            selected_index,
+            ..Default::default()
+        }
+    }
+
+    /// 清單列數 (含第一列的儲存選項)
+    pub fn row_count(&self) -> usize {
+        self.presets.len() + 1
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index + 1 < self.row_count() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn page_up(&mut self, page: usize) {
+        self.selected_index = self.selected_index.saturating_sub(page.max(1));
+    }
+
+    pub fn page_down(&mut self, page: usize) {
+        self.selected_index = (self.selected_index + page.max(1)).min(self.row_count() - 1);
+    }
+
+    /// 選取的 preset，選在儲存選項上時為 None
+    pub fn selected(&self) -> Option<&FilterPreset> {
+        self.selected_index.checked_sub(1).and_then(|index| self.presets.get(index))
+    }
+
+    pub fn start_name_input(&mut self) {
+        // 選在既有的 preset 上時以其名稱為預設值，方便覆寫
+        let name = self.selected().filter(|p| !p.from_config).map(|p| p.name.clone()).unwrap_or_default();
+        self.name_input = Some(name);
+        self.message = None;
+    }
+}
+
+pub struct PresetPickerView;
+
+impl PresetPickerView {
+    pub fn render(state: &PresetPickerState, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let mut rows = vec![ListRow::colored(" + Save current filters as preset...", Color::Green)];
+        rows.extend(state.presets.iter().map(Self::format_preset));
+
+        let footer = if let Some(name) = &state.name_input {
+            format!("Preset name: {}█  [Enter]save [Esc]cancel", name)
+        } else if state.delete_confirmation {
+            "Press [Delete] again to delete this preset, any other key to cancel".to_string()
+        } else if let Some(message) = &state.message {
+            message.clone()
+        } else {
+            "[Enter]apply/save [Del]delete [↑↓]navigate [Esc]cancel".to_string()
+        };
+
+        ListPanel {
+            title: "Filter Presets",
+            header: Some(&format!(" {:<24} {}", "Name", "Filters")),
+            rows: &rows,
+            selected_index: state.selected_index,
+            empty_text: "",
+            footer: &footer,
+        }
+        .render(terminal_width, terminal_height)
+    }
+
+    fn format_preset(preset: &FilterPreset) -> ListRow {
+        let name = if preset.from_config {
+            format!("{} (config)", preset.name)
+        } else {
+            preset.name.clone()
+        };
+        let text = format!(" {:<24} {}", name, preset.summary());
+        if preset.from_config {
+            ListRow::colored(text, Color::Cyan)
+        } else {
+            ListRow::new(text)
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::Result;
+use crossterm::style::Color;
+
+use crate::ui::columns::{TopicColumn, TopicColumnConfig};
+use crate::ui::widgets::{ListPanel, ListRow};
+
+// 調整欄寬的範圍
+const MIN_WIDTH: usize = 3;
+const MAX_WIDTH: usize = 80;
+
+/// 編輯 Topic 清單顯示的欄位、順序與寬度
+#[derive(Debug, Clone)]
+pub struct TopicColumnEditorState {
+    pub columns: Vec<(TopicColumnConfig, bool)>, // (欄位, 是否顯示)，顯示的欄位依序排在前面
+    pub selected_index: usize,
+    pub message: Option<String>,
+}
+
+impl TopicColumnEditorState {
+    pub fn new(visible: &[TopicColumnConfig]) -> Self {
+        let mut columns: Vec<(TopicColumnConfig, bool)> = visible.iter().map(|config| (*config, true)).collect();
+        columns.extend(
+            TopicColumn::ALL
+                .iter()
+                .filter(|column| !visible.iter().any(|config| config.column == **column))
+                .map(|column| (TopicColumnConfig::new(*column), false)),
+        );
+        Self {
+            columns,
+            selected_index: 0,
+            message: None,
+        }
+    }
+
+    /// 顯示的欄位 (依目前順序)
+    pub fn visible_columns(&self) -> Vec<TopicColumnConfig> {
+        self.columns.iter().filter(|(_, visible)| *visible).map(|(config, _)| *config).collect()
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index + 1 < self.columns.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    /// 切換選取欄位的顯示，至少保留一個欄位
+    pub fn toggle_selected(&mut self) -> bool {
+        let visible_count = self.columns.iter().filter(|(_, visible)| *visible).count();
+        match self.columns.get_mut(self.selected_index) {
+            Some((_, visible)) if !*visible || visible_count > 1 => {
+                *visible = !*visible;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 將選取的欄位往前 (-1) 或往後 (+1) 移
+    pub fn move_selected(&mut self, offset: isize) -> bool {
+        let target = self.selected_index as isize + offset;
+        if target < 0 || target as usize >= self.columns.len() {
+            return false;
+        }
+        self.columns.swap(self.selected_index, target as usize);
+        self.selected_index = target as usize;
+        true
+    }
+
+    /// 調整選取欄位的寬度；None 表示回到預設寬度
+    pub fn resize_selected(&mut self, delta: Option<isize>) -> bool {
+        // 快速過濾器計數欄的寬度依各過濾器名稱決定
+        let Some((config, _)) = self.columns.get_mut(self.selected_index).filter(|(config, _)| config.column != TopicColumn::QuickFilters) else {
+            return false;
+        };
+        let width = delta.map(|delta| config.width().saturating_add_signed(delta).clamp(MIN_WIDTH, MAX_WIDTH));
+        // 與預設相同的寬度不寫入設定檔
+        let width = width.filter(|&width| width != config.column.default_width());
+        let changed = config.width != width;
+        config.width = width;
+        changed
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(&TopicColumnConfig::defaults());
+    }
+}
+
+pub struct TopicColumnEditorView;
+
+impl TopicColumnEditorView {
+    pub fn render(state: &TopicColumnEditorState, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let rows: Vec<ListRow> = state
+            .columns
+            .iter()
+            .map(|(config, visible)| {
+                let width = match (config.column, config.width) {
+                    (TopicColumn::QuickFilters, _) => "auto".to_string(),
+                    (_, Some(width)) => width.to_string(),
+                    (column, None) => format!("{} (default)", column.default_width()),
+                };
+                let text = format!(" [{}] {:<22} {}", if *visible { "x" } else { " " }, config.column.title(), width);
+                if *visible {
+                    ListRow::new(text)
+                } else {
+                    ListRow::colored(text, Color::DarkGrey)
+                }
+            })
+            .collect();
+
+        let footer = state.message.clone().unwrap_or_else(|| {
+            "[Space/Enter]show/hide [-/+]move [</>]width [a]auto width [r]reset [Esc]close".to_string()
+        });
+        ListPanel {
+            title: "Topic List Columns",
+            header: Some(&format!(" {:<26} {}", "Column", "Width")),
+            rows: &rows,
+            selected_index: state.selected_index,
+            empty_text: "",
+            footer: &footer,
+        }
+        .render(terminal_width, terminal_height)
+    }
+}
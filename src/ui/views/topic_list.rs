@@ -7,10 +7,59 @@ use crossterm::{
 use std::io::{stdout, Write};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-use crate::db::TopicStat;
+use crate::config::TopicListConfig;
+use crate::db::{TopicRates, TopicStat};
+use crate::ui::columns::{TopicColumn, TopicColumnConfig};
+use crate::decoders::{sparkplug, SparkplugTopic};
+use crate::filter::format_silence;
 use crate::utils::json_formatter::JsonFormatter;
 
+/// Topic 清單的排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopicSortKey {
+    #[default]
+    #[serde(rename = "last_seen")]
+    LastMessage,
+    Silence,            // 依沉寂時間，未沉寂的 topic 視為最短
+    FirstSeen,
+    Name,
+    Count,
+    Bytes,
+    Rate,               // 依 1 分鐘速率，同分時依 5 分鐘、1 小時
+    QuickFilter(usize), // 依該快速過濾器的訊息數
+}
+
+impl TopicSortKey {
+    /// 依序切換：最後訊息 → 沉寂時間 → 最早訊息 → 名稱 → 訊息數 → 位元組 → 速率 → 各快速過濾器計數欄
+    pub fn next(self, count_columns: &[(usize, String)]) -> Self {
+        let column_position = |index: usize| count_columns.iter().position(|(i, _)| *i == index);
+        match self {
+            TopicSortKey::LastMessage => TopicSortKey::Silence,
+            TopicSortKey::Silence => TopicSortKey::FirstSeen,
+            TopicSortKey::FirstSeen => TopicSortKey::Name,
+            TopicSortKey::Name => TopicSortKey::Count,
+            TopicSortKey::Count => TopicSortKey::Bytes,
+            TopicSortKey::Bytes => TopicSortKey::Rate,
+            TopicSortKey::Rate => count_columns
+                .first()
+                .map(|(index, _)| TopicSortKey::QuickFilter(*index))
+                .unwrap_or(TopicSortKey::LastMessage),
+            TopicSortKey::QuickFilter(index) => column_position(index)
+                .and_then(|position| count_columns.get(position + 1))
+                .map(|(next, _)| TopicSortKey::QuickFilter(*next))
+                .unwrap_or(TopicSortKey::LastMessage),
+        }
+    }
+
+    /// 切換到此排序時的預設方向：名稱由 A 到 Z，其餘由大到小
+    pub fn default_descending(self) -> bool {
+        self != TopicSortKey::Name
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TopicListState {
     pub topics: Vec<TopicStat>,
@@ -18,6 +67,13 @@ pub struct TopicListState {
     pub scroll_offset: usize,
     pub visible_rows: usize,
     pub delete_confirmation: bool,
+    pub sort_key: TopicSortKey,
+    pub descending: bool,
+    pub columns: Vec<TopicColumnConfig>,
+    pub count_columns: Vec<(usize, String)>, // (快速過濾器索引, 名稱)，顯示為計數欄
+    pub stale_only: bool,                    // 只列出已沉寂的 topic
+    pub pinned: Vec<String>,                 // 釘選的 topic，排在最上方
+    pub pinned_only: bool,                   // 只列出釘選的 topic
 }
 
 impl Default for TopicListState {
@@ -28,14 +84,28 @@ impl Default for TopicListState {
             scroll_offset: 0,
             visible_rows: 10,
             delete_confirmation: false,
+            sort_key: TopicSortKey::default(),
+            descending: true,
+            columns: TopicColumnConfig::defaults(),
+            count_columns: Vec::new(),
+            stale_only: false,
+            pinned: Vec::new(),
+            pinned_only: false,
         }
     }
 }
 
 impl TopicListState {
-    pub fn update_topics(&mut self, topics: Vec<TopicStat>) {
+    pub fn update_topics(&mut self, mut topics: Vec<TopicStat>) {
         let was_empty = self.topics.is_empty();
+        if self.stale_only {
+            topics.retain(|topic| topic.silent_for.is_some());
+        }
+        if self.pinned_only {
+            topics.retain(|topic| self.is_pinned(&topic.topic));
+        }
         self.topics = topics;
+        self.sort();
         
         // Reset selection if topics list was empty
         if was_empty && !self.topics.is_empty() {
@@ -133,6 +203,129 @@ impl TopicListState {
         self.topics.get(self.selected_index)
     }
     
+    // 資料庫已依最後訊息時間排序；排序為穩定排序，同分時維持時間順序
+    fn sort_topics(topics: &mut Vec<TopicStat>, sort_key: TopicSortKey, descending: bool) {
+        use std::cmp::Ordering;
+        
+        if sort_key == TopicSortKey::LastMessage && descending {
+            // Sparkplug B topic 依 group / edge node / device 排在一起
+            sparkplug::group_topics(topics, |t| t.topic.as_str());
+            return;
+        }
+        topics.sort_by(|a, b| {
+            let ordering = match sort_key {
+                TopicSortKey::LastMessage => a.last_message_time.cmp(&b.last_message_time),
+                TopicSortKey::Silence => a.silent_for.cmp(&b.silent_for),
+                TopicSortKey::FirstSeen => a.first_message_time.cmp(&b.first_message_time),
+                TopicSortKey::Name => a.topic.to_lowercase().cmp(&b.topic.to_lowercase()),
+                TopicSortKey::Count => a.message_count.cmp(&b.message_count),
+                TopicSortKey::Bytes => a.total_bytes.cmp(&b.total_bytes),
+                TopicSortKey::Rate => a.rates.per_second.partial_cmp(&b.rates.per_second).unwrap_or(Ordering::Equal),
+                TopicSortKey::QuickFilter(index) => {
+                    let count = |t: &TopicStat| t.quick_filter_counts.get(index).copied().unwrap_or(0);
+                    count(a).cmp(&count(b))
+                }
+            };
+            if descending { ordering.reverse() } else { ordering }
+        });
+    }
+    
+    // 釘選的 topic 維持排序後的相對順序，移到最前面
+    fn sort(&mut self) {
+        Self::sort_topics(&mut self.topics, self.sort_key, self.descending);
+        if !self.pinned.is_empty() {
+            let pinned = &self.pinned;
+            self.topics.sort_by_key(|topic| !pinned.contains(&topic.topic));
+        }
+    }
+    
+    pub fn is_pinned(&self, topic: &str) -> bool {
+        self.pinned.iter().any(|pinned| pinned == topic)
+    }
+    
+    /// 釘選或取消釘選選取的 topic，選取跟著該 topic 移動
+    pub fn toggle_pin(&mut self) {
+        let Some(topic) = self.get_selected_topic().map(|topic| topic.topic.clone()) else {
+            return;
+        };
+        if self.is_pinned(&topic) {
+            self.pinned.retain(|pinned| *pinned != topic);
+        } else {
+            self.pinned.push(topic.clone());
+        }
+        self.sort();
+        if let Some(index) = self.topics.iter().position(|stat| stat.topic == topic) {
+            self.selected_index = index;
+        }
+        self.adjust_scroll();
+    }
+    
+    /// 切換是否只列出釘選的 topic (下次更新清單時生效)
+    pub fn toggle_pinned_only(&mut self) {
+        self.pinned_only = !self.pinned_only;
+        self.selected_index = 0;
+        self.scroll_offset = 0;
+    }
+    
+    pub fn cycle_sort_key(&mut self) {
+        self.sort_key = self.sort_key.next(&self.count_columns);
+        self.descending = self.sort_key.default_descending();
+        self.resort();
+    }
+    
+    /// 切換是否只列出已沉寂的 topic (下次更新清單時生效)
+    pub fn toggle_stale_only(&mut self) {
+        self.stale_only = !self.stale_only;
+        self.selected_index = 0;
+        self.scroll_offset = 0;
+    }
+    
+    /// 切換遞增 / 遞減
+    pub fn toggle_sort_direction(&mut self) {
+        self.descending = !self.descending;
+        self.resort();
+    }
+    
+    fn resort(&mut self) {
+        self.sort();
+        self.selected_index = 0;
+        self.scroll_offset = 0;
+    }
+    
+    /// 套用設定檔的排序與欄位
+    pub fn set_layout(&mut self, layout: &TopicListConfig) {
+        self.columns = layout.columns.clone();
+        if self.pinned != layout.pinned {
+            self.pinned = layout.pinned.clone();
+            self.sort();
+        }
+        if self.sort_key != layout.sort || self.descending != layout.descending {
+            self.sort_key = layout.sort;
+            self.descending = layout.descending;
+            self.resort();
+        }
+    }
+    
+    pub fn layout(&self) -> TopicListConfig {
+        TopicListConfig {
+            sort: self.sort_key,
+            descending: self.descending,
+            pinned: self.pinned.clone(),
+            columns: self.columns.clone(),
+        }
+    }
+    
+    /// 快速過濾器重新載入後更新計數欄；排序用的欄位不存在時改回預設排序
+    pub fn set_count_columns(&mut self, count_columns: Vec<(usize, String)>) {
+        if let TopicSortKey::QuickFilter(index) = self.sort_key {
+            if !count_columns.iter().any(|(i, _)| *i == index) {
+                self.sort_key = TopicSortKey::LastMessage;
+                self.descending = true;
+            }
+        }
+        self.count_columns = count_columns;
+    }
+    
     pub fn set_visible_rows(&mut self, rows: usize) {
         self.visible_rows = rows;
         self.adjust_scroll();
@@ -194,11 +387,37 @@ impl TopicListView {
         }
         stdout.queue(Print("│ "))?;
         
-        let header = format!(
-            "{:>5} │ {:<12} │ {:<18} │ {:<6} │ {:<25}",
-            "No.", "Last Message", "Topic", "Count", "Latest Payload"
-        );
-        let padded_header = format!("{:<width$}", header, width = terminal_width.saturating_sub(3) as usize);
+        // 排序欄位的標題加上 ▼ (遞減) 或 ▲ (遞增)
+        let arrow = if state.descending { "▼" } else { "▲" };
+        let titled = |title: &str, sort_key: Option<TopicSortKey>| -> String {
+            if sort_key == Some(state.sort_key) {
+                format!("{}{}", title, arrow)
+            } else {
+                title.to_string()
+            }
+        };
+        let cells: Vec<String> = state.columns
+            .iter()
+            .flat_map(|config| -> Vec<String> {
+                match config.column {
+                    TopicColumn::QuickFilters => state.count_columns
+                        .iter()
+                        .map(|(index, name)| {
+                            let title = titled(name, Some(TopicSortKey::QuickFilter(*index)));
+                            Self::fit_cell(&title, Self::count_column_width(name), true, false)
+                        })
+                        .collect(),
+                    column => {
+                        let title = titled(column.title(), Self::column_sort_key(column));
+                        vec![Self::fit_cell(&title, config.width(), column.right_aligned(), false)]
+                    }
+                }
+            })
+            .collect();
+        let header = cells.join(" │ ");
+        let header_width = terminal_width.saturating_sub(3) as usize;
+        let header: String = header.chars().take(header_width).collect();
+        let padded_header = format!("{:<width$}", header, width = header_width);
         stdout.queue(Print(&padded_header))?;
         stdout.queue(Print("│"))?;
         
@@ -218,7 +437,13 @@ impl TopicListView {
                 
                 if i == available_list_height / 2 {
                     stdout.queue(SetForegroundColor(Color::DarkGrey))?;
-                    let message = "No MQTT topics available. Waiting for messages...";
+                    let message = if state.stale_only {
+                        "No stale topics."
+                    } else if state.pinned_only {
+                        "No pinned topics. Press [p] to pin the selected topic."
+                    } else {
+                        "No MQTT topics available. Waiting for messages..."
+                    };
                     let centered = (terminal_width.saturating_sub(message.len() as u16 + 2)) / 2;
                     stdout.queue(Print(&format!("{:>width$}{}", "", message, width = centered as usize)))?;
                     let padding = terminal_width.saturating_sub(message.len() as u16 + centered + 2);
@@ -262,12 +487,27 @@ impl TopicListView {
                             Self::render_topic_row_with_confirmation(&mut stdout, topic, terminal_width, topic_index + 1)?;
                         } else {
                             tracing::info!("No delete confirmation for selected topic: {}", topic.topic);
-                            Self::render_topic_row_with_border(&mut stdout, topic, terminal_width, topic_index + 1)?;
+                            Self::render_topic_row_with_border(&mut stdout, topic, &state.columns, &state.count_columns, terminal_width, topic_index + 1)?;
                         }
                         
+                        stdout.queue(ResetColor)?;
+                    } else if topic.muted {
+                        // 顯示靜音的 topic 時以暗灰色標示
+                        stdout.queue(SetForegroundColor(Color::DarkGrey))?;
+                        Self::render_topic_row_with_border(&mut stdout, topic, &state.columns, &state.count_columns, terminal_width, topic_index + 1)?;
+                        stdout.queue(ResetColor)?;
+                    } else if topic.silent_for.is_some() {
+                        // 已沉寂的 topic 以紅字標示
+                        stdout.queue(SetForegroundColor(Color::Red))?;
+                        Self::render_topic_row_with_border(&mut stdout, topic, &state.columns, &state.count_columns, terminal_width, topic_index + 1)?;
+                        stdout.queue(ResetColor)?;
+                    } else if state.is_pinned(&topic.topic) {
+                        // 釘選的 topic 以黃字標示
+                        stdout.queue(SetForegroundColor(Color::Yellow))?;
+                        Self::render_topic_row_with_border(&mut stdout, topic, &state.columns, &state.count_columns, terminal_width, topic_index + 1)?;
                         stdout.queue(ResetColor)?;
                     } else {
-                        Self::render_topic_row_with_border(&mut stdout, topic, terminal_width, topic_index + 1)?;
+                        Self::render_topic_row_with_border(&mut stdout, topic, &state.columns, &state.count_columns, terminal_width, topic_index + 1)?;
                     }
                     
                     stdout.queue(Print("│"))?;
@@ -353,72 +593,161 @@ impl TopicListView {
         Ok(())
     }
 
+    // 快速過濾器計數欄寬度 (名稱與排序符號 ▼)
+    fn count_column_width(name: &str) -> usize {
+        (name.chars().count() + 1).max(5)
+    }
+    
+    fn column_sort_key(column: TopicColumn) -> Option<TopicSortKey> {
+        match column {
+            TopicColumn::LastSeen => Some(TopicSortKey::LastMessage),
+            TopicColumn::Silence => Some(TopicSortKey::Silence),
+            TopicColumn::FirstSeen => Some(TopicSortKey::FirstSeen),
+            TopicColumn::Topic => Some(TopicSortKey::Name),
+            TopicColumn::Count => Some(TopicSortKey::Count),
+            TopicColumn::Bytes => Some(TopicSortKey::Bytes),
+            TopicColumn::Rate1m => Some(TopicSortKey::Rate),
+            _ => None,
+        }
+    }
+    
+    // 截斷 (以 ... 結尾) 或補空白到欄寬，以字元計；overflow 時不截斷
+    fn fit_cell(text: &str, width: usize, right_aligned: bool, overflow: bool) -> String {
+        let text = if text.chars().count() > width && !overflow {
+            let keep = width.saturating_sub(3);
+            format!("{}{}", text.chars().take(keep).collect::<String>(), &"..."[..width.min(3)])
+        } else {
+            text.to_string()
+        };
+        if right_aligned {
+            format!("{:>width$}", text, width = width)
+        } else {
+            format!("{:<width$}", text, width = width)
+        }
+    }
+    
+    fn format_bytes(bytes: i64) -> String {
+        const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+        if bytes < 1024 {
+            return format!("{}B", bytes);
+        }
+        let mut value = bytes as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit + 1 < UNITS.len() {
+            value /= 1024.0;
+            unit += 1;
+        }
+        if value >= 100.0 {
+            format!("{:.0}{}", value, UNITS[unit])
+        } else {
+            format!("{:.1}{}", value, UNITS[unit])
+        }
+    }
+    
+    // JSON 物件只顯示 key，例如 {"temperature","unit"}；其他內容取開頭
+    fn payload_summary(topic: &TopicStat, width: usize) -> String {
+        let Some(payload) = &topic.latest_payload else {
+            return "(no data)".to_string();
+        };
+        let trimmed = payload.trim();
+        if trimmed.starts_with('{') && trimmed.ends_with('}') {
+            if let Ok(serde_json::Value::Object(obj)) = serde_json::from_str::<serde_json::Value>(payload) {
+                let keys: Vec<String> = obj.keys().map(|k| format!("\"{}\"", k)).collect();
+                return format!("{{{}}}", keys.join(","));
+            }
+        }
+        if payload.is_empty() {
+            "(empty)".to_string()
+        } else if payload.chars().count() > width {
+            format!("{}...", payload.chars().take(width).collect::<String>())
+        } else {
+            payload.clone()
+        }
+    }
+    
+    // 每秒訊息數，依大小調整小數位數
+    fn format_rate(rate: f64) -> String {
+        if rate == 0.0 {
+            "0".to_string()
+        } else if rate >= 100.0 {
+            format!("{:.0}", rate)
+        } else if rate >= 10.0 {
+            format!("{:.1}", rate)
+        } else {
+            format!("{:.2}", rate)
+        }
+    }
+    
+    // 最近一小時的訊息數分佈，沒有訊息的時段留空
+    fn sparkline(rates: &TopicRates) -> String {
+        const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let max = rates.buckets.iter().copied().max().unwrap_or(0);
+        rates.buckets
+            .iter()
+            .map(|&count| match count {
+                0 => ' ',
+                _ => LEVELS[((count as usize * LEVELS.len()).div_ceil(max as usize) - 1).min(LEVELS.len() - 1)],
+            })
+            .collect()
+    }
+    
+    fn format_count(count: i64) -> String {
+        if count > 9999 {
+            "9999+".to_string()
+        } else {
+            count.to_string()
+        }
+    }
+    
     fn render_topic_row_with_border<W: Write>(
         writer: &mut W,
         topic: &TopicStat,
+        columns: &[TopicColumnConfig],
+        count_columns: &[(usize, String)],
         terminal_width: u16,
         sequence_number: usize
     ) -> Result<()> {
-        // Format timestamp (convert from UTC to Local time)
-        let local_time = topic.last_message_time.with_timezone(&chrono::Local);
-        let time_str = local_time.format("%H:%M:%S").to_string();
-        
-        // Format message count
-        let count_str = if topic.message_count > 9999 {
-            "9999+".to_string()
-        } else {
-            topic.message_count.to_string()
-        };
-        
-        // Simplify payload for display according to PRD
-        let payload_str = if let Some(payload) = &topic.latest_payload {
-            // JSON format: show only keys like {"temperature","unit"}
-            if payload.trim().starts_with('{') && payload.trim().ends_with('}') {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(payload) {
-                    if let Some(obj) = json.as_object() {
-                        let keys: Vec<String> = obj.keys().map(|k| format!("\"{}\"", k)).collect();
-                        format!("{{{}}}", keys.join(","))
-                    } else {
-                        payload.chars().take(25).collect()
+        let cells: Vec<String> = columns
+            .iter()
+            .flat_map(|config| -> Vec<String> {
+                let width = config.width();
+                let text = match config.column {
+                    TopicColumn::Number => sequence_number.to_string(),
+                    TopicColumn::LastSeen => topic.last_message_time.with_timezone(&chrono::Local).format("%H:%M:%S").to_string(),
+                    TopicColumn::Silence => topic.silent_for.map(format_silence).unwrap_or_default(),
+                    TopicColumn::FirstSeen => topic.first_message_time.with_timezone(&chrono::Local).format("%m-%d %H:%M:%S").to_string(),
+                    TopicColumn::Topic => match SparkplugTopic::parse(&topic.topic) {
+                        Some(sparkplug_topic) => sparkplug_topic.display_label(),
+                        None => topic.topic.clone(),
+                    },
+                    TopicColumn::Count => Self::format_count(topic.message_count),
+                    TopicColumn::Bytes => Self::format_bytes(topic.total_bytes),
+                    TopicColumn::Rate1m => Self::format_rate(topic.rates.per_second[0]),
+                    TopicColumn::Rate5m => Self::format_rate(topic.rates.per_second[1]),
+                    TopicColumn::Rate1h => Self::format_rate(topic.rates.per_second[2]),
+                    TopicColumn::Sparkline => Self::sparkline(&topic.rates),
+                    TopicColumn::Payload => Self::payload_summary(topic, width),
+                    TopicColumn::QuickFilters => {
+                        return count_columns
+                            .iter()
+                            .map(|(index, name)| {
+                                let count = topic.quick_filter_counts.get(*index).copied().unwrap_or(0);
+                                Self::fit_cell(&Self::format_count(count), Self::count_column_width(name), true, false)
+                            })
+                            .collect();
                     }
-                } else {
-                    payload.chars().take(25).collect()
-                }
-            } else if payload.is_empty() {
-                "(empty)".to_string()
-            } else {
-                // Non-JSON: show first 25 chars
-                let trimmed: String = payload.chars().take(25).collect();
-                if payload.len() > 25 {
-                    format!("{}...", trimmed)
-                } else {
-                    trimmed
-                }
-            }
-        } else {
-            "(no data)".to_string()
-        };
-        
-        // Truncate topic name if too long
-        let topic_name = if topic.topic.len() > 18 {
-            format!("{}...", &topic.topic[..15])
-        } else {
-            topic.topic.clone()
-        };
-        
-        let line = format!(
-            " {:>5} │ {:<12} │ {:<18} │ {:<6} │ {:<25}",
-            sequence_number,
-            time_str,
-            topic_name,
-            count_str,
-            payload_str
-        );
+                };
+                // 最後一欄 (通常是 payload) 可以延伸到畫面邊緣
+                let is_last = std::ptr::eq(config, columns.last().unwrap_or(config));
+                vec![Self::fit_cell(&text, width, config.column.right_aligned(), is_last)]
+            })
+            .collect();
+        let line = format!(" {}", cells.join(" │ "));
         
         // Pad line to fit terminal width minus borders
         let max_width = terminal_width.saturating_sub(3) as usize;
-        let padded_line = if line.len() > max_width {
-            format!("{}", &line[..max_width])
+        let padded_line = if line.chars().count() > max_width {
+            line.chars().take(max_width).collect()
         } else {
             format!("{:<width$}", line, width = max_width)
         };
@@ -475,4 +804,45 @@ impl TopicListView {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(topic: &str, message_count: i64) -> TopicStat {
+        TopicStat {
+            topic: topic.to_string(),
+            message_count,
+            last_message_time: chrono::Utc::now(),
+            first_message_time: chrono::Utc::now(),
+            latest_payload: None,
+            quick_filter_counts: Vec::new(),
+            total_bytes: 0,
+            rates: Default::default(),
+            silent_for: None,
+            muted: false,
+        }
+    }
+
+    #[test]
+    fn test_pinned_topics_stay_on_top() {
+        let mut state = TopicListState {
+            sort_key: TopicSortKey::Count,
+            pinned: vec!["c".to_string(), "b".to_string()],
+            ..Default::default()
+        };
+        state.update_topics(vec![stat("a", 30), stat("b", 10), stat("c", 5), stat("d", 20)]);
+        let order = |state: &TopicListState| state.topics.iter().map(|t| t.topic.clone()).collect::<Vec<_>>();
+        // 釘選的 topic 之間仍依排序方式排列
+        assert_eq!(order(&state), ["b", "c", "a", "d"]);
+
+        state.selected_index = 2;
+        state.toggle_pin();
+        assert_eq!(order(&state), ["a", "b", "c", "d"]);
+        assert_eq!(state.selected_index, 0);
+
+        state.toggle_pinned_only();
+        state.update_topics(vec![stat("a", 30), stat("b", 10), stat("c", 5), stat("d", 20)]);
+        assert_eq!(order(&state), ["a", "b", "c"]);
+    }
+}
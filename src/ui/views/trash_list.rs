@@ -0,0 +1,74 @@
+use anyhow::Result;
+
+use crate::db::TrashEntry;
+use crate::ui::widgets::{ListPanel, ListRow};
+
+/// 垃圾桶：每次刪除為一列，可整批還原或永久刪除
+#[derive(Debug, Clone, Default)]
+pub struct TrashListState {
+    pub entries: Vec<TrashEntry>, // 新→舊
+    pub selected_index: usize,
+    pub message: Option<String>,
+    pub purge_confirmation: bool,
+}
+
+impl TrashListState {
+    pub fn set_entries(&mut self, entries: Vec<TrashEntry>) {
+        self.entries = entries;
+        self.selected_index = self.selected_index.min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index + 1 < self.entries.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn page_up(&mut self, page: usize) {
+        self.selected_index = self.selected_index.saturating_sub(page.max(1));
+    }
+
+    pub fn page_down(&mut self, page: usize) {
+        self.selected_index = (self.selected_index + page.max(1)).min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn selected(&self) -> Option<&TrashEntry> {
+        self.entries.get(self.selected_index)
+    }
+}
+
+pub struct TrashListView;
+
+impl TrashListView {
+    pub fn render(state: &TrashListState, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let rows: Vec<ListRow> = state.entries.iter().map(Self::format_entry).collect();
+        let footer = state.message.clone().unwrap_or_else(|| {
+            "[Enter/r]restore [Del]purge [↑↓]navigate [Esc]back  Expired with cleanup_days".to_string()
+        });
+        ListPanel {
+            title: "Trash",
+            header: Some(&format!(" {:<19} {:>7}  {:<41} {}", "Deleted", "Count", "Messages from - to", "Topic")),
+            rows: &rows,
+            selected_index: state.selected_index,
+            empty_text: "Trash is empty. Deleted topics and messages are kept here until purged.",
+            footer: &footer,
+        }
+        .render(terminal_width, terminal_height)
+    }
+
+    fn format_entry(entry: &TrashEntry) -> ListRow {
+        let local = |time: &chrono::DateTime<chrono::Utc>| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string();
+        ListRow::new(format!(
+            " {:<19} {:>7}  {:<19} - {:<19} {}",
+            local(&entry.deleted_at),
+            entry.count,
+            local(&entry.first_time),
+            local(&entry.last_time),
+            entry.topic,
+        ))
+    }
+}
@@ -0,0 +1,98 @@
+// Braille 點位 (行, 列) 對應的位元，U+2800 起算
+const DOT_BITS: [[u8; 2]; 4] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80],
+];
+
+/// 以 Braille 字元繪圖的畫布，每個字元格有 2×4 個點
+#[derive(Debug, Clone)]
+pub struct BrailleCanvas {
+    width: usize,  // 字元格數
+    height: usize, // 字元列數
+    cells: Vec<u8>,
+}
+
+impl BrailleCanvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![0; width * height],
+        }
+    }
+
+    pub fn dot_width(&self) -> usize {
+        self.width * 2
+    }
+
+    pub fn dot_height(&self) -> usize {
+        self.height * 4
+    }
+
+    /// 點亮 (x, y)，y = 0 為最上方；超出畫布的點忽略
+    pub fn set(&mut self, x: i64, y: i64) {
+        if x < 0 || y < 0 || x as usize >= self.dot_width() || y as usize >= self.dot_height() {
+            return;
+        }
+        let (x, y) = (x as usize, y as usize);
+        self.cells[(y / 4) * self.width + x / 2] |= DOT_BITS[y % 4][x % 2];
+    }
+
+    /// 以 Bresenham 演算法畫線
+    pub fn line(&mut self, from: (i64, i64), to: (i64, i64)) {
+        let (mut x, mut y) = from;
+        let dx = (to.0 - x).abs();
+        let dy = -(to.1 - y).abs();
+        let step_x = if x < to.0 { 1 } else { -1 };
+        let step_y = if y < to.1 { 1 } else { -1 };
+        let mut error = dx + dy;
+        loop {
+            self.set(x, y);
+            if (x, y) == to {
+                break;
+            }
+            let doubled = error * 2;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    pub fn is_blank(&self, col: usize, row: usize) -> bool {
+        self.cells[row * self.width + col] == 0
+    }
+
+    /// 一列的字元，空白格為 U+2800
+    pub fn row(&self, row: usize) -> Vec<char> {
+        self.cells[row * self.width..(row + 1) * self.width]
+            .iter()
+            .map(|&bits| char::from_u32(0x2800 + bits as u32).unwrap_or(' '))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_braille_canvas() {
+        let mut canvas = BrailleCanvas::new(2, 1);
+        canvas.set(0, 0);
+        canvas.set(3, 3);
+        canvas.set(4, 0); // 超出畫布
+        assert_eq!(canvas.row(0), vec!['⠁', '⢀']);
+
+        let mut canvas = BrailleCanvas::new(2, 1);
+        canvas.line((0, 3), (3, 0));
+        assert_eq!(canvas.row(0), vec!['⡠', '⠊']);
+        assert!(!canvas.is_blank(1, 0));
+    }
+}
@@ -6,7 +6,9 @@ use crossterm::{
 };
 use std::io::{stdout, Write};
 use anyhow::Result;
-use chrono::{DateTime, Local, Datelike, Timelike, NaiveDateTime};
+use chrono::{DateTime, Local, Datelike, Timelike};
+
+use crate::filter::{TimeExpr, DEFAULT_TIME_FROM, DEFAULT_TIME_TO};
 
 #[derive(Debug, Clone)]
 pub struct FilterState {
@@ -19,6 +21,7 @@ pub struct FilterState {
     pub time_edit_mode: bool,  // 是否處於時間編輯模式
     pub time_edit_position: TimeEditPosition,  // 當前編輯的時間部分
     pub temp_datetime: Option<DateTime<Local>>,  // 暫存的時間值
+    pub filter_error: Option<String>,  // 查詢語法或時間的錯誤，顯示在 payload 欄位後
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -41,21 +44,17 @@ pub enum FilterField {
 
 impl Default for FilterState {
     fn default() -> Self {
-        use chrono::{Duration, Local};
-        let now = Local::now();
-        let yesterday = now - Duration::days(1);
-        let tomorrow = now + Duration::days(1);
-        
         Self {
             topic_filter: String::new(),
             payload_filter: String::new(),
-            start_time: yesterday.format("%Y-%m-%d %H:%M:%S").to_string(),
-            end_time: tomorrow.format("%Y-%m-%d %H:%M:%S").to_string(),
+            start_time: DEFAULT_TIME_FROM.to_string(),
+            end_time: DEFAULT_TIME_TO.to_string(),
             active_field: FilterField::Topic,
             is_editing: false,
             time_edit_mode: false,
             time_edit_position: TimeEditPosition::Year,
             temp_datetime: None,
+            filter_error: None,
         }
     }
 }
@@ -135,11 +134,9 @@ impl FilterState {
                         _ => {}
                     }
                 } else {
-                    // 解析現有時間
-                    if let Ok(naive) = NaiveDateTime::parse_from_str(current_value, "%Y-%m-%d %H:%M:%S") {
-                        // 將NaiveDateTime轉換為Local時間
-                        use chrono::TimeZone;
-                        self.temp_datetime = Some(Local.from_local_datetime(&naive).unwrap());
+                    // 解析現有時間；相對時間換算成目前對應的時間再調整
+                    if let Ok(expr) = TimeExpr::parse(current_value) {
+                        self.temp_datetime = Some(expr.resolve(Local::now()));
                     }
                 }
                 
@@ -173,11 +170,9 @@ impl FilterState {
                     _ => {}
                 }
             } else {
-                // 解析現有時間
-                if let Ok(naive) = NaiveDateTime::parse_from_str(current_value, "%Y-%m-%d %H:%M:%S") {
-                    // 將NaiveDateTime轉換為Local時間
-                    use chrono::TimeZone;
-                    self.temp_datetime = Some(Local.from_local_datetime(&naive).unwrap());
+                // 解析現有時間；相對時間換算成目前對應的時間再調整
+                if let Ok(expr) = TimeExpr::parse(current_value) {
+                    self.temp_datetime = Some(expr.resolve(Local::now()));
                 }
             }
             
@@ -301,6 +296,7 @@ impl FilterBar {
         
         let payload_changed = prev_state.map_or(true, |prev| 
             prev.payload_filter != state.payload_filter || 
+            prev.filter_error != state.filter_error ||
             prev.active_field != state.active_field ||
             prev.is_editing != state.is_editing
         );
@@ -348,7 +344,14 @@ impl FilterBar {
             stdout.queue(Print("│ Payload Filter: "))?;
             Self::render_field(&mut stdout, &state.payload_filter, state.active_field == FilterField::Payload && state.is_editing)?;
             stdout.queue(Print("]"))?;
-            let padding = terminal_width.saturating_sub(40); // 17 + 19 + 4 = 40 chars for payload filter line
+            let mut padding = terminal_width.saturating_sub(40); // 17 + 19 + 4 = 40 chars for payload filter line
+            if let Some(error) = &state.filter_error {
+                let error: String = format!(" {}", error).chars().take(padding).collect();
+                stdout.queue(SetForegroundColor(Color::Red))?;
+                stdout.queue(Print(&error))?;
+                stdout.queue(ResetColor)?;
+                padding = padding.saturating_sub(error.chars().count());
+            }
             stdout.queue(Print(&format!("{:<width$}", "", width = padding)))?;
             stdout.queue(Print("│"))?;
         
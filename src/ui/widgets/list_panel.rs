@@ -0,0 +1,133 @@
+use crossterm::{
+    cursor,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
+    QueueableCommand,
+};
+use std::io::{stdout, Write};
+use anyhow::Result;
+
+/// 清單中的一列
+#[derive(Debug, Clone)]
+pub struct ListRow {
+    pub text: String,
+    pub color: Option<Color>,
+}
+
+impl ListRow {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into(), color: None }
+    }
+
+    pub fn colored(text: impl Into<String>, color: Color) -> Self {
+        Self { text: text.into(), color: Some(color) }
+    }
+}
+
+/// 全螢幕的框線清單 (標題、表頭、可捲動的列、底部說明)，給 picker 類的畫面共用
+pub struct ListPanel<'a> {
+    pub title: &'a str,
+    pub header: Option<&'a str>,
+    pub rows: &'a [ListRow],
+    pub selected_index: usize,
+    pub empty_text: &'a str,
+    pub footer: &'a str,
+}
+
+impl ListPanel<'_> {
+    /// 標題、表頭、框線與底部說明以外可放清單的列數
+    pub fn visible_rows(terminal_height: u16) -> usize {
+        terminal_height.saturating_sub(6) as usize
+    }
+
+    /// 讓選取列保持在畫面內的捲動位置
+    pub fn scroll_offset(selected_index: usize, visible_rows: usize) -> usize {
+        if visible_rows == 0 {
+            0
+        } else {
+            selected_index.saturating_sub(visible_rows - 1)
+        }
+    }
+
+    pub fn render(&self, terminal_width: u16, terminal_height: u16) -> Result<()> {
+        let mut stdout = stdout();
+        let width = terminal_width.max(20) as usize;
+        let inner_width = width - 2;
+        let visible_rows = Self::visible_rows(terminal_height);
+        let scroll_offset = Self::scroll_offset(self.selected_index, visible_rows);
+        let mut row: u16 = 0;
+
+        // 標題列
+        stdout.queue(cursor::MoveTo(0, row))?;
+        stdout.queue(Clear(ClearType::CurrentLine))?;
+        let title = format!("┌─ {} ", self.title);
+        let title_len = title.chars().count();
+        stdout.queue(Print(&title))?;
+        stdout.queue(Print(&"─".repeat(width.saturating_sub(title_len + 1))))?;
+        stdout.queue(Print("┐"))?;
+        row += 1;
+
+        if let Some(header) = self.header {
+            stdout.queue(cursor::MoveTo(0, row))?;
+            stdout.queue(Clear(ClearType::CurrentLine))?;
+            stdout.queue(Print("│"))?;
+            stdout.queue(Print(&Self::fit(header, inner_width)))?;
+            stdout.queue(Print("│"))?;
+            row += 1;
+            stdout.queue(cursor::MoveTo(0, row))?;
+            stdout.queue(Clear(ClearType::CurrentLine))?;
+            stdout.queue(Print(&format!("├{}┤", "─".repeat(inner_width))))?;
+            row += 1;
+        }
+
+        for i in 0..visible_rows {
+            let index = scroll_offset + i;
+            stdout.queue(cursor::MoveTo(0, row))?;
+            stdout.queue(Clear(ClearType::CurrentLine))?;
+            stdout.queue(Print("│"))?;
+
+            if let Some(list_row) = self.rows.get(index) {
+                if index == self.selected_index {
+                    stdout.queue(SetBackgroundColor(Color::Blue))?;
+                    stdout.queue(SetForegroundColor(Color::White))?;
+                } else if let Some(color) = list_row.color {
+                    stdout.queue(SetForegroundColor(color))?;
+                }
+                stdout.queue(Print(&Self::fit(&list_row.text, inner_width)))?;
+                stdout.queue(ResetColor)?;
+            } else if self.rows.is_empty() && i == 0 {
+                stdout.queue(SetForegroundColor(Color::DarkGrey))?;
+                stdout.queue(Print(&Self::fit(self.empty_text, inner_width)))?;
+                stdout.queue(ResetColor)?;
+            } else {
+                stdout.queue(Print(&" ".repeat(inner_width)))?;
+            }
+
+            stdout.queue(Print("│"))?;
+            row += 1;
+        }
+
+        stdout.queue(cursor::MoveTo(0, row))?;
+        stdout.queue(Clear(ClearType::CurrentLine))?;
+        stdout.queue(Print(&format!("└{}┘", "─".repeat(inner_width))))?;
+        row += 1;
+
+        stdout.queue(cursor::MoveTo(0, row))?;
+        stdout.queue(Clear(ClearType::CurrentLine))?;
+        stdout.queue(Print(self.footer))?;
+
+        stdout.flush()?;
+        Ok(())
+    }
+
+    // 截斷或補空白到固定寬度 (以字元計)
+    fn fit(text: &str, width: usize) -> String {
+        let char_count = text.chars().count();
+        if char_count > width {
+            let truncated: String = text.chars().take(width.saturating_sub(3)).collect();
+            format!("{}...", truncated)
+        } else {
+            format!("{}{}", text, " ".repeat(width - char_count))
+        }
+    }
+}
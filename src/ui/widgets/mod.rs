@@ -1,5 +1,9 @@
 pub mod filter_bar;
+pub mod list_panel;
 pub mod status_bar;
+pub mod braille_canvas;
 
 pub use filter_bar::*;
-pub use status_bar::*;
\ No newline at end of file
+pub use list_panel::*;
+pub use status_bar::*;
+pub use braille_canvas::*;
@@ -1,31 +1,69 @@
 use crossterm::{
     cursor,
-    style::{Color, Print, ResetColor, SetForegroundColor},
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
     terminal::{Clear, ClearType},
     ExecutableCommand, QueueableCommand,
 };
 use std::io::{stdout, Write};
 use anyhow::Result;
 
+use crate::mqtt::QueueStats;
+
 #[derive(Debug, Clone)]
 pub struct StatusBarState {
     pub connection_status: ConnectionStatus,
     pub total_topics: usize,
+    pub stale_topics: usize, // 超過預期間隔未收到訊息的 topic 數
+    pub muted_topics: usize, // 符合靜音規則的 topic 數
+    pub show_muted: bool,    // 是否仍列出靜音的 topic
     pub total_messages: i64,
+    pub queue_stats: QueueStats,
     pub last_update: Option<chrono::DateTime<chrono::Utc>>,
     pub current_view: ViewType,
     pub help_text: String,
-    pub quick_filter_states: Vec<(String, String, bool)>, // (name, color, enabled)
+    pub quick_filter_states: Vec<QuickFilterStatus>,
+    pub alert_banner: Option<String>, // 最新的告警，開啟告警清單後清除
+    pub undo_hint: Option<String>,    // 最近一次刪除，可按 z 復原
 }
 
-#[derive(Debug, Clone)]
+/// 狀態列上顯示的單一快速過濾器
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickFilterStatus {
+    pub label: String, // 快捷鍵，例如 F1、Alt+E
+    pub name: String,
+    pub color: Option<Color>,
+    pub enabled: bool,
+    pub count: Option<i64>, // Topic 清單中所有 topic 的符合訊息總數
+}
+
+impl QuickFilterStatus {
+    pub fn text(&self) -> String {
+        let status_symbol = if self.enabled { "✓" } else { "✗" };
+        match self.count {
+            Some(count) => format!("[{}:{} {} {}]", self.label, self.name, count, status_symbol),
+            None => format!("[{}:{} {}]", self.label, self.name, status_symbol),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionStatus {
     Disconnected,
     Connecting,
     Connected(String), // broker address
+    Reconnecting {
+        attempt: u32,
+        delay: std::time::Duration,
+        error: String,
+    },
+    AuthFailed(String),
     Error(String),
+    Archive(String), // 唯讀開啟的資料庫檔案路徑
 }
 
+// Filter bar 的連線欄位寬度 (從第 14 欄到右邊框前)
+const CONNECTION_FIELD_WIDTH: usize = 84;
+
 #[derive(Debug, Clone)]
 pub enum ViewType {
     TopicList,
@@ -38,11 +76,17 @@ impl Default for StatusBarState {
         Self {
             connection_status: ConnectionStatus::Disconnected,
             total_topics: 0,
+            stale_topics: 0,
+            muted_topics: 0,
+            show_muted: false,
             total_messages: 0,
+            queue_stats: QueueStats::default(),
             last_update: None,
             current_view: ViewType::TopicList,
             help_text: "[/]filter [Enter]select [↑↓]navigate [F5]refresh [F1]help".to_string(),
             quick_filter_states: Vec::new(),
+            alert_banner: None,
+            undo_hint: None,
         }
     }
 }
@@ -73,17 +117,15 @@ impl StatusBar {
         
         // Check if status line changed
         let status_line_changed = prev_state.map_or(true, |prev| {
-            // Check connection status
-            let connection_changed = match (&prev.connection_status, &state.connection_status) {
-                (ConnectionStatus::Disconnected, ConnectionStatus::Disconnected) => false,
-                (ConnectionStatus::Connecting, ConnectionStatus::Connecting) => false,
-                (ConnectionStatus::Connected(a), ConnectionStatus::Connected(b)) => a != b,
-                _ => true,
-            };
-            
-            connection_changed ||
+            prev.connection_status != state.connection_status ||
             prev.total_topics != state.total_topics ||
+            prev.stale_topics != state.stale_topics ||
+            prev.muted_topics != state.muted_topics ||
+            prev.show_muted != state.show_muted ||
+            prev.alert_banner != state.alert_banner ||
+            prev.undo_hint != state.undo_hint ||
             prev.total_messages != state.total_messages ||
+            prev.queue_stats != state.queue_stats ||
             prev.last_update != state.last_update
         });
         
@@ -93,7 +135,7 @@ impl StatusBar {
         
         // Update connection status in the filter bar (row 1)
         if force_redraw || status_line_changed {
-            stdout.queue(cursor::MoveTo(14, 1))?; // Position after "│ Connection: "
+            stdout.queue(cursor::MoveTo(2, 1))?; // Position after "│ "
             Self::render_connection_status(&mut stdout, &state.connection_status)?;
             
             // Render stats line (Status: X topics | Y messages | Last: timestamp)
@@ -103,11 +145,46 @@ impl StatusBar {
             let stats_text = format!("{} topics | {} messages", 
                                    state.total_topics, state.total_messages);
             stdout.queue(Print(&stats_text))?;
+            if state.stale_topics > 0 {
+                stdout.queue(SetForegroundColor(Color::Red))?;
+                stdout.queue(Print(&format!(" | Stale: {}", state.stale_topics)))?;
+                stdout.queue(ResetColor)?;
+            }
+            if state.muted_topics > 0 {
+                let shown = if state.show_muted { "shown" } else { "hidden" };
+                stdout.queue(SetForegroundColor(Color::DarkGrey))?;
+                stdout.queue(Print(&format!(" | Muted: {} ({}, [v] toggle)", state.muted_topics, shown)))?;
+                stdout.queue(ResetColor)?;
+            }
+            
+            // 待寫入佇列與丟棄數量，有丟棄時以紅色顯示 (唯讀瀏覽時沒有即時資料)
+            let is_archive = matches!(state.connection_status, ConnectionStatus::Archive(_));
+            if !is_archive {
+                stdout.queue(Print(&format!(" | Queue: {}", state.queue_stats.queued)))?;
+            }
+            if !is_archive && state.queue_stats.dropped > 0 {
+                stdout.queue(SetForegroundColor(Color::Red))?;
+                stdout.queue(Print(&format!(" | Dropped: {}", state.queue_stats.dropped)))?;
+                stdout.queue(ResetColor)?;
+            }
             
             if let Some(last_update) = &state.last_update {
                 let time_str = last_update.format("%Y-%m-%d %H:%M:%S").to_string();
                 stdout.queue(Print(&format!(" | Last: {}", time_str)))?;
             }
+            
+            if let Some(hint) = &state.undo_hint {
+                stdout.queue(SetForegroundColor(Color::Yellow))?;
+                stdout.queue(Print(&format!(" | {} [z]undo", hint)))?;
+                stdout.queue(ResetColor)?;
+            }
+            
+            if let Some(banner) = &state.alert_banner {
+                stdout.queue(SetBackgroundColor(Color::Red))?;
+                stdout.queue(SetForegroundColor(Color::White))?;
+                stdout.queue(Print(&format!(" ALERT: {} ", banner)))?;
+                stdout.queue(ResetColor)?;
+            }
         }
         
         // Render help line if changed
@@ -122,54 +199,32 @@ impl StatusBar {
                 let (terminal_width, _) = crossterm::terminal::size()?;
                 
                 // 計算所有過濾器狀態文字的總長度
-                let mut filter_status_parts = Vec::new();
-                for (index, (name, color, enabled)) in state.quick_filter_states.iter().enumerate() {
-                    if index < 5 { // 只顯示F1-F5
-                        let status_symbol = if *enabled { "✓" } else { "✗" };
-                        let status_text = format!("[F{}:{} {}]", index + 1, name, status_symbol);
-                        filter_status_parts.push((status_text, color.clone(), *enabled));
-                    }
-                }
-                
-                let total_filter_len: usize = filter_status_parts.iter()
-                    .map(|(text, _, _)| text.len() + 1) // +1 for space
+                let total_filter_len: usize = state.quick_filter_states.iter()
+                    .map(|status| status.text().chars().count() + 1) // +1 for space
                     .sum();
                 
-                if total_filter_len > 0 {
-                    // 計算右對齊位置
-                    let help_len = state.help_text.len();
-                    let available_space = terminal_width.saturating_sub(help_len as u16 + total_filter_len as u16 + 3); // +3 for " | "
+                // 計算右對齊位置，放不下時不顯示
+                let help_len = state.help_text.chars().count();
+                let available_space = terminal_width.saturating_sub(help_len as u16 + total_filter_len as u16 + 3); // +3 for " | "
+                
+                if available_space > 0 {
+                    stdout.queue(Print(&format!("{:<width$}", "", width = available_space as usize)))?;
+                    stdout.queue(Print(" | "))?;
                     
-                    if available_space > 0 {
-                        stdout.queue(Print(&format!("{:<width$}", "", width = available_space as usize)))?;
-                        stdout.queue(Print(" | "))?;
+                    // 顯示每個過濾器狀態，使用對應顏色
+                    for (i, status) in state.quick_filter_states.iter().enumerate() {
+                        if i > 0 {
+                            stdout.queue(Print(" "))?;
+                        }
                         
-                        // 顯示每個過濾器狀態，使用對應顏色
-                        for (i, (status_text, color_name, is_enabled)) in filter_status_parts.iter().enumerate() {
-                            if i > 0 {
-                                stdout.queue(Print(" "))?;
-                            }
-                            
-                            // 根據顏色名稱設定顏色
-                            let color = match color_name.as_str() {
-                                "Green" => Color::Green,
-                                "Yellow" => Color::Yellow,
-                                "Red" => Color::Red,
-                                "Blue" => Color::Blue,
-                                "Cyan" => Color::Cyan,
-                                "Magenta" => Color::Magenta,
-                                _ => Color::White,
-                            };
-                            
-                            if *is_enabled {
-                                stdout.queue(SetForegroundColor(color))?;
-                            } else {
-                                stdout.queue(SetForegroundColor(Color::DarkGrey))?;
-                            }
-                            
-                            stdout.queue(Print(status_text))?;
-                            stdout.queue(ResetColor)?;
+                        if status.enabled {
+                            stdout.queue(SetForegroundColor(status.color.unwrap_or(Color::White)))?;
+                        } else {
+                            stdout.queue(SetForegroundColor(Color::DarkGrey))?;
                         }
+                        
+                        stdout.queue(Print(status.text()))?;
+                        stdout.queue(ResetColor)?;
                     }
                 }
             }
@@ -179,29 +234,41 @@ impl StatusBar {
         Ok(())
     }
     
-    fn render_connection_status<W: Write>(writer: &mut W, status: &ConnectionStatus) -> Result<()> {
-        match status {
-            ConnectionStatus::Disconnected => {
-                writer.queue(SetForegroundColor(Color::Red))?;
-                writer.queue(Print("●Disconnected"))?;
-                writer.queue(ResetColor)?;
-            }
-            ConnectionStatus::Connecting => {
-                writer.queue(SetForegroundColor(Color::Yellow))?;
-                writer.queue(Print("●Connecting..."))?;
-                writer.queue(ResetColor)?;
-            }
-            ConnectionStatus::Connected(addr) => {
-                writer.queue(SetForegroundColor(Color::Green))?;
-                writer.queue(Print(&format!("●Connected ({})", addr)))?;
-                writer.queue(ResetColor)?;
-            }
-            ConnectionStatus::Error(err) => {
-                writer.queue(SetForegroundColor(Color::Red))?;
-                writer.queue(Print(&format!("●Error: {}", err)))?;
-                writer.queue(ResetColor)?;
-            }
+    /// 重連等待時間，不到 1 秒時以毫秒顯示
+    pub fn format_delay(delay: std::time::Duration) -> String {
+        if delay < std::time::Duration::from_secs(1) {
+            format!("{}ms", delay.as_millis())
+        } else {
+            format!("{:.1}s", delay.as_secs_f32())
         }
+    }
+    
+    fn render_connection_status<W: Write>(writer: &mut W, status: &ConnectionStatus) -> Result<()> {
+        let (color, text) = match status {
+            ConnectionStatus::Disconnected => (Color::Red, "●Disconnected".to_string()),
+            ConnectionStatus::Connecting => (Color::Yellow, "●Connecting...".to_string()),
+            ConnectionStatus::Connected(addr) => (Color::Green, format!("●Connected ({})", addr)),
+            ConnectionStatus::Reconnecting { attempt, delay, error } => (
+                Color::Yellow,
+                format!("●Reconnecting #{} in {}: {}", attempt, Self::format_delay(*delay), error),
+            ),
+            ConnectionStatus::AuthFailed(err) => (Color::Red, format!("●Auth failed: {}", err)),
+            ConnectionStatus::Error(err) => (Color::Red, format!("●Error: {}", err)),
+            ConnectionStatus::Archive(path) => (Color::Cyan, format!("{} (read-only)", path)),
+        };
+        
+        // 唯讀瀏覽時沒有連線，欄位標籤改成 Archive
+        let label = match status {
+            ConnectionStatus::Archive(_) => "Archive:    ",
+            _ => "Connection: ",
+        };
+        writer.queue(Print(label))?;
+        
+        // 固定寬度輸出，避免較短的狀態殘留上一個狀態的文字
+        let text: String = text.chars().take(CONNECTION_FIELD_WIDTH).collect();
+        writer.queue(SetForegroundColor(color))?;
+        writer.queue(Print(&format!("{:<width$}", text, width = CONNECTION_FIELD_WIDTH)))?;
+        writer.queue(ResetColor)?;
         Ok(())
     }
     
@@ -210,14 +277,27 @@ impl StatusBar {
         
         state.help_text = match view {
             ViewType::TopicList => {
-                "[/]filter [Enter]select [↑↓]navigate [Home/End]first/last [←]back [F5]refresh [F1]help".to_string()
+                "[/]filter [Enter]select [↑↓]navigate [Home/End]first/last [s]sort [o]order [c]columns [p]pin [f]pinned only [u]mute [v]show muted [M]mute rules [!]stale only [a]alerts [b]bookmarks [t]trash [z]undo delete [←]back [F5]refresh [Ctrl+P]presets [Ctrl+O]open [F1]help".to_string()
             }
             ViewType::MessageList(_) => {
-                "[←]back [/]filter [Enter]view [↑↓]navigate [Home/End]first/last [F2]json [F1]help".to_string()
+                "[←]back [/]filter [Enter]view [↑↓]navigate [Home/End]first/last [F2]json [m]mark [d]diff [b]bookmark [n]note [g]chart [c]columns [Ctrl+P]presets [F1]help".to_string()
             }
             ViewType::PayloadDetail(_, _) => {
                 "[←]back [F2]json-depth [c]opy [↑↓]scroll [PgUp/PgDn]page [F1]help".to_string()
             }
         };
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_delay() {
+        assert_eq!(StatusBar::format_delay(Duration::from_millis(250)), "250ms");
+        assert_eq!(StatusBar::format_delay(Duration::from_millis(1500)), "1.5s");
+        assert_eq!(StatusBar::format_delay(Duration::from_secs(60)), "60.0s");
+    }
+}
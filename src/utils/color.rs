@@ -0,0 +1,41 @@
+use crossterm::style::Color;
+
+/// 設定檔中的顏色名稱 (不分大小寫，_ - 空白可省略) 或 #rrggbb 轉成終端機顏色
+pub fn parse_color(name: &str) -> Option<Color> {
+    let name = name.trim();
+    if let Some(hex) = name.strip_prefix('#') {
+        if hex.len() == 6 {
+            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+            return Some(Color::Rgb { r, g, b });
+        }
+        return None;
+    }
+
+    let normalized: String = name
+        .chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    match normalized.as_str() {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "darkred" => Some(Color::DarkRed),
+        "green" => Some(Color::Green),
+        "lightgreen" | "darkgreen" => Some(Color::DarkGreen),
+        "yellow" => Some(Color::Yellow),
+        "darkyellow" => Some(Color::DarkYellow),
+        "blue" => Some(Color::Blue),
+        "darkblue" => Some(Color::DarkBlue),
+        "cyan" => Some(Color::Cyan),
+        "darkcyan" => Some(Color::DarkCyan),
+        "magenta" => Some(Color::Magenta),
+        "darkmagenta" => Some(Color::DarkMagenta),
+        "white" => Some(Color::White),
+        "grey" | "gray" => Some(Color::Grey),
+        "darkgrey" | "darkgray" => Some(Color::DarkGrey),
+        _ => None,
+    }
+}
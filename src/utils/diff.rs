@@ -0,0 +1,180 @@
+use serde_json::Value;
+
+use crate::utils::json_path::child_path;
+
+// 超過此大小 (舊行數 × 新行數) 時不做 LCS，直接視為整段刪除再新增
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// JSON 結構差異中的一項，path 為 $.a.b[0] 形式
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonChange {
+    Added { path: String, value: Value },
+    Removed { path: String, value: Value },
+    Changed { path: String, old: Value, new: Value },
+}
+
+/// 文字逐行差異
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineChange {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// 兩個 payload 的差異：兩者都是 JSON 物件或陣列時比對結構，否則逐行比對
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayloadDiff {
+    Json(Vec<JsonChange>),
+    Text(Vec<LineChange>),
+}
+
+impl PayloadDiff {
+    pub fn compute(old: &str, new: &str) -> Self {
+        match (parse_document(old), parse_document(new)) {
+            (Some(old), Some(new)) => PayloadDiff::Json(json_diff(&old, &new)),
+            _ => PayloadDiff::Text(line_diff(old, new)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            PayloadDiff::Json(changes) => changes.is_empty(),
+            PayloadDiff::Text(lines) => lines.iter().all(|line| matches!(line, LineChange::Same(_))),
+        }
+    }
+}
+
+fn parse_document(text: &str) -> Option<Value> {
+    serde_json::from_str::<Value>(text)
+        .ok()
+        .filter(|value| value.is_object() || value.is_array())
+}
+
+pub fn json_diff(old: &Value, new: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    diff_value("$", old, new, &mut changes);
+    changes
+}
+
+fn diff_value(path: &str, old: &Value, new: &Value, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                let child = child_path(path, key);
+                match new_map.get(key) {
+                    Some(new_value) => diff_value(&child, old_value, new_value, changes),
+                    None => changes.push(JsonChange::Removed { path: child, value: old_value.clone() }),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    changes.push(JsonChange::Added { path: child_path(path, key), value: new_value.clone() });
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for index in 0..old_items.len().max(new_items.len()) {
+                let child = format!("{}[{}]", path, index);
+                match (old_items.get(index), new_items.get(index)) {
+                    (Some(old_item), Some(new_item)) => diff_value(&child, old_item, new_item, changes),
+                    (Some(old_item), None) => changes.push(JsonChange::Removed { path: child, value: old_item.clone() }),
+                    (None, Some(new_item)) => changes.push(JsonChange::Added { path: child, value: new_item.clone() }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => changes.push(JsonChange::Changed {
+            path: path.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// 以最長共同子序列計算逐行差異
+pub fn line_diff(old: &str, new: &str) -> Vec<LineChange> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // 先去掉相同的開頭與結尾，減少 LCS 的計算量
+    let prefix = old_lines.iter().zip(&new_lines).take_while(|(a, b)| a == b).count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old_lines[prefix..old_lines.len() - suffix];
+    let new_middle = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut changes: Vec<LineChange> = old_lines[..prefix].iter().map(|line| LineChange::Same(line.to_string())).collect();
+
+    if old_middle.len().saturating_mul(new_middle.len()) > MAX_LCS_CELLS {
+        changes.extend(old_middle.iter().map(|line| LineChange::Removed(line.to_string())));
+        changes.extend(new_middle.iter().map(|line| LineChange::Added(line.to_string())));
+    } else {
+        // lengths[i][j] = old_middle[i..] 與 new_middle[j..] 的 LCS 長度
+        let (n, m) = (old_middle.len(), new_middle.len());
+        let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lengths[i][j] = if old_middle[i] == new_middle[j] {
+                    lengths[i + 1][j + 1] + 1
+                } else {
+                    lengths[i + 1][j].max(lengths[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_middle[i] == new_middle[j] {
+                changes.push(LineChange::Same(old_middle[i].to_string()));
+                i += 1;
+                j += 1;
+            } else if j < m && (i == n || lengths[i][j + 1] >= lengths[i + 1][j]) {
+                changes.push(LineChange::Added(new_middle[j].to_string()));
+                j += 1;
+            } else {
+                changes.push(LineChange::Removed(old_middle[i].to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    changes.extend(old_lines[old_lines.len() - suffix..].iter().map(|line| LineChange::Same(line.to_string())));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_diff() {
+        let old = json!({"mode": "auto", "limits": {"max": 80}, "tags": ["a", "b"], "old key": 1});
+        let new = json!({"mode": "manual", "limits": {"max": 80, "min": 10}, "tags": ["a"]});
+        assert_eq!(json_diff(&old, &new), vec![
+            JsonChange::Added { path: "$.limits.min".to_string(), value: json!(10) },
+            JsonChange::Changed { path: "$.mode".to_string(), old: json!("auto"), new: json!("manual") },
+            JsonChange::Removed { path: "$[\"old key\"]".to_string(), value: json!(1) },
+            JsonChange::Removed { path: "$.tags[1]".to_string(), value: json!("b") },
+        ]);
+        assert!(PayloadDiff::compute(r#"{"a":1}"#, r#"{ "a": 1 }"#).is_empty());
+    }
+
+    #[test]
+    fn test_line_diff() {
+        let diff = line_diff("a\nb\nc\nd", "a\nc\nx\nd");
+        assert_eq!(diff, vec![
+            LineChange::Same("a".to_string()),
+            LineChange::Removed("b".to_string()),
+            LineChange::Same("c".to_string()),
+            LineChange::Added("x".to_string()),
+            LineChange::Same("d".to_string()),
+        ]);
+        assert!(matches!(PayloadDiff::compute("plain", "text"), PayloadDiff::Text(_)));
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// 簡化的 JSON path：$.a.b、$.items[0].level、$["key with space"]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let rest = text.strip_prefix('$').unwrap_or(text);
+        let chars: Vec<char> = rest.chars().collect();
+        let mut segments = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '.' => {
+                    let start = i + 1;
+                    let mut end = start;
+                    while end < chars.len() && chars[end] != '.' && chars[end] != '[' {
+                        end += 1;
+                    }
+                    if end == start {
+                        bail!("Empty key in JSON path '{}'", text);
+                    }
+                    segments.push(Segment::Key(chars[start..end].iter().collect()));
+                    i = end;
+                }
+                '[' => {
+                    let close = match chars[i..].iter().position(|&c| c == ']') {
+                        Some(offset) => i + offset,
+                        None => bail!("Unclosed '[' in JSON path '{}'", text),
+                    };
+                    let inner: String = chars[i + 1..close].iter().collect();
+                    let inner = inner.trim();
+                    let quoted = inner
+                        .strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+                        .or_else(|| inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')));
+                    if let Some(key) = quoted {
+                        segments.push(Segment::Key(key.to_string()));
+                    } else {
+                        match inner.parse::<usize>() {
+                            Ok(index) => segments.push(Segment::Index(index)),
+                            Err(_) => bail!("Invalid index '{}' in JSON path '{}'", inner, text),
+                        }
+                    }
+                    i = close + 1;
+                }
+                // 允許省略開頭的 "$."，例如 "level"
+                _ if i == 0 => {
+                    let mut end = 0;
+                    while end < chars.len() && chars[end] != '.' && chars[end] != '[' {
+                        end += 1;
+                    }
+                    segments.push(Segment::Key(chars[..end].iter().collect()));
+                    i = end;
+                }
+                c => bail!("Unexpected '{}' in JSON path '{}'", c, text),
+            }
+        }
+
+        Ok(Self { segments })
+    }
+
+    pub fn get<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments.iter().try_fold(value, |current, segment| match segment {
+            Segment::Key(key) => current.get(key.as_str()),
+            Segment::Index(index) => current.get(*index),
+        })
+    }
+
+    /// 轉成 SQLite json_extract 的路徑；key 含引號時無法表示，回傳 None
+    pub fn to_sqlite(&self) -> Option<String> {
+        let mut path = String::from("$");
+        for segment in &self.segments {
+            match segment {
+                Segment::Key(key) if key.contains('"') => return None,
+                Segment::Key(key) => path.push_str(&format!(".\"{}\"", key)),
+                Segment::Index(index) => path.push_str(&format!("[{}]", index)),
+            }
+        }
+        Some(path)
+    }
+
+    /// 解析 payload 後取值，非 JSON 或找不到時回傳 None
+    pub fn extract(&self, payload: &str) -> Option<Value> {
+        let value: Value = serde_json::from_str(payload).ok()?;
+        self.get(&value).cloned()
+    }
+}
+
+/// 加上一層 key 的路徑：一般的 key 用 .key，含特殊字元時用 ["key"]
+pub fn child_path(path: &str, key: &str) -> String {
+    let simple = !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    if simple {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}[{}]", path, Value::String(key.to_string()))
+    }
+}
+
+/// 數字或可解析成數字的字串 (例如 "21.5")，不接受 NaN/inf
+pub fn json_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok().filter(|n: &f64| n.is_finite()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_path_get() {
+        let value = json!({"log": {"level": "warn"}, "items": [{"v": 1}, {"v": 2}], "a b": true});
+        assert_eq!(JsonPath::parse("$.log.level").unwrap().get(&value), Some(&json!("warn")));
+        assert_eq!(JsonPath::parse("$.items[1].v").unwrap().get(&value), Some(&json!(2)));
+        assert_eq!(JsonPath::parse("$[\"a b\"]").unwrap().get(&value), Some(&json!(true)));
+        assert_eq!(JsonPath::parse("log.level").unwrap().get(&value), Some(&json!("warn")));
+        assert_eq!(JsonPath::parse("$.missing").unwrap().get(&value), None);
+        assert_eq!(JsonPath::parse("$.items[1].v").unwrap().to_sqlite().as_deref(), Some("$.\"items\"[1].\"v\""));
+        assert!(JsonPath::parse("$.items[x]").is_err());
+        assert!(JsonPath::parse("$..a").is_err());
+    }
+}
@@ -1 +1,5 @@
-pub mod json_formatter;
\ No newline at end of file
+pub mod color;
+pub mod diff;
+pub mod json_formatter;
+pub mod json_path;
+pub mod topic_pattern;
@@ -0,0 +1,32 @@
+/// MQTT topic 萬用字元比對：`+` 比對單一層級，`#` 比對其後所有層級 (含零層)
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let mut pattern_levels = pattern.split('/');
+    let mut topic_levels = topic.split('/');
+
+    loop {
+        match (pattern_levels.next(), topic_levels.next()) {
+            (Some("#"), _) => return true,
+            (Some("+"), Some(_)) => {}
+            (Some(p), Some(t)) if p == t => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_matches() {
+        assert!(topic_matches("sensors/+/temp", "sensors/a/temp"));
+        assert!(!topic_matches("sensors/+/temp", "sensors/a/b/temp"));
+        assert!(topic_matches("sensors/#", "sensors"));
+        assert!(topic_matches("sensors/#", "sensors/a/b"));
+        assert!(topic_matches("#", "anything/at/all"));
+        assert!(topic_matches("a/b", "a/b"));
+        assert!(!topic_matches("a/b", "a/b/c"));
+        assert!(!topic_matches("a/b/c", "a/b"));
+    }
+}